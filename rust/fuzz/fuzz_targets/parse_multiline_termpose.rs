@@ -0,0 +1,60 @@
+//! Reads arbitrary text with the termpose parser, whose raw pointers are what most needs the sanitizers' attention. Whatever it reads has to print back to the same woods, and its other readers have to agree with it.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use wood::*;
+
+fuzz_target!(|text: &str| {
+    let recovered = parse_multiline_termpose_recovering(text);
+    match parse_multiline_termpose(text) {
+        Ok(w) => {
+            let printed = pretty_termpose(&w);
+            assert_eq!(w, parse_termpose(&printed).unwrap(), "{:?}", printed);
+            assert_eq!(
+                w,
+                parse_multiline_termpose_borrowed(text)
+                    .unwrap()
+                    .into_owned()
+            );
+            let streamed: Vec<Wood> = stream_termpose(text.as_bytes())
+                .collect::<Result<_, _>>()
+                .unwrap();
+            assert_eq!(w, Wood::branch(streamed));
+            assert!(recovered.errors.is_empty());
+            assert_eq!(w, recovered.wood);
+            let file = termpose::cst::parse(text).unwrap();
+            assert_eq!(text, file.to_string());
+            assert_eq!(w, file.lower().unwrap());
+        }
+        Err(e) => {
+            assert_eq!(
+                (e.line, e.column, &e.msg),
+                (
+                    recovered.errors[0].line,
+                    recovered.errors[0].column,
+                    &recovered.errors[0].msg
+                )
+            );
+            assert_eq!(e.msg, termpose::cst::parse(text).unwrap_err().msg);
+        }
+    }
+
+    let style = TermposeStyle {
+        comments: CommentStyle {
+            line: Some("//".into()),
+            block: Some(("/*".into(), "*/".into())),
+            attach: true,
+        },
+        ..DEFAULT_STYLE.clone()
+    };
+    let _ = parse_multiline_termpose_style_recovering(text, style.clone());
+    if let Ok(w) = parse_multiline_termpose_style(text, style.clone()) {
+        let printed = pretty_termpose_detail(&w, true, 1, 40, &style);
+        assert_eq!(
+            w,
+            parse_termpose_style(&printed, style).unwrap(),
+            "{:?}",
+            printed
+        );
+    }
+});
@@ -0,0 +1,42 @@
+//! Reads arbitrary text with the woodslist parser. Whatever it reads has to print back to the same woods, and its other readers have to agree with it.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use wood::*;
+
+fuzz_target!(|text: &str| {
+    if let Ok(w) = parse_multiline_woodslist(text) {
+        let printed = to_woodslist(&w);
+        assert_eq!(w, parse_woodslist(&printed).unwrap(), "{:?}", printed);
+        let printed = indented_woodslist(&w);
+        assert_eq!(w, parse_woodslist(&printed).unwrap(), "{:?}", printed);
+        assert_eq!(
+            w,
+            parse_multiline_woodslist_borrowed(text)
+                .unwrap()
+                .into_owned()
+        );
+        let streamed: Vec<Wood> = stream_woodslist(text.as_bytes())
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(w, Wood::branch(streamed));
+    }
+
+    let style = WoodslistStyle {
+        comments: CommentStyle {
+            line: Some(";".into()),
+            block: Some(("#|".into(), "|#".into())),
+            attach: true,
+        },
+        ..DEFAULT_WOODSLIST_STYLE.clone()
+    };
+    if let Ok(w) = parse_multiline_woodslist_style(text, style.clone()) {
+        let printed = indented_woodslist_style(&w, false, 2, 40, &style);
+        assert_eq!(
+            w,
+            parse_woodslist_style(&printed, style).unwrap(),
+            "{:?}",
+            printed
+        );
+    }
+});
@@ -0,0 +1,118 @@
+//! Prints arbitrary woods in every layout the printers and serializers have, and reads them back. Each has to come back as the wood that was printed.
+#![no_main]
+
+use libfuzzer_sys::{
+    arbitrary::{self, Arbitrary, Unstructured},
+    fuzz_target,
+};
+use wood::*;
+
+#[derive(Debug)]
+struct Input {
+    wood: Wood,
+    indent_is_tab: bool,
+    tab_size: usize,
+    column_limit: usize,
+}
+
+/// branches are limited in depth and width, so that the fuzzer spends its time on what's in the leaves
+fn arbitrary_wood(u: &mut Unstructured, depth: usize) -> arbitrary::Result<Wood> {
+    if depth == 0 || u.arbitrary()? {
+        Ok(Wood::leaf(u.arbitrary()?))
+    } else {
+        let len = u.int_in_range(0..=6)?;
+        (0..len)
+            .map(|_| arbitrary_wood(u, depth - 1))
+            .collect::<arbitrary::Result<_>>()
+            .map(Wood::branch)
+    }
+}
+
+impl<'a> Arbitrary<'a> for Input {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Input {
+            wood: arbitrary_wood(u, 8)?,
+            indent_is_tab: u.arbitrary()?,
+            tab_size: u.int_in_range(1..=8)?,
+            column_limit: u.int_in_range(0..=100)?,
+        })
+    }
+}
+
+fn reads_back(w: &Wood, printed: &str, reread: Result<Wood, Box<WoodError>>) {
+    match reread {
+        Ok(r) => assert_eq!(w, &r, "{:?}", printed),
+        Err(e) => panic!("{:?} couldn't be read back: {}", printed, e),
+    }
+}
+
+fuzz_target!(|input: Input| {
+    let Input {
+        wood: w,
+        indent_is_tab,
+        tab_size,
+        column_limit,
+    } = input;
+
+    let printed = to_woodslist(&w);
+    reads_back(&w, &printed, parse_woodslist(&printed));
+    let printed = indented_woodslist_detail(&w, indent_is_tab, tab_size, column_limit);
+    reads_back(&w, &printed, parse_woodslist(&printed));
+    let printed = pretty_termpose_detail(&w, indent_is_tab, tab_size, column_limit, &DEFAULT_STYLE);
+    reads_back(&w, &printed, parse_termpose(&printed));
+
+    //leaves that would begin comments have to be quoted when the style has comment markers
+    let style = TermposeStyle {
+        comments: CommentStyle {
+            line: Some("//".into()),
+            block: Some(("/*".into(), "*/".into())),
+            attach: false,
+        },
+        ..DEFAULT_STYLE.clone()
+    };
+    let printed = pretty_termpose_detail(&w, indent_is_tab, tab_size, column_limit, &style);
+    reads_back(&w, &printed, parse_termpose_style(&printed, style));
+    let style = WoodslistStyle {
+        comments: CommentStyle {
+            line: Some(";".into()),
+            block: Some(("#|".into(), "|#".into())),
+            attach: false,
+        },
+        ..DEFAULT_WOODSLIST_STYLE.clone()
+    };
+    let printed = indented_woodslist_style(&w, indent_is_tab, tab_size, column_limit, &style);
+    reads_back(&w, &printed, parse_woodslist_style(&printed, style));
+
+    //the serializers write a root item at a time, so the contents of w are written as the items of a stream
+    let items = w.contents().cloned().collect::<Vec<Wood>>();
+    let mut serializer = WoodSerializer::termpose_detail(
+        Vec::new(),
+        indent_is_tab,
+        tab_size,
+        column_limit,
+        DEFAULT_STYLE.clone(),
+    );
+    items.iter().try_for_each(|i| serializer.emit(i)).unwrap();
+    let printed = serializer.into_inner();
+    let reread = stream_termpose(&printed[..]).collect::<Result<Vec<Wood>, _>>();
+    reads_back(
+        &Wood::branch(items.clone()),
+        &String::from_utf8_lossy(&printed),
+        reread.map(Wood::branch),
+    );
+    let mut serializer = WoodSerializer::woodslist_detail(
+        Vec::new(),
+        indent_is_tab,
+        tab_size,
+        column_limit,
+        DEFAULT_WOODSLIST_STYLE.clone(),
+    );
+    items.iter().try_for_each(|i| serializer.emit(i)).unwrap();
+    let printed = serializer.into_inner();
+    let reread = stream_woodslist(&printed[..]).collect::<Result<Vec<Wood>, _>>();
+    reads_back(
+        &Wood::branch(items),
+        &String::from_utf8_lossy(&printed),
+        reread.map(Wood::branch),
+    );
+});
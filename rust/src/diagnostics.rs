@@ -0,0 +1,170 @@
+use super::*;
+
+const RED: &str = "\x1b[1;31m";
+const BLUE: &str = "\x1b[1;34m";
+const BOLD: &str = "\x1b[1m";
+const RESET: &str = "\x1b[0m";
+
+struct Palette {
+    color: bool,
+}
+impl Palette {
+    fn paint(&self, code: &str, s: &str) -> String {
+        if self.color {
+            format!("{}{}{}", code, s, RESET)
+        } else {
+            s.to_string()
+        }
+    }
+}
+
+/// tabs are expanded so that carets line up with the text above them
+fn expand_tabs(line: &str) -> String {
+    line.replace('\t', "    ")
+}
+
+fn display_width(s: &str) -> usize {
+    s.chars().map(|c| if c == '\t' { 4 } else { 1 }).sum()
+}
+
+/// the text of the `line`th (1-based) line, without its line ending, and its byte offset
+fn nth_line(source: &str, line: usize) -> Option<(&str, usize)> {
+    let starts = line_starts(source);
+    let start = *starts.get(line.checked_sub(1)?)?;
+    let rest = &source[start..];
+    let len = rest.find(['\n', '\r']).unwrap_or(rest.len());
+    Some((&rest[..len], start))
+}
+
+impl WoodError {
+    /// A rustc-style report of the error, showing the line of `source` that it occurred on, with a caret under the offending text, followed by the chain of causes.
+    /// ```text
+    /// error: b: expected a leaf
+    ///  --> conf.term:3:5
+    ///   |
+    /// 3 |   b:perhaps
+    ///   |     ^^^^^^^
+    /// ```
+    /// `source` should be the text that the errant Wood was parsed from. Errors with no position are rendered without a snippet. The error's path, if it has one, goes before its message.
+    pub fn render(&self, source: &str, file_name: Option<&str>) -> String {
+        self.render_detail(source, file_name, false)
+    }
+
+    /// `render`, optionally highlighting the report with ANSI color codes for display in a terminal
+    pub fn render_detail(&self, source: &str, file_name: Option<&str>, color: bool) -> String {
+        let p = Palette { color };
+        let headline = if self.path.is_empty() {
+            format!(": {}", self.msg)
+        } else {
+            format!(": {}: {}", self.path_string(), self.msg)
+        };
+        let mut out = format!("{}{}\n", p.paint(RED, "error"), p.paint(BOLD, &headline));
+        if self.line >= 0 {
+            let line = self.line as usize;
+            let column = self.column.max(1) as usize;
+            let gutter = line.to_string();
+            let pad = " ".repeat(gutter.len());
+            out.push_str(&format!(
+                "{}{} {}:{}:{}\n",
+                pad,
+                p.paint(BLUE, "-->"),
+                file_name.unwrap_or("<input>"),
+                line,
+                column
+            ));
+            if let Some((text, line_start)) = nth_line(source, line) {
+                let bar = p.paint(BLUE, "|");
+                let before: String = text.chars().take(column - 1).collect();
+                // the caret covers the span when the span is confined to this line
+                let caret_len = self
+                    .span
+                    .and_then(|sp| {
+                        let in_line = sp.start.checked_sub(line_start)?;
+                        let covered = text.get(in_line..sp.end.checked_sub(line_start)?)?;
+                        Some(display_width(covered))
+                    })
+                    .unwrap_or(1)
+                    .max(1);
+                out.push_str(&format!("{} {}\n", pad, bar));
+                out.push_str(&format!(
+                    "{} {} {}\n",
+                    p.paint(BLUE, &gutter),
+                    bar,
+                    expand_tabs(text)
+                ));
+                out.push_str(&format!(
+                    "{} {} {}{}\n",
+                    pad,
+                    bar,
+                    " ".repeat(display_width(&before)),
+                    p.paint(RED, &"^".repeat(caret_len))
+                ));
+            }
+        }
+        let mut cause = self.source();
+        while let Some(c) = cause {
+            out.push_str(&format!("  {} caused by: {}\n", p.paint(BLUE, "="), c));
+            cause = c.source();
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wooder::*;
+
+    #[test]
+    fn renders_parse_errors() {
+        let source = "a b\n  c d)\ne";
+        let e = parse_multiline_termpose(source).unwrap_err();
+        assert_eq!(
+            e.render(source, Some("conf.term")),
+            "error: unmatched paren\n --> conf.term:2:6\n  |\n2 |   c d)\n  |      ^\n"
+        );
+
+        let source = "(a\n\t(b c\n";
+        let e = parse_woodslist(source).unwrap_err();
+        let r = e.render(source, None);
+        assert_eq!(
+            r,
+            "error: unmatched opening paren\n --> <input>:2:2\n  |\n2 |     (b c\n  |     ^\n"
+        );
+    }
+
+    #[test]
+    fn renders_dewoodify_errors_under_the_errant_node() {
+        let source = "config\n  name:\"big\"\n  port\tnothing";
+        let w = parse_multiline_termpose(source).unwrap();
+        let config = w.head().unwrap();
+        let mut scan = FieldScanning::new(config);
+        let port = scan.find("port").unwrap();
+        let e = usize::dewoodify(port).unwrap_err();
+        let r = e.render(source, Some("conf.term"));
+        assert!(r.contains(" --> conf.term:3:8\n"), "{}", r);
+        assert!(r.contains("3 |   port    nothing\n  |           ^^^^^^^\n"), "{}", r);
+        assert!(r.ends_with("  = caused by: invalid digit found in string\n"), "{}", r);
+
+        // spans over several lines just get a single caret
+        let e = scan.find("host").unwrap_err();
+        assert!(e.render(source, None).ends_with("1 | config\n  | ^\n"));
+        assert!(e
+            .render(source, None)
+            .starts_with("error: host: could not find key \"host\"\n"));
+    }
+
+    #[test]
+    fn renders_positionless_errors_and_colors() {
+        let e = WoodError::new(&Wood::leaf("a".into()), "nope".into());
+        assert_eq!(e.render("", None), "error: nope\n");
+        assert_eq!(e.to_string(), "nope");
+
+        let source = "a)";
+        let e = parse_termpose(source).unwrap_err();
+        assert_eq!(e.to_string(), "1:2: unmatched paren");
+        let r = e.render_detail(source, None, true);
+        assert!(r.starts_with("\x1b[1;31merror\x1b[0m"));
+        assert!(r.contains("\x1b[1;31m^\x1b[0m"));
+    }
+}
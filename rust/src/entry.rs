@@ -0,0 +1,202 @@
+use super::*;
+
+/// A place in a wood's contents where a `(key value)` term is or could be, from `Wood::entry`. As with `seek`, an entry's wood is the whole term, or its initial list, so the value is its `second`.
+pub enum Entry<'a, S = String> {
+    Occupied(OccupiedEntry<'a, S>),
+    Vacant(VacantEntry<'a, S>),
+}
+
+pub struct OccupiedEntry<'a, S = String> {
+    contents: &'a mut Vec<Wood<S>>,
+    index: usize,
+}
+
+pub struct VacantEntry<'a, S = String> {
+    contents: &'a mut Vec<Wood<S>>,
+    key: String,
+}
+
+fn unlocated_leaf<S>(v: S) -> Wood<S> {
+    Leafv(Leaf {
+        line: -1,
+        column: -1,
+        span: None,
+        comments: Vec::new(),
+        v,
+    })
+}
+
+impl<'a, S: AsRef<str> + From<String>> Entry<'a, S> {
+    pub fn key(&self) -> &str {
+        match *self {
+            Entry::Occupied(ref e) => e.key(),
+            Entry::Vacant(ref e) => e.key(),
+        }
+    }
+    /// the term, after adding `(key value)` if there wasn't one
+    pub fn or_insert(self, value: Wood<S>) -> &'a mut Wood<S> {
+        self.or_insert_with(|| value)
+    }
+    /// the term, after adding `(key value())` if there wasn't one
+    pub fn or_insert_with(self, value: impl FnOnce() -> Wood<S>) -> &'a mut Wood<S> {
+        match self {
+            Entry::Occupied(e) => e.into_mut(),
+            Entry::Vacant(e) => e.insert(value()),
+        }
+    }
+    /// runs `f` on the term, if there is one
+    pub fn and_modify(mut self, f: impl FnOnce(&mut Wood<S>)) -> Self {
+        if let Entry::Occupied(ref mut e) = self {
+            f(e.get_mut());
+        }
+        self
+    }
+}
+
+impl<'a, S: AsRef<str>> OccupiedEntry<'a, S> {
+    pub fn key(&self) -> &str {
+        self.get().initial_str()
+    }
+    pub fn get(&self) -> &Wood<S> {
+        self.contents[self.index].initial_list()
+    }
+    pub fn get_mut(&mut self) -> &mut Wood<S> {
+        self.contents[self.index].initial_list_mut()
+    }
+    pub fn into_mut(self) -> &'a mut Wood<S> {
+        self.contents[self.index].initial_list_mut()
+    }
+    /// Sets the term's value, its second wood, to `value`, returning the value it replaced, if it had one. If the term was just the key, `value` is added after it.
+    pub fn insert(&mut self, value: Wood<S>) -> Option<Wood<S>> {
+        let term = self.get_mut().contents_mut();
+        match term.get_mut(1) {
+            Some(old) => Some(std::mem::replace(old, value)),
+            None => {
+                term.push(value);
+                None
+            }
+        }
+    }
+    /// removes the whole of the wood that was found, as `Wood::remove` does
+    pub fn remove(self) -> Wood<S> {
+        self.contents.remove(self.index)
+    }
+}
+
+impl<'a, S: From<String>> VacantEntry<'a, S> {
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+    /// adds `(key value)` to the end of the contents, and returns it
+    pub fn insert(self, value: Wood<S>) -> &'a mut Wood<S> {
+        let key = unlocated_leaf(S::from(self.key));
+        self.contents.push(Branchv(Branch {
+            line: -1,
+            column: -1,
+            span: None,
+            comments: Vec::new(),
+            v: vec![key, value],
+        }));
+        self.contents.last_mut().unwrap()
+    }
+}
+
+impl<S: AsRef<str> + From<String>> Wood<S> {
+    /// The `(key value)` term with initial_str == key, for finding, adding or changing it in place. If this is a leaf, it's first made into a branch containing the leaf, as `contents_mut` does.
+    /// ```
+    /// let mut config = wood::parse_multiline_termpose("host example.com\nport 80").unwrap();
+    /// config.entry("port").or_insert("8080".into());
+    /// config.entry("user").or_insert("admin".into());
+    /// assert_eq!("((host example.com) (port 80) (user admin))", wood::to_woodslist(&config));
+    /// ```
+    pub fn entry(&mut self, key: &str) -> Entry<'_, S> {
+        let contents = self.contents_mut();
+        match contents.iter().position(|el| el.initial_str() == key) {
+            Some(index) => Entry::Occupied(OccupiedEntry { contents, index }),
+            None => Entry::Vacant(VacantEntry {
+                contents,
+                key: key.to_string(),
+            }),
+        }
+    }
+    /// Sets the value of the `(key value)` term with initial_str == key, adding the term if there isn't one. Returns the value that was replaced, if there was one.
+    pub fn set_val(&mut self, key: &str, value: Wood<S>) -> Option<Wood<S>> {
+        match self.entry(key) {
+            Entry::Occupied(mut e) => e.insert(value),
+            Entry::Vacant(e) => {
+                e.insert(value);
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn editing() {
+        let mut w = parse_multiline_termpose(
+            "
+server
+  host example.com
+  ((port 80) deprecated)
+  debug
+",
+        )
+        .unwrap();
+        let server = w.find_mut("server").unwrap();
+        //the initial list's value is set, and what's attached to it is left alone
+        assert_eq!(
+            Some(Wood::from("80")),
+            server.set_val("port", "8080".into())
+        );
+        assert_eq!(None, server.set_val("debug", "true".into()));
+        assert_eq!(None, server.set_val("user", "admin".into()));
+        *server.find_val_mut("host").unwrap() = "example.org".into();
+        assert_eq!(
+            "(server (host example.org) ((port 8080) deprecated) (debug true) (user admin))",
+            to_woodslist(server)
+        );
+
+        let server = w.seek_mut("server").unwrap();
+        assert_eq!("debug", server.remove("debug").unwrap().initial_str());
+        assert!(server.remove("debug").is_none());
+        server.retain(|el| el.initial_str() != "user");
+        server.insert_at(1, woods!("name", "main"));
+        match server.entry("port") {
+            Entry::Occupied(e) => assert_eq!("((port 8080) deprecated)", to_woodslist(&e.remove())),
+            Entry::Vacant(_) => panic!("port should be there"),
+        }
+        assert_eq!(
+            "(server (name main) (host example.org))",
+            to_woodslist(server)
+        );
+
+        let mut taken = w.take();
+        assert_eq!(Wood::default(), w);
+        assert_eq!(
+            "could not find child with key \"port\"",
+            taken
+                .find_mut("server")
+                .unwrap()
+                .find_val_mut("port")
+                .unwrap_err()
+                .msg
+        );
+    }
+
+    #[test]
+    fn leaves_become_branches() {
+        let mut w = Wood::from("a");
+        w.push("b".into());
+        assert_eq!(woods!("a", "b"), w);
+        let mut w = Wood::from("a");
+        assert_eq!(None, w.set_val("a", "1".into()));
+        assert_eq!("((a 1))", to_woodslist(&w));
+        let mut w = Wood::from("a");
+        assert!(w.remove("a").is_none());
+        assert_eq!(Wood::from("a"), w);
+    }
+}
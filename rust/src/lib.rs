@@ -2,14 +2,22 @@
 #![feature(extract_if)]
 
 use std::{
+    borrow::Cow,
     cmp::PartialEq,
     error::Error,
     fmt::{Debug, Display, Formatter},
     mem::forget,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6},
+    num::{
+        NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroIsize, NonZeroU128,
+        NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU8, NonZeroUsize,
+    },
+    path::{Path, PathBuf},
     ptr::null_mut,
     result::Result,
     slice,
     str::FromStr,
+    time::Duration,
 };
 
 // pub trait Wood where Self:Sized {
@@ -24,46 +32,113 @@ use std::{
 // 	}
 // }
 
-/// Line numbers aren't checked in equality comparisons
-impl PartialEq for Wood {
+/// Line numbers aren't checked in equality comparisons. Comparison doesn't recurse, so trees of any depth can be compared.
+impl<S: PartialEq> PartialEq for Wood<S> {
     fn eq(&self, other: &Self) -> bool {
-        match *self {
-            Leafv(ref sa) => match *other {
-                Leafv(ref so) => sa.v == so.v,
-                _ => false,
-            },
-            Branchv(ref la) => match *other {
-                Branchv(ref lo) => la.v == lo.v,
-                _ => false,
-            },
+        let mut pending = vec![(self, other)];
+        while let Some((a, o)) = pending.pop() {
+            match (a, o) {
+                (Leafv(sa), Leafv(so)) => {
+                    if sa.v != so.v {
+                        return false;
+                    }
+                }
+                (Branchv(la), Branchv(lo)) => {
+                    if la.v.len() != lo.v.len() {
+                        return false;
+                    }
+                    pending.extend(la.v.iter().zip(lo.v.iter()).rev());
+                }
+                _ => return false,
+            }
         }
+        true
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct Branch {
+/// `line` and `column` are 1-based, columns are counted in chars. Woods that weren't parsed from text have a line and column of -1 and no span.
+/// `comments` are the comments that were written just before the wood, if the parser was asked to attach them (see `CommentStyle`). Printers put them back if their style has comment markers.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Branch<S = String> {
     pub line: isize,
     pub column: isize,
-    pub v: Vec<Wood>,
+    pub span: Option<Span>,
+    pub comments: Vec<String>,
+    pub v: Vec<Wood<S>>,
+}
+/// Cloning doesn't recurse, so trees of any depth can be cloned
+impl<S: Clone> Clone for Branch<S> {
+    fn clone(&self) -> Self {
+        let shell = |b: &Branch<S>| Branch {
+            line: b.line,
+            column: b.column,
+            span: b.span,
+            comments: b.comments.clone(),
+            v: Vec::with_capacity(b.v.len()),
+        };
+        let mut open = vec![(self.v.iter(), shell(self))];
+        loop {
+            let (originals, copy) = open.last_mut().unwrap();
+            match originals.next() {
+                Some(Leafv(l)) => copy.v.push(Leafv(l.clone())),
+                Some(Branchv(b)) => open.push((b.v.iter(), shell(b))),
+                None => {
+                    let (_, copy) = open.pop().unwrap();
+                    match open.last_mut() {
+                        Some((_, parent)) => parent.v.push(Branchv(copy)),
+                        None => return copy,
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Dropping a branch doesn't recurse, so that trees of any depth can be dropped
+impl<S> Drop for Branch<S> {
+    fn drop(&mut self) {
+        if !self
+            .v
+            .iter()
+            .any(|w| w.get_branch().is_some_and(|v| !v.is_empty()))
+        {
+            return;
+        }
+        //every branch beneath this one is emptied before it's dropped, so no drop ever has more than leaves to drop
+        let mut beneath: Vec<Wood<S>> = std::mem::take(&mut self.v);
+        while let Some(mut w) = beneath.pop() {
+            if let Branchv(ref mut b) = w {
+                beneath.append(&mut b.v);
+            }
+        }
+    }
 }
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct Leaf {
+pub struct Leaf<S = String> {
     pub line: isize,
     pub column: isize,
-    pub v: String,
+    pub span: Option<Span>,
+    pub comments: Vec<String>,
+    pub v: S,
 }
+/// `S` is the type of the leaves' contents. You will nearly always be using the default, `String`. See `WoodRef` for the other one.
 #[derive(Debug, Clone, Eq)]
-pub enum Wood {
-    Branchv(Branch),
-    Leafv(Leaf),
+pub enum Wood<S = String> {
+    Branchv(Branch<S>),
+    Leafv(Leaf<S>),
 }
 pub use Wood::*;
 
+/// A Wood that borrows its leaves from the text it was parsed from wherever it can (which is wherever a leaf contains no escapes). Produced by `parse_termpose_borrowed` and `parse_woodslist_borrowed`, which are faster than the owning parsers because they don't need to allocate for most leaves.
+pub type WoodRef<'a> = Wood<Cow<'a, str>>;
+
 impl From<String> for Wood {
     fn from(v: String) -> Wood {
         Leafv(Leaf {
             line: -1,
             column: -1,
+            span: None,
+            comments: Vec::new(),
             v,
         })
     }
@@ -78,6 +153,8 @@ impl From<Vec<Wood>> for Wood {
         Branchv(Branch {
             line: -1,
             column: -1,
+            span: None,
+            comments: Vec::new(),
             v,
         })
     }
@@ -98,32 +175,39 @@ fn tail<I: Iterator>(mut v: I) -> I {
 // 	}
 // }
 
-fn push_escaped(take: &mut String, give: &str) {
+/// escapes everything that the parsers would otherwise read as something else, and control characters, which the parsers would translate or which would be unreadable
+fn write_escaped<W: std::fmt::Write>(take: &mut W, give: &str) -> std::fmt::Result {
     for c in give.chars() {
         match c {
-            '\n' => {
-                take.push('\\');
-                take.push('n');
-            }
-            '\t' => {
-                take.push('\\');
-                take.push('t');
-            }
-            '"' => {
-                take.push('\\');
-                take.push('"');
-            }
-            _ => {
-                take.push(c);
-            }
+            '\n' => take.write_str("\\n")?,
+            '\r' => take.write_str("\\r")?,
+            '\t' => take.write_str("\\t")?,
+            '"' => take.write_str("\\\"")?,
+            '\\' => take.write_str("\\\\")?,
+            c if c.is_control() => write!(take, "\\u{{{:x}}}", c as u32)?,
+            _ => take.write_char(c)?,
         }
     }
+    Ok(())
+}
+
+/// an empty branch, with no position
+impl<S> Default for Wood<S> {
+    fn default() -> Self {
+        Branchv(Branch {
+            line: -1,
+            column: -1,
+            span: None,
+            comments: Vec::new(),
+            v: Vec::new(),
+        })
+    }
 }
 
 ///A more succinct enum for discriminating leaves and branches, accessible via `Wood::what`
-pub enum LB<'a> {
+pub enum LB<'a, S = String> {
     L(&'a str),
-    B(&'a [Wood]),
+    B(&'a [Wood<S>]),
 }
 pub use LB::*;
 
@@ -132,6 +216,8 @@ impl Wood {
         Wood::Leafv(Leaf {
             line: -1,
             column: -1,
+            span: None,
+            comments: Vec::new(),
             v: v,
         })
     }
@@ -139,12 +225,67 @@ impl Wood {
         Wood::Branchv(Branch {
             line: -1,
             column: -1,
+            span: None,
+            comments: Vec::new(),
             v: v,
         })
     }
     pub fn empty() -> Wood {
         Wood::branch(Vec::new())
     }
+    pub fn to_string(&self) -> String {
+        to_woodslist(self)
+    }
+
+    pub fn strip_comments_escape(&mut self, comment_str: &str, comment_escape_str: &str) {
+        struct Stripper<'a> {
+            comment_str: &'a str,
+            comment_escape_str: &'a str,
+        }
+        impl WoodVisitorMut for Stripper<'_> {
+            fn enter_branch(&mut self, b: &mut Branch) -> bool {
+                b.v.retain(|i| i.initial_str() != self.comment_str);
+                true
+            }
+            fn visit_leaf(&mut self, l: &mut Leaf) {
+                if l.v == self.comment_escape_str {
+                    l.v = self.comment_str.into();
+                }
+            }
+        }
+        self.visit_mut(&mut Stripper {
+            comment_str,
+            comment_escape_str,
+        });
+    }
+
+    /// Strips any branches with first element of comment_str. If you need to produce a leaf that is equivalent to comment_str.
+    /// If you need the wood to contain a leaf that is the comment_str, you can escape it with a backslash.
+    /// This is actually a highly flawed way of providing commenting, because this will also strip out any serialization of a list of strings where the first element happens to equal the `comment_str`. That's a really subtle error, that violates a lot of expectations.
+    /// You could get around it by escaping your wood so that any strs that resemble comment tags wont read that way, but it's a bit awkward and sometimes wont really work.
+    /// Real comments, which the parsers skip over instead of reading as woods, can be configured with `CommentStyle`.
+    pub fn strip_comments(&mut self, comment_str: &str) {
+        let escstr = format!("\\{}", comment_str);
+        self.strip_comments_escape(comment_str, &escstr);
+    }
+}
+
+impl<'a> WoodRef<'a> {
+    /// Copies any borrowed leaves, producing an ordinary `Wood`
+    pub fn into_owned(self) -> Wood {
+        self.map_leaves(&mut |l| {
+            Leafv(Leaf {
+                line: l.line,
+                column: l.column,
+                span: l.span,
+                comments: l.comments,
+                v: l.v.into_owned(),
+            })
+        })
+    }
+}
+
+impl<S> Wood<S> {
     pub fn is_leaf(&self) -> bool {
         match self {
             &Leafv(_) => true,
@@ -157,19 +298,7 @@ impl Wood {
             _ => false,
         }
     }
-    pub fn what(&self) -> LB {
-        match *self {
-            Leafv(ref s) => L(&s.v),
-            Branchv(ref s) => B(&s.v),
-        }
-    }
-    pub fn get_leaf(&self) -> Option<&str> {
-        match *self {
-            Leafv(ref s) => Some(&s.v),
-            Branchv(_) => None,
-        }
-    }
-    pub fn get_branch(&self) -> Option<&[Wood]> {
+    pub fn get_branch(&self) -> Option<&[Wood<S>]> {
         match *self {
             Leafv(_) => None,
             Branchv(ref s) => Some(&s.v),
@@ -193,61 +322,26 @@ impl Wood {
             Branchv(ref s) => s.column,
         }
     }
-    /// Seeks the earliest string in the tree by looking at the first element of each branch recursively until it hits a leaf. (If it runs into an empty list, returns the empty string.
-    /// I recommend this whenever you want to use the first string as a discriminator, or whenever you want to get leaf str contents in general.
-    /// This abstracts over similar structures in a way that I consider generally desirable. I would go as far as to say that the more obvious, less abstract way of getting initial string should be Considered Harmful.
-    /// A few motivating examples:
-    /// If you wanted to add a feature to a programming language that allows you to add a special tag to an invocation, you want to put the tag inside the invocation's ast node but you don't want it to be confused for a parameter, this pattern enables:
-    /// ((f tag(special_invoke_inline)) a b)
-    /// If the syntax of a sexp language had more structure to it than usual:
-    /// ((if condition) then...) would still get easily picked up as an 'if' node.
-    /// Annotations are a good example, more generally, if you're refactoring and you decide you want to add an extra field to what was previously a leaf, this pattern enables you to make that change, confident that your code will still read its string content in the same way
-    /// (list key:value "some prose") -> (list key:value ("some prose" modifier:italicise))
-    pub fn initial_str(&self) -> &str {
+    /// the range of the source text that this wood was parsed from, if it was parsed
+    pub fn span(&self) -> Option<Span> {
         match *self {
-            Branchv(ref v) => {
-                if let Some(ref ss) = v.v.first() {
-                    ss.initial_str()
-                } else {
-                    ""
-                }
-            }
-            Leafv(ref v) => v.v.as_str(),
+            Leafv(ref s) => s.span,
+            Branchv(ref s) => s.span,
         }
     }
-    pub fn to_string(&self) -> String {
-        to_woodslist(self)
-    }
-
-    pub fn strip_comments_escape(&mut self, comment_str: &str, comment_escape_str: &str) {
+    /// the comments that came before this wood in the text, if the parser attached them
+    pub fn comments(&self) -> &[String] {
         match *self {
-            Branchv(ref mut b) => {
-                b.v.retain_mut(|i| {
-                    if i.initial_str() == comment_str {
-                        false
-                    } else {
-                        i.strip_comments_escape(comment_str, comment_escape_str);
-                        true
-                    }
-                });
-            }
-            Leafv(ref mut l) => {
-                if &l.v == comment_escape_str {
-                    l.v = comment_str.into();
-                }
-            }
+            Leafv(ref s) => &s.comments,
+            Branchv(ref s) => &s.comments,
         }
     }
-
-    /// Strips any branches with first element of comment_str. If you need to produce a leaf that is equivalent to comment_str.
-    /// If you need the wood to contain a leaf that is the comment_str, you can escape it with a backslash.
-    /// This is actually a highly flawed way of providing commenting, because this will also strip out any serialization of a list of strings where the first element happens to equal the `comment_str`. That's a really subtle error, that violates a lot of expectations.
-    /// You could get around it by escaping your wood so that any strs that resemble comment tags wont read that way, but it's a bit awkward and sometimes wont really work.
-    pub fn strip_comments(&mut self, comment_str: &str) {
-        let escstr = format!("\\{}", comment_str);
-        self.strip_comments_escape(comment_str, &escstr);
+    pub fn comments_mut(&mut self) -> &mut Vec<String> {
+        match *self {
+            Leafv(ref mut s) => &mut s.comments,
+            Branchv(ref mut s) => &mut s.comments,
+        }
     }
-
     /// if Leaf, returns a slice iter containing just this, else Branch, iterates over branch contents
     pub fn contents(&self) -> std::slice::Iter<Self> {
         match *self {
@@ -256,18 +350,18 @@ impl Wood {
         }
     }
     /// returns the first wood, or if it's a leaf, itself
-    pub fn head(&self) -> Result<&Wood, Box<WoodError>> {
+    pub fn head(&self) -> Result<&Wood<S>, Box<WoodError>> {
         self.contents().next().ok_or_else(|| {
-            Box::new(WoodError::new(
+            Box::new(WoodError::at(
                 self,
                 "there shouldn't be an empty list here".to_string(),
             ))
         })
     }
     /// returns the second wood within this one, if it is a list wood, if there is a second wood
-    pub fn second(&self) -> Result<&Wood, Box<WoodError>> {
+    pub fn second(&self) -> Result<&Wood<S>, Box<WoodError>> {
         self.tail().next().ok_or_else(|| {
-            Box::new(WoodError::new(
+            Box::new(WoodError::at(
                 self,
                 "a second wood was supposed to be present".to_string(),
             ))
@@ -280,6 +374,61 @@ impl Wood {
             Leafv(_) => [].iter(),
         }
     }
+    /// The list at the start of this wood that directly holds its `initial_str`, found by following the first element of each branch until the first element is a leaf. For `((key vv) val)`, that's `(key vv)`. For anything else, including leaves and empty branches, it's the wood itself.
+    pub fn initial_list(&self) -> &Wood<S> {
+        let mut at = self;
+        while let Some(first @ Branchv(_)) = at.get_branch().and_then(|v| v.first()) {
+            at = first;
+        }
+        at
+    }
+    /// `initial_list`, to be edited
+    pub fn initial_list_mut(&mut self) -> &mut Wood<S> {
+        let mut at = self;
+        while let Some(&Branchv(_)) = at.get_branch().and_then(|v| v.first()) {
+            at = match *at {
+                Branchv(ref mut b) => &mut b.v[0],
+                Leafv(_) => unreachable!(),
+            };
+        }
+        at
+    }
+    /// The contents, to be edited. If this is a leaf, it's first made into a branch containing just the leaf, which has the same contents, in the sense of `contents`.
+    pub fn contents_mut(&mut self) -> &mut Vec<Wood<S>> {
+        if let Leafv(ref l) = *self {
+            let (line, column, span) = (l.line, l.column, l.span);
+            let leaf = std::mem::take(self);
+            *self = Branchv(Branch {
+                line,
+                column,
+                span,
+                comments: Vec::new(),
+                v: vec![leaf],
+            });
+        }
+        match *self {
+            Branchv(ref mut b) => &mut b.v,
+            Leafv(_) => unreachable!(),
+        }
+    }
+    /// adds `w` to the end of the contents (see `contents_mut` for what happens to leaves)
+    pub fn push(&mut self, w: Wood<S>) {
+        self.contents_mut().push(w);
+    }
+    /// inserts `w` into the contents at `index`, shifting everything after it along. Panics if `index` is greater than the number of contents, as `Vec::insert` does. (see `contents_mut` for what happens to leaves)
+    pub fn insert_at(&mut self, index: usize, w: Wood<S>) {
+        self.contents_mut().insert(index, w);
+    }
+    /// keeps only the branch's contents for which `f` returns true. Has no effect on leaves.
+    pub fn retain(&mut self, f: impl FnMut(&Wood<S>) -> bool) {
+        if let Branchv(ref mut b) = *self {
+            b.v.retain(f);
+        }
+    }
+    /// takes the wood out, leaving an empty branch in its place
+    pub fn take(&mut self) -> Wood<S> {
+        std::mem::take(self)
+    }
     /// returns the tail of the first element of the wood chained with the rest of the wood. For dealing with a common situation with the termpose syntax where you want to ignore the structure of initial line items
     /// ```plain
     /// call a b car
@@ -298,35 +447,110 @@ impl Wood {
             [].iter().chain([].iter())
         }
     }
-    /// `self.contents().find(|el| el.initial_str() == key)`
-    // TODO: Make this seek the initial list. For instance, In (((key vv) val) (nonkey a)), it should return (key vv), not ((key vv) val). This allows the schema to be evolved so that things can be associated with a kv pair without changing it
-    pub fn seek<'a, 'b>(&'a self, key: &'b str) -> Option<&'a Wood> {
-        self.contents().find(|el| el.initial_str() == key)
+}
+
+impl<S: AsRef<str>> Wood<S> {
+    pub fn what(&self) -> LB<'_, S> {
+        match *self {
+            Leafv(ref s) => L(s.v.as_ref()),
+            Branchv(ref s) => B(&s.v),
+        }
+    }
+    pub fn get_leaf(&self) -> Option<&str> {
+        match *self {
+            Leafv(ref s) => Some(s.v.as_ref()),
+            Branchv(_) => None,
+        }
     }
-    pub fn seek_val<'a, 'b>(&'a self, key: &'b str) -> Option<&'a Wood> {
+    /// Seeks the earliest string in the tree by looking at the first element of each branch recursively until it hits a leaf. (If it runs into an empty list, returns the empty string.
+    /// I recommend this whenever you want to use the first string as a discriminator, or whenever you want to get leaf str contents in general.
+    /// This abstracts over similar structures in a way that I consider generally desirable. I would go as far as to say that the more obvious, less abstract way of getting initial string should be Considered Harmful.
+    /// A few motivating examples:
+    /// If you wanted to add a feature to a programming language that allows you to add a special tag to an invocation, you want to put the tag inside the invocation's ast node but you don't want it to be confused for a parameter, this pattern enables:
+    /// ((f tag(special_invoke_inline)) a b)
+    /// If the syntax of a sexp language had more structure to it than usual:
+    /// ((if condition) then...) would still get easily picked up as an 'if' node.
+    /// Annotations are a good example, more generally, if you're refactoring and you decide you want to add an extra field to what was previously a leaf, this pattern enables you to make that change, confident that your code will still read its string content in the same way
+    /// (list key:value "some prose") -> (list key:value ("some prose" modifier:italicise))
+    pub fn initial_str(&self) -> &str {
+        let mut w = self;
+        loop {
+            match *w {
+                Branchv(ref v) => match v.v.first() {
+                    Some(first) => w = first,
+                    None => return "",
+                },
+                Leafv(ref v) => return v.v.as_ref(),
+            }
+        }
+    }
+    /// `self.contents().find(|el| el.initial_str() == key).map(|el| el.initial_list())`
+    /// Seeks the initial list. For instance, In (((key vv) val) (nonkey a)), it returns (key vv), not ((key vv) val). This allows the schema to be evolved so that things can be associated with a kv pair without changing it
+    pub fn seek<'a, 'b>(&'a self, key: &'b str) -> Option<&'a Wood<S>> {
+        self.contents()
+            .find(|el| el.initial_str() == key)
+            .map(|el| el.initial_list())
+    }
+    pub fn seek_val<'a, 'b>(&'a self, key: &'b str) -> Option<&'a Wood<S>> {
         self.seek(key).and_then(|w| w.tail().next())
     }
 
     /// returns the first child term with initial_str == key, or if none is found, an error
-    pub fn find<'a, 'b>(&'a self, key: &'b str) -> Result<&'a Wood, Box<WoodError>> {
+    pub fn find<'a, 'b>(&'a self, key: &'b str) -> Result<&'a Wood<S>, Box<WoodError>> {
         self.seek(key).ok_or_else(|| {
-            Box::new(WoodError::new(
+            Box::new(WoodError::at(
                 self,
                 format!("could not find child with key \"{}\"", key),
             ))
         })
     }
     /// find(self, key).and_then(|v| v.second())
-    pub fn find_val<'a, 'b>(&'a self, key: &'b str) -> Result<&'a Wood, Box<WoodError>> {
+    pub fn find_val<'a, 'b>(&'a self, key: &'b str) -> Result<&'a Wood<S>, Box<WoodError>> {
         self.find(key).and_then(|v| v.second())
     }
+
+    /// `seek`, to be edited
+    pub fn seek_mut<'a>(&'a mut self, key: &str) -> Option<&'a mut Wood<S>> {
+        match *self {
+            Branchv(ref mut b) => {
+                b.v.iter_mut()
+                    .find(|el| el.initial_str() == key)
+                    .map(|el| el.initial_list_mut())
+            }
+            Leafv(ref l) if l.v.as_ref() == key => Some(self),
+            Leafv(_) => None,
+        }
+    }
+    /// `find`, to be edited
+    pub fn find_mut<'a>(&'a mut self, key: &str) -> Result<&'a mut Wood<S>, Box<WoodError>> {
+        self.find(key)?;
+        Ok(self.seek_mut(key).unwrap())
+    }
+    /// `find_val`, to be edited
+    pub fn find_val_mut<'a>(&'a mut self, key: &str) -> Result<&'a mut Wood<S>, Box<WoodError>> {
+        self.find_val(key)?;
+        match *self.seek_mut(key).unwrap() {
+            Branchv(ref mut b) => Ok(&mut b.v[1]),
+            Leafv(_) => unreachable!(),
+        }
+    }
+    /// Removes and returns the first of the branch's contents with initial_str == key. Unlike `seek`, this is the whole of that wood, not just its initial list. Leaves have nothing to remove.
+    pub fn remove(&mut self, key: &str) -> Option<Wood<S>> {
+        match *self {
+            Branchv(ref mut b) => {
+                let i = b.v.iter().position(|el| el.initial_str() == key)?;
+                Some(b.v.remove(i))
+            }
+            Leafv(_) => None,
+        }
+    }
 }
 
 //I really wanted to make this recurse over everything, in braces, making branches of each, but this did not work
 #[macro_export]
 macro_rules! woods {
 	($($el:expr),* $(,)?)=> {
-		$crate::Branchv($crate::Branch{line:-1, column:-1, v:vec!($($crate::Wood::from($el)),*)})
+		$crate::Branchv($crate::Branch{line:-1, column:-1, span: ::std::option::Option::None, comments: ::std::vec::Vec::new(), v: ::std::vec!($($crate::Wood::from($el)),*)})
 	};
 	// ($e:expr)=> { Wood::from($e) }
 }
@@ -336,35 +560,127 @@ pub trait Wooder<T> {
 }
 pub trait Dewooder<T> {
     fn dewoodify(&self, v: &Wood) -> Result<T, Box<WoodError>>;
+    /// `Dewoodable::dewoodify_all_into`, for dewooders
+    fn dewoodify_all_into(&self, v: &Wood, errors: &mut WoodErrors) -> Option<T> {
+        errors.record(self.dewoodify(v))
+    }
+    /// Like `dewoodify`, but reports every error in the wood instead of just the first
+    fn dewoodify_all(&self, v: &Wood) -> Result<T, WoodErrors> {
+        let mut errors = WoodErrors::new();
+        let r = self.dewoodify_all_into(v, &mut errors);
+        errors.into_result(r)
+    }
+}
+
+/// One step on the way from the root of a wood to one of its parts. `Key` steps into the entry under a key, in a struct's fields or a map, `Index` steps into an element of a list.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+/// writes paths like `servers[2].port`
+fn write_path<W: std::fmt::Write>(path: &[PathSegment], f: &mut W) -> std::fmt::Result {
+    for (i, s) in path.iter().enumerate() {
+        match *s {
+            PathSegment::Key(ref k) if i == 0 => f.write_str(k)?,
+            PathSegment::Key(ref k) => write!(f, ".{}", k)?,
+            PathSegment::Index(n) => write!(f, "[{}]", n)?,
+        }
+    }
+    Ok(())
+}
+
+/// What sort of error a `WoodError` is, so that callers can handle some of them differently without reading the messages
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// the text isn't valid termpose or woodslist
+    Syntax,
+    /// the text goes beyond one of the parser's `ParseLimits`
+    LimitExceeded,
+    /// a field's key wasn't there
+    MissingKey,
+    /// there was a key that isn't one of the struct's fields, under `#[wood(deny_unknown_fields)]`
+    UnknownKey,
+    /// there was a branch where there should have been a leaf
+    UnexpectedBranch,
+    /// there was a leaf where there should have been a branch
+    UnexpectedLeaf,
+    /// a leaf's text isn't a valid number, bool, address or whatever else it was supposed to be
+    ParseScalar,
+    /// a branch has the wrong number of elements
+    ArityMismatch,
+    /// a tag isn't the name of any of the variants, or isn't the tag that was expected
+    UnknownVariant,
+    /// errors of hand-written impls, and anything else
+    Other,
 }
 
 #[derive(Debug)]
 pub struct WoodError {
     pub line: isize,
     pub column: isize,
+    pub span: Option<Span>,
+    pub kind: ErrorKind,
+    /// where in the value being read the error was, eg, `servers[2].port`, which locates it even when the wood was made in code and has no line numbers. The readers add each step as the error comes back up through them.
+    pub path: Vec<PathSegment>,
     pub msg: String,
     pub cause: Option<Box<dyn Error>>,
 }
+/// `line:column: path: msg`, leaving out the position if the error has none and the path if it's empty. For a report that shows the offending source, see `WoodError::render`.
 impl Display for WoodError {
     fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
-        Debug::fmt(self, f)
+        if self.line >= 0 {
+            write!(f, "{}:{}: ", self.line, self.column)?;
+        }
+        if !self.path.is_empty() {
+            write_path(&self.path, f)?;
+            f.write_str(": ")?;
+        }
+        f.write_str(&self.msg)
     }
 }
 impl WoodError {
     pub fn new(source: &Wood, msg: String) -> Self {
+        Self::at(source, msg)
+    }
+    /// `new`, for any kind of `Wood`
+    pub fn at<S>(source: &Wood<S>, msg: String) -> Self {
         let (line, column) = source.line_and_col();
         Self {
             line,
             column,
+            span: source.span(),
+            kind: ErrorKind::Other,
+            path: Vec::new(),
             msg,
             cause: None,
         }
     }
+    /// the error, with its kind set to `kind`. Errors made with `new` and `at` are `ErrorKind::Other`.
+    pub fn with_kind(mut self, kind: ErrorKind) -> Self {
+        self.kind = kind;
+        self
+    }
+    /// the error, a step further down the path. Readers use this to build up the path as an error passes back up through them, from the inside out.
+    pub fn within(mut self: Box<Self>, step: PathSegment) -> Box<Self> {
+        self.path.insert(0, step);
+        self
+    }
+    /// the path to the error, as it's displayed, eg, `servers[2].port`
+    pub fn path_string(&self) -> String {
+        let mut ret = String::new();
+        write_path(&self.path, &mut ret).unwrap();
+        ret
+    }
     pub fn new_with_cause(source: &Wood, msg: String, cause: Box<dyn Error>) -> Self {
         let (line, column) = source.line_and_col();
         WoodError {
             line,
             column,
+            span: source.span(),
+            kind: ErrorKind::Other,
+            path: Vec::new(),
             msg,
             cause: Some(cause),
         }
@@ -374,6 +690,86 @@ impl Error for WoodError {
     fn cause(&self) -> Option<&dyn Error> {
         self.cause.as_ref().map(|e| e.as_ref())
     }
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.cause.as_deref()
+    }
+}
+
+/// Every error an accumulating read, `dewoodify_all`, found, in the order it found them. While the read is underway, it also keeps track of where in the wood the read is, so that each error can be given the path to it.
+#[derive(Debug, Default)]
+pub struct WoodErrors {
+    pub errors: Vec<WoodError>,
+    at: Vec<PathSegment>,
+}
+impl WoodErrors {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// notes an error found at the current path
+    pub fn push(&mut self, mut e: WoodError) {
+        e.path.splice(0..0, self.at.iter().cloned());
+        self.errors.push(e);
+    }
+    /// the value, if `r` is one, otherwise notes the error
+    pub fn record<T>(&mut self, r: Result<T, Box<WoodError>>) -> Option<T> {
+        match r {
+            Ok(v) => Some(v),
+            Err(e) => {
+                self.push(*e);
+                None
+            }
+        }
+    }
+    /// runs `f` a step further down the path, for reading a part of the wood
+    pub fn within<R>(&mut self, step: PathSegment, f: impl FnOnce(&mut WoodErrors) -> R) -> R {
+        self.at.push(step);
+        let r = f(self);
+        self.at.pop();
+        r
+    }
+    pub fn len(&self) -> usize {
+        self.errors.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+    pub fn iter(&self) -> std::slice::Iter<'_, WoodError> {
+        self.errors.iter()
+    }
+    /// Finishes a read, giving the value only if no errors were found
+    pub fn into_result<T>(self, v: Option<T>) -> Result<T, WoodErrors> {
+        match v {
+            Some(v) if self.is_empty() => Ok(v),
+            _ => Err(self),
+        }
+    }
+}
+/// each error on its own line
+impl Display for WoodErrors {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        for (i, e) in self.errors.iter().enumerate() {
+            if i != 0 {
+                f.write_str("\n")?;
+            }
+            write!(f, "{}", e)?;
+        }
+        Ok(())
+    }
+}
+impl Error for WoodErrors {}
+impl IntoIterator for WoodErrors {
+    type Item = WoodError;
+    type IntoIter = std::vec::IntoIter<WoodError>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.errors.into_iter()
+    }
+}
+impl<'a> IntoIterator for &'a WoodErrors {
+    type Item = &'a WoodError;
+    type IntoIter = std::slice::Iter<'a, WoodError>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.errors.iter()
+    }
 }
 
 pub trait Woodable {
@@ -383,6 +779,13 @@ pub trait Dewoodable {
     fn dewoodify(v: &Wood) -> Result<Self, Box<WoodError>>
     where
         Self: Sized;
+    /// Like `dewoodify`, but carries on past errors, noting each one in `errors`, so that a single read can report everything that's wrong with the wood. Returns None if it couldn't make a value. The collections and the derived impls read every element and field, and the default notes just the first error `dewoodify` finds.
+    fn dewoodify_all_into(v: &Wood, errors: &mut WoodErrors) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        errors.record(Self::dewoodify(v))
+    }
 }
 
 pub fn woodify<T>(v: &T) -> Wood
@@ -398,6 +801,22 @@ where
     T::dewoodify(v)
 }
 
+/// Like `dewoodify`, but reports every error in the wood instead of just the first, each with the path to it
+/// ```
+/// let w = wood::parse_termpose("1 a 3 b").unwrap();
+/// let errors = wood::dewoodify_all::<Vec<u32>>(&w).unwrap_err();
+/// assert_eq!(2, errors.len());
+/// assert_eq!("1:7: [3]: couldn't parse u32", errors.errors[1].to_string());
+/// ```
+pub fn dewoodify_all<T>(v: &Wood) -> Result<T, WoodErrors>
+where
+    T: Dewoodable,
+{
+    let mut errors = WoodErrors::new();
+    let r = T::dewoodify_all_into(v, &mut errors);
+    errors.into_result(r)
+}
+
 /// parse_termpose(v).and_then(dewoodify)
 pub fn deserialize<T>(v: &str) -> Result<T, Box<WoodError>>
 where
@@ -427,11 +846,14 @@ macro_rules! do_basic_destringifying_dewoodable_for {
         impl Dewoodable for $Type {
             fn dewoodify(v: &Wood) -> Result<$Type, Box<WoodError>> {
                 $Type::from_str(v.initial_str()).map_err(|er| {
-                    Box::new(WoodError::new_with_cause(
-                        v,
-                        format!("couldn't parse {}", stringify!($name)),
-                        Box::new(er),
-                    ))
+                    Box::new(
+                        WoodError::new_with_cause(
+                            v,
+                            format!("couldn't parse {}", stringify!($Type)),
+                            Box::new(er),
+                        )
+                        .with_kind(ErrorKind::ParseScalar),
+                    )
                 })
             }
         }
@@ -460,6 +882,52 @@ do_basic_stringifying_woodable_for!(isize);
 do_basic_destringifying_dewoodable_for!(isize);
 do_basic_stringifying_woodable_for!(usize);
 do_basic_destringifying_dewoodable_for!(usize);
+do_basic_stringifying_woodable_for!(u8);
+do_basic_destringifying_dewoodable_for!(u8);
+do_basic_stringifying_woodable_for!(u16);
+do_basic_destringifying_dewoodable_for!(u16);
+do_basic_stringifying_woodable_for!(i8);
+do_basic_destringifying_dewoodable_for!(i8);
+do_basic_stringifying_woodable_for!(i16);
+do_basic_destringifying_dewoodable_for!(i16);
+
+do_basic_stringifying_woodable_for!(NonZeroU8);
+do_basic_destringifying_dewoodable_for!(NonZeroU8);
+do_basic_stringifying_woodable_for!(NonZeroU16);
+do_basic_destringifying_dewoodable_for!(NonZeroU16);
+do_basic_stringifying_woodable_for!(NonZeroU32);
+do_basic_destringifying_dewoodable_for!(NonZeroU32);
+do_basic_stringifying_woodable_for!(NonZeroU64);
+do_basic_destringifying_dewoodable_for!(NonZeroU64);
+do_basic_stringifying_woodable_for!(NonZeroU128);
+do_basic_destringifying_dewoodable_for!(NonZeroU128);
+do_basic_stringifying_woodable_for!(NonZeroUsize);
+do_basic_destringifying_dewoodable_for!(NonZeroUsize);
+do_basic_stringifying_woodable_for!(NonZeroI8);
+do_basic_destringifying_dewoodable_for!(NonZeroI8);
+do_basic_stringifying_woodable_for!(NonZeroI16);
+do_basic_destringifying_dewoodable_for!(NonZeroI16);
+do_basic_stringifying_woodable_for!(NonZeroI32);
+do_basic_destringifying_dewoodable_for!(NonZeroI32);
+do_basic_stringifying_woodable_for!(NonZeroI64);
+do_basic_destringifying_dewoodable_for!(NonZeroI64);
+do_basic_stringifying_woodable_for!(NonZeroI128);
+do_basic_destringifying_dewoodable_for!(NonZeroI128);
+do_basic_stringifying_woodable_for!(NonZeroIsize);
+do_basic_destringifying_dewoodable_for!(NonZeroIsize);
+
+do_basic_stringifying_woodable_for!(IpAddr);
+do_basic_destringifying_dewoodable_for!(IpAddr);
+do_basic_stringifying_woodable_for!(Ipv4Addr);
+do_basic_destringifying_dewoodable_for!(Ipv4Addr);
+do_basic_stringifying_woodable_for!(Ipv6Addr);
+do_basic_destringifying_dewoodable_for!(Ipv6Addr);
+do_basic_stringifying_woodable_for!(SocketAddr);
+do_basic_destringifying_dewoodable_for!(SocketAddr);
+do_basic_stringifying_woodable_for!(SocketAddrV4);
+do_basic_destringifying_dewoodable_for!(SocketAddrV4);
+do_basic_stringifying_woodable_for!(SocketAddrV6);
+do_basic_destringifying_dewoodable_for!(SocketAddrV6);
 
 do_basic_stringifying_woodable_for!(bool);
 impl Dewoodable for bool {
@@ -467,7 +935,9 @@ impl Dewoodable for bool {
         match v.initial_str() {
             "true" | "⊤" | "yes" => Ok(true),
             "false" | "⟂" | "no" => Ok(false),
-            _ => Err(Box::new(WoodError::new(v, "expected a bool here".into()))),
+            _ => Err(Box::new(
+                WoodError::new(v, "expected a bool here".into()).with_kind(ErrorKind::ParseScalar),
+            )),
         }
     }
 }
@@ -476,6 +946,11 @@ impl Dewoodable for bool {
 
 // }
 
+impl Woodable for str {
+    fn woodify(&self) -> Wood {
+        self.into()
+    }
+}
 impl Woodable for String {
     fn woodify(&self) -> Wood {
         self.as_str().into()
@@ -485,14 +960,100 @@ impl Dewoodable for String {
     fn dewoodify(v: &Wood) -> Result<Self, Box<WoodError>> {
         match *v {
             Leafv(ref a) => Ok(a.v.clone()),
-            Branchv(_) => Err(Box::new(WoodError::new(
-                v,
-                "sought string, found branch".into(),
-            ))),
+            Branchv(_) => Err(Box::new(
+                WoodError::new(v, "sought string, found branch".into())
+                    .with_kind(ErrorKind::UnexpectedBranch),
+            )),
         }
     }
 }
 
+impl<T> Woodable for &T
+where
+    T: Woodable + ?Sized,
+{
+    fn woodify(&self) -> Wood {
+        (**self).woodify()
+    }
+}
+
+/// `()` is the empty branch
+impl Woodable for () {
+    fn woodify(&self) -> Wood {
+        Wood::branch(Vec::new())
+    }
+}
+impl Dewoodable for () {
+    fn dewoodify(v: &Wood) -> Result<(), Box<WoodError>> {
+        match *v {
+            Branchv(ref b) if b.v.is_empty() => Ok(()),
+            Leafv(_) => Err(Box::new(
+                WoodError::new(v, "expected an empty branch, ()".into())
+                    .with_kind(ErrorKind::UnexpectedLeaf),
+            )),
+            _ => Err(Box::new(
+                WoodError::new(v, "expected an empty branch, ()".into())
+                    .with_kind(ErrorKind::ArityMismatch),
+            )),
+        }
+    }
+}
+
+/// Paths are leaves. Paths that aren't valid unicode are written lossily.
+impl Woodable for Path {
+    fn woodify(&self) -> Wood {
+        self.to_string_lossy().as_ref().into()
+    }
+}
+impl Woodable for PathBuf {
+    fn woodify(&self) -> Wood {
+        self.as_path().woodify()
+    }
+}
+impl Dewoodable for PathBuf {
+    fn dewoodify(v: &Wood) -> Result<PathBuf, Box<WoodError>> {
+        String::dewoodify(v).map(PathBuf::from)
+    }
+}
+
+/// A leaf of seconds, with as many decimal places as it needs to be exact, eg, `1.5` or `0.000000001`
+impl Woodable for Duration {
+    fn woodify(&self) -> Wood {
+        let nanos = self.subsec_nanos();
+        if nanos == 0 {
+            self.as_secs().to_string().into()
+        } else {
+            let fraction = format!("{:09}", nanos);
+            format!("{}.{}", self.as_secs(), fraction.trim_end_matches('0')).into()
+        }
+    }
+}
+impl Dewoodable for Duration {
+    fn dewoodify(v: &Wood) -> Result<Duration, Box<WoodError>> {
+        let bad = || {
+            Box::new(
+                WoodError::new(
+                    v,
+                    "expected a duration, a number of seconds like 2 or 0.5".into(),
+                )
+                .with_kind(ErrorKind::ParseScalar),
+            )
+        };
+        let s = v.initial_str();
+        let (secs, fraction) = s.split_once('.').unwrap_or((s, ""));
+        let all_digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+        if secs.is_empty() || fraction.len() > 9 || !all_digits(secs) || !all_digits(fraction) {
+            return Err(bad());
+        }
+        let secs = u64::from_str(secs).map_err(|_| bad())?;
+        let nanos = format!("{:0<9}", fraction);
+        Ok(Duration::new(
+            secs,
+            u32::from_str(&nanos).map_err(|_| bad())?,
+        ))
+    }
+}
+
 pub fn woodify_seq_into<'a, InnerTran, T, I>(inner: &InnerTran, v: I, output: &mut Vec<Wood>)
 where
     InnerTran: Wooder<T>,
@@ -512,20 +1073,35 @@ where
     InnerTran: Dewooder<T>,
     I: Iterator<Item = &'a Wood>,
 {
-    // let errors = Vec::new();
-    for vi in v {
+    for (i, vi) in v.enumerate() {
         match inner.dewoodify(vi) {
             Ok(vii) => output.push(vii),
-            Err(e) => return Err(e),
+            Err(e) => return Err(e.within(PathSegment::Index(i))),
         }
     }
     Ok(())
-    // if errors.len() > 0 {
-    // 	let msgs = String::new();
-    // 	for e in errors {
-    // 		msgs.push(format!("{}\n"))
-    // 	}
-    // }
+}
+/// `dewoodify_seq_into`, but reads every element, noting the errors of the ones that fail. Returns whether they all succeeded.
+pub fn dewoodify_seq_all_into<'a, InnerTran, T, I>(
+    inner: &InnerTran,
+    v: I,
+    output: &mut Vec<T>,
+    errors: &mut WoodErrors,
+) -> bool
+where
+    InnerTran: Dewooder<T>,
+    I: Iterator<Item = &'a Wood>,
+{
+    let mut all = true;
+    for (i, vi) in v.enumerate() {
+        match errors.within(PathSegment::Index(i), |errors| {
+            inner.dewoodify_all_into(vi, errors)
+        }) {
+            Some(vii) => output.push(vii),
+            None => all = false,
+        }
+    }
+    all
 }
 
 impl<T> Woodable for Vec<T>
@@ -547,13 +1123,72 @@ where
         dewoodify_seq_into(&wooder::Iden, v.contents(), &mut ret)?;
         Ok(ret)
     }
+    fn dewoodify_all_into(v: &Wood, errors: &mut WoodErrors) -> Option<Vec<T>> {
+        let mut ret = Vec::new();
+        dewoodify_seq_all_into(&wooder::Iden, v.contents(), &mut ret, errors).then_some(ret)
+    }
+}
+
+/// `None` is the empty branch, `()`. `Some(v)` is just `v`'s wood, so if `v`'s wood is also `()`, it'll read back as `None`. The derives leave `None` fields out entirely instead of writing them as `()`, and read absent fields as `None`.
+impl<T> Woodable for Option<T>
+where
+    T: Woodable,
+{
+    fn woodify(&self) -> Wood {
+        match *self {
+            Some(ref v) => v.woodify(),
+            None => Wood::branch(Vec::new()),
+        }
+    }
+}
+impl<T> Dewoodable for Option<T>
+where
+    T: Dewoodable,
+{
+    fn dewoodify(v: &Wood) -> Result<Option<T>, Box<WoodError>> {
+        match *v {
+            Branchv(ref b) if b.v.is_empty() => Ok(None),
+            _ => T::dewoodify(v).map(Some),
+        }
+    }
+    fn dewoodify_all_into(v: &Wood, errors: &mut WoodErrors) -> Option<Option<T>> {
+        match *v {
+            Branchv(ref b) if b.v.is_empty() => Some(None),
+            _ => T::dewoodify_all_into(v, errors).map(Some),
+        }
+    }
 }
 
 mod parsers;
 pub use parsers::*;
 
+mod source_map;
+pub use source_map::*;
+
+mod diagnostics;
+
+mod wood_path;
+pub use wood_path::*;
+
+mod entry;
+pub use entry::*;
+
+mod visit;
+pub use visit::*;
+
+pub mod termpose;
+
 pub mod wooder;
 
+#[cfg(feature = "serde")]
+pub mod serde;
+
+#[cfg(any(test, feature = "proptest"))]
+pub mod proptest;
+
+#[cfg(test)]
+mod test_util;
+
 #[cfg(test)]
 mod tests {
     extern crate test;
@@ -4,6 +4,7 @@
 
 use super::*;
 use std::{
+	fmt::{self, Write},
 	mem::replace,
 	ptr::read
 };
@@ -20,21 +21,21 @@ fn get_back_mut<T>(v:&mut Vec<T>)-> &mut T {
 
 
 #[inline(always)]
-fn assume_branch_mut(v:&mut Wood)-> &mut Branch {
+fn assume_branch_mut<S>(v:&mut Wood<S>)-> &mut Branch<S> {
 	match *v {
 		Branchv(ref mut ls)=> ls,
 		Leafv(_)=> panic!("this Wood is supposed to be a branch"),
 	}
 }
 #[inline(always)]
-fn assume_leaf_mut(v:&mut Wood)-> &mut Leaf {
+fn assume_leaf_mut<S>(v:&mut Wood<S>)-> &mut Leaf<S> {
 	match *v {
 		Branchv(_)=> panic!("this Wood is supposed to be an leaf"),
 		Leafv(ref mut ar)=> ar,
 	}
 }
 #[inline(always)]
-fn assume_branch(v:Wood)-> Branch {
+fn assume_branch<S>(v:Wood<S>)-> Branch<S> {
 	match v {
 		Branchv(ls)=> ls,
 		Leafv(_)=> panic!("this Wood is supposed to be a branch"),
@@ -48,11 +49,13 @@ fn yank_first<T>(v:Vec<T>)-> T { //you must ensure that the v contains at least
 	}
 }
 
-fn accrete_branch(v:&mut Wood)-> &mut Vec<Wood> {
+fn accrete_branch<S>(v:&mut Wood<S>)-> &mut Vec<Wood<S>> {
 	unsafe{
-		replace_self(v, |vv|{
+		replace_self(v, |mut vv|{
 			let (line, column) = vv.line_and_col();
-			Branchv(Branch{line, column, v:vec!(vv)})
+			let span = vv.span();
+			let comments = std::mem::take(vv.comments_mut()); //the comments came before the whole of the new branch
+			Branchv(Branch{line, column, span, comments, v:vec!(vv)})
 		}); //safe: branch creation doesn't panic
 	}
 	&mut assume_branch_mut(v).v
@@ -70,8 +73,245 @@ unsafe fn str_from_bounds<'a>(o:*const u8, f:*const u8)-> &'a str {
 
 fn is_whitespace(c:char)-> bool { c == ' ' || c == '\t' }
 
-fn do_indent(indent:&str, indent_depth:usize, out:&mut String){
-	for _ in 0..indent_depth { out.push_str(indent); }
+///reads what follows an escape slash, in either format, taking chars from `next`, and returns the char it stands for. `\u{XXXX}` takes one to six hex digits naming any unicode scalar value.
+pub(crate) fn escaped_char(mut next:impl FnMut()-> Option<char>)-> Option<char> {
+	match next()? {
+		'n'=> Some('\n'),
+		'r'=> Some('\r'),
+		't'=> Some('\t'),
+		'h'=> Some('☃'),
+		'"'=> Some('"'),
+		'\\'=> Some('\\'),
+		'u'=> {
+			if next()? != '{' { return None; }
+			let mut code:u32 = 0;
+			for digits in 0..7 {
+				match next()? {
+					'}' if digits > 0 => return char::from_u32(code),
+					c => code = code*16 + c.to_digit(16)?,
+				}
+			}
+			None
+		}
+		_=> None,
+	}
+}
+
+///Which comments the lexers recognise. Comments are read wherever a term could begin, and never become Woods. If `attach` is set, the text of each comment is kept in the `comments` of the Wood that follows it, so that printers can put them back.
+///Markers shouldn't begin with any character that already means something in the syntax (parens, quotes, the pairing char), or they wont be noticed everywhere they should be. They can be literals, or strings decided at runtime:
+///```
+///use wood::CommentStyle;
+///let marker = String::from("--");
+///let style = CommentStyle{ line:Some(marker.into()), block:Some(("{-".into(), "-}".into())), attach:false };
+///assert_eq!(Some("--"), style.line.as_deref());
+///```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CommentStyle {
+	///begins a comment that runs to the end of the line, eg `"//"`
+	pub line: Option<Cow<'static, str>>,
+	///the opener and closer of a comment that can span lines, eg `("/*", "*/")`. They don't nest.
+	pub block: Option<(Cow<'static, str>, Cow<'static, str>)>,
+	pub attach: bool,
+}
+
+pub const NO_COMMENTS:CommentStyle = CommentStyle{ line:None, block:None, attach:false };
+
+pub(crate) const UNTERMINATED_BLOCK_COMMENT:&str = "unterminated block comment";
+
+///a comment found in the text
+pub(crate) struct Comment<'a> {
+	///the number of bytes it spans, which for line comments doesn't include the line ending
+	pub len: usize,
+	///what's between its markers
+	pub text: &'a str,
+	///false if it was a block comment that was never closed
+	pub closed: bool,
+}
+
+impl CommentStyle {
+	pub fn is_empty(&self)-> bool { self.line.is_none() && self.block.is_none() }
+	pub(crate) fn block_markers(&self)-> Option<(&str, &str)> { self.block.as_ref().map(|(open, close)| (&**open, &**close)) }
+
+	///the comment that `s` begins with, if it begins with one
+	pub(crate) fn comment_at<'a>(&self, s:&'a str)-> Option<Comment<'a>> {
+		if let Some(m) = self.line.as_deref() {
+			if let Some(rest) = s.strip_prefix(m) {
+				let text_len = rest.find(['\n', '\r']).unwrap_or(rest.len());
+				return Some(Comment{ len:m.len() + text_len, text:&rest[..text_len], closed:true });
+			}
+		}
+		if let Some((open, close)) = self.block_markers() {
+			if let Some(inner) = s.strip_prefix(open) {
+				return Some(match inner.find(close) {
+					Some(e)=> Comment{ len:open.len() + e + close.len(), text:&inner[..e], closed:true },
+					None=> Comment{ len:s.len(), text:inner, closed:false },
+				});
+			}
+		}
+		None
+	}
+
+	///writes a comment out on lines of its own, each beginning with a newline and the indentation
+	fn print_comment<W:Write>(&self, text:&str, indent:&str, indent_depth:usize, out:&mut W)-> fmt::Result {
+		let line_out = |out:&mut W, open:&str, line:&str, close:&str|-> fmt::Result {
+			out.write_char('\n')?;
+			do_indent(indent, indent_depth, out)?;
+			out.write_str(open)?;
+			if !line.is_empty() {
+				out.write_char(' ')?;
+				out.write_str(line)?;
+				if !close.is_empty() { out.write_char(' ')?; }
+			}
+			out.write_str(close)
+		};
+		if let Some(m) = self.line.as_deref() {
+			//a comment ends at any of the line endings the parsers recognise, a lone '\r' included
+			for l in text.split('\n').flat_map(|l| l.strip_suffix('\r').unwrap_or(l).split('\r')) { line_out(out, m, l, "")?; }
+		}else if let Some((open, close)) = self.block_markers() {
+			line_out(out, open, text, close)?;
+		}
+		Ok(())
+	}
+
+	///whether any of the contents of `w` (not counting `w` itself) have comments that the printers would need to put on lines of their own
+	fn comments_within<S>(&self, w:&Wood<S>)-> bool {
+		let b = match *w { Branchv(ref b) if !self.is_empty()=> b, _=> return false };
+		//an explicit stack rather than recursion, so that deep trees don't overflow
+		let mut stack = vec![b.v.iter()];
+		while let Some(contents) = stack.last_mut() {
+			match contents.next() {
+				Some(c)=> {
+					if !c.comments().is_empty() { return true; }
+					if let Branchv(ref cb) = *c { stack.push(cb.v.iter()); }
+				}
+				None=> { stack.pop(); }
+			}
+		}
+		false
+	}
+	///whether `w` itself has comments that the printers would need to put on lines of their own. The printers only look within branches that are short enough to go on one line, so that a deep tree isn't searched once for every level of it
+	fn has_comments<S>(&self, w:&Wood<S>)-> bool {
+		!self.is_empty() && !w.comments().is_empty()
+	}
+	fn print_comments<S, W:Write>(&self, w:&Wood<S>, indent:&str, indent_depth:usize, out:&mut W)-> fmt::Result {
+		if !self.is_empty() {
+			for c in w.comments() { self.print_comment(c, indent, indent_depth, out)?; }
+		}
+		Ok(())
+	}
+}
+
+///Bounds on what the parsers will read, for when the text comes from somewhere that can't be trusted. A parse that would go beyond any of them fails with an `ErrorKind::LimitExceeded` error instead. Set them in the `limits` of `TermposeStyle` or `WoodslistStyle`. The default, `NO_LIMITS`, has none.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ParseLimits {
+	///how many branches deep woods can be nested, not counting the root branch that the multiline parsers put everything in
+	pub max_depth: usize,
+	///the length of the longest leaf, in bytes
+	pub max_leaf_len: usize,
+	///how many woods, leaves and branches, the parse can produce in all
+	pub max_nodes: usize,
+}
+
+pub const NO_LIMITS:ParseLimits = ParseLimits{ max_depth:usize::MAX, max_leaf_len:usize::MAX, max_nodes:usize::MAX };
+
+impl Default for ParseLimits {
+	fn default()-> Self { NO_LIMITS }
+}
+
+impl ParseLimits {
+	pub(crate) fn depth_error(&self, line:isize, column:isize, span:Option<Span>)-> Box<WoodError> {
+		limit_error(line, column, span, format!("this is nested deeper than the limit of {} branches", self.max_depth))
+	}
+	pub(crate) fn check_leaf<S:AsRef<str>>(&self, l:&Leaf<S>)-> Result<(), Box<WoodError>> {
+		if l.v.as_ref().len() > self.max_leaf_len {
+			Err(limit_error(l.line, l.column, l.span, format!("this leaf is longer than the limit of {} bytes", self.max_leaf_len)))
+		}else{ Ok(()) }
+	}
+	pub(crate) fn nodes_error(&self, line:isize, column:isize, span:Option<Span>)-> Box<WoodError> {
+		limit_error(line, column, span, format!("there are more woods than the limit of {}", self.max_nodes))
+	}
+	///counts another wood, failing at the position given if there are now too many
+	pub(crate) fn count_node(&self, nodes:&mut usize, line:isize, column:isize, span:Option<Span>)-> Result<(), Box<WoodError>> {
+		*nodes += 1;
+		if *nodes > self.max_nodes { Err(self.nodes_error(line, column, span)) }else{ Ok(()) }
+	}
+}
+
+fn limit_error(line:isize, column:isize, span:Option<Span>, msg:String)-> Box<WoodError> {
+	Box::new(WoodError{ line, column, span, kind:ErrorKind::LimitExceeded, path:Vec::new(), msg, cause:None })
+}
+
+///moves the end of the span up to `end`
+fn extend_span(span:&mut Option<Span>, end:usize){
+	if let Some(ref mut sp) = *span { sp.end = end; }
+}
+
+///the parsers only know where branches end when they're closed with a paren. This extends every branch's span to cover its contents.
+pub(crate) fn settle_branch_spans<S>(w:&mut Wood<S>){
+	struct Settler;
+	impl<S> WoodVisitorMut<S> for Settler {
+		fn leave_branch(&mut self, b:&mut Branch<S>){
+			//the contents have already been settled
+			let end = b.v.last().and_then(|c| c.span()).map(|sp| sp.end);
+			if let (Some(ref mut sp), Some(e)) = (&mut b.span, end) {
+				sp.end = sp.end.max(e);
+			}
+		}
+	}
+	w.visit_mut(&mut Settler);
+}
+
+///if the multiline parse found only one root term, returns just that term, otherwise returns the root branch
+fn unwrap_single_root<S>(t:Wood<S>)-> Wood<S> {
+	let mut l = assume_branch(t); //multiline parses only return branches
+	if l.v.len() == 1 {
+		yank_first(std::mem::take(&mut l.v)) //just confirmed it's there
+	}else{
+		//then the caller was wrong, it wasn't a single root term, so I guess, they get the whole Branch? Maybe this should be a WoodError... I dunno about that
+		Branchv(l)
+	}
+}
+
+///What the parsers read leaf contents into. Lets the same parsers produce either `Wood` or `WoodRef`.
+trait LeafStorage<'a>: AsRef<str> {
+	///an empty leaf that would begin at byte `at` of `source`
+	fn starting_at(source:&'a str, at:usize)-> Self;
+	///`c` was read from byte `at` of `source`, unless it was translated from something else (an escape, a crlf), in which case `source[at..]` wont start with `c`
+	fn push_from(&mut self, source:&'a str, at:usize, c:char);
+	fn clear(&mut self);
+}
+impl<'a> LeafStorage<'a> for String {
+	#[inline(always)]
+	fn starting_at(_source:&'a str, _at:usize)-> Self { String::new() }
+	#[inline(always)]
+	fn push_from(&mut self, _source:&'a str, _at:usize, c:char){ self.push(c); }
+	fn clear(&mut self){ String::clear(self); }
+}
+impl<'a> LeafStorage<'a> for Cow<'a, str> {
+	#[inline(always)]
+	fn starting_at(source:&'a str, at:usize)-> Self { Cow::Borrowed(&source[at..at]) }
+	#[inline(always)]
+	fn push_from(&mut self, source:&'a str, at:usize, c:char){
+		if let Cow::Borrowed(b) = *self {
+			//as long as every char pushed is the next char of the source, the leaf can remain a slice of it
+			let start = (b.as_ptr() as usize).wrapping_sub(source.as_ptr() as usize);
+			if start.wrapping_add(b.len()) == at {
+				let is_next = if c.is_ascii() { source.as_bytes().get(at) == Some(&(c as u8)) } else { source[at..].starts_with(c) };
+				if is_next {
+					//safe: b ends at `at`, so either b is empty and start == at, or b is a slice of source beginning at start, and we just confirmed that c is the char in source at `at`
+					*self = Cow::Borrowed(unsafe{ source.get_unchecked(start .. at + c.len_utf8()) });
+					return;
+				}
+			}
+		}
+		self.to_mut().push(c);
+	}
+	fn clear(&mut self){ *self = Cow::Borrowed(""); }
+}
+
+fn do_indent<W:Write>(indent:&str, indent_depth:usize, out:&mut W)-> fmt::Result {
+	for _ in 0..indent_depth { out.write_str(indent)?; }
+	Ok(())
 }
 
 // pub fn parse_nakedbranch<'a>(v:&'a str)-> Result<Wood, WoodError> {
@@ -92,6 +332,12 @@ pub use self::termpose_parser::*;
 mod woodslist_parser;
 pub use self::woodslist_parser::*;
 
+mod streaming;
+pub use self::streaming::*;
+
+mod serializer;
+pub use self::serializer::*;
+
 
 
 
@@ -99,18 +345,7 @@ pub use self::woodslist_parser::*;
 mod tests {
 	extern crate test;
 	use super::*;
-	use std::fs::File;
-	use std::io::Read;
-	use std::path::PathBuf;
-	
-	fn read_file_from_root(file_name:&str)-> String {
-		let mut d = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
-		d.push(file_name);
-		let mut ret = String::new();
-		let mut file = File::open(d).unwrap();
-		file.read_to_string(&mut ret).unwrap();
-		ret
-	}
+	use crate::test_util::read_file_from_root;
 	
 	
 	#[test]
@@ -204,6 +439,197 @@ mod tests {
 		assert_eq!(&w, &wiw);
 	}
 	
+	#[test]
+	fn borrowed_parses_agree_with_owned(){
+		let term = read_file_from_root("longterm.term");
+		assert_eq!(&parse_multiline_termpose(&term).unwrap(), &parse_multiline_termpose_borrowed(&term).unwrap().into_owned());
+		let sli = read_file_from_root("big curvy.sli");
+		assert_eq!(&parse_multiline_woodslist(&sli).unwrap(), &parse_multiline_woodslist_borrowed(&sli).unwrap().into_owned());
+		let escapey = "a \"b\\tc\" d\\ne \"\n  f\"";
+		assert_eq!(&parse_termpose(escapey).unwrap(), &parse_termpose_borrowed(escapey).unwrap().into_owned());
+		assert_eq!(&parse_woodslist(escapey).unwrap(), &parse_woodslist_borrowed(escapey).unwrap().into_owned());
+	}
+	
+	#[test]
+	fn borrowed_parses_only_copy_escaped_leaves(){
+		let is_borrowed = |w:&WoodRef|{ match *w { Leafv(Leaf{ v:Cow::Borrowed(_), .. })=> true, _=> false } };
+		let t = parse_termpose_borrowed("first \"se cond\" thi\\rd:fourth").unwrap();
+		let b = t.get_branch().unwrap();
+		assert!(is_borrowed(&b[0]));
+		assert!(is_borrowed(&b[1]));
+		assert_eq!("se cond", b[1].initial_str());
+		assert!(!is_borrowed(&b[2].head().unwrap()));
+		assert!(is_borrowed(t.seek_val("thi\rd").unwrap()));
+		let w = parse_woodslist_borrowed("(first \"se cond\") thi\\rd").unwrap();
+		assert!(is_borrowed(w.head().unwrap().second().unwrap()));
+		assert!(!is_borrowed(w.second().unwrap()));
+		assert_eq!("thi\rd", w.second().unwrap().initial_str());
+	}
+	
+	fn check_spans_agree_with_positions(w:&Wood, sm:&SourceMap, within:Span){
+		for c in w.contents() {
+			if let Some(sp) = c.span() {
+				assert!(within.start <= sp.start && sp.start <= sp.end && sp.end <= within.end, "{:?} wasn't within {:?}", sp, within);
+				assert_eq!(Some((c.line() as usize, c.col() as usize)), sm.start_line_col(sp), "at {}", c.to_string());
+				if c.is_branch() { check_spans_agree_with_positions(c, sm, sp); }
+			}else{
+				panic!("{} has no span", c.to_string());
+			}
+		}
+	}
+	
+	#[test]
+	fn spans_agree_with_line_and_column(){
+		let mut sm = SourceMap::new();
+		let tf = sm.add_file("longterm.term", read_file_from_root("longterm.term"));
+		let tw = sm.parse_multiline_termpose(tf).unwrap();
+		check_spans_agree_with_positions(&tw, &sm, tw.span().unwrap());
+		let wf = sm.add_file("big curvy.sli", windowsify(&read_file_from_root("big curvy.sli")));
+		let ww = sm.parse_multiline_woodslist(wf).unwrap();
+		check_spans_agree_with_positions(&ww, &sm, ww.span().unwrap());
+		assert_eq!(Some(wf), ww.head().unwrap().span().unwrap().file);
+	}
+	
+	#[test]
+	fn spans_cover_their_text(){
+		let mut sm = SourceMap::new();
+		let f = sm.add_file("a", "a:b \"c\\td\" (e f) g(h)\n  i\nj".into());
+		let g = sm.add_file("b", "(a \"b c\")\n(d)".into());
+		let w = sm.parse_multiline_termpose(f).unwrap();
+		let snip = |w:&Wood| sm.snippet(w.span().unwrap()).unwrap();
+		let line = w.head().unwrap();
+		assert_eq!("a:b \"c\\td\" (e f) g(h)\n  i", snip(line));
+		assert_eq!(vec!["a:b \"c\\td\" (e f) g(h)", "i"], line.contents().map(snip).collect::<Vec<_>>());
+		let items = line.head().unwrap().get_branch().unwrap();
+		assert_eq!(vec!["a:b", "\"c\\td\"", "(e f)", "g(h)"], items.iter().map(snip).collect::<Vec<_>>());
+		assert_eq!("g", snip(items[3].head().unwrap()));
+		assert_eq!(Some((3, 1)), sm.start_line_col(w.second().unwrap().span().unwrap()));
+		
+		let w = sm.parse_multiline_woodslist(g).unwrap();
+		assert_eq!(vec!["(a \"b c\")", "(d)"], w.contents().map(snip).collect::<Vec<_>>());
+		assert_eq!("\"b c\"", snip(w.head().unwrap().second().unwrap()));
+		let e = parse_woodslist("(a b\n  (c)").unwrap_err();
+		assert_eq!((1, 1, Some(Span::new(None, 0, 1))), (e.line, e.column, e.span));
+		let e = parse_termpose("a\n  b)").unwrap_err();
+		assert_eq!((2, 4), (e.line, e.column));
+	}
+	
+	#[test]
+	fn recovering_parse_agrees_when_there_are_no_errors(){
+		let term = read_file_from_root("longterm.term");
+		let r = parse_multiline_termpose_recovering(&term);
+		assert!(r.errors.is_empty());
+		assert!(r.recovered_spans().is_empty());
+		assert_eq!(&parse_multiline_termpose(&term).unwrap(), &r.wood);
+	}
+	
+	#[test]
+	fn recovering_parse_reports_every_error(){
+		let text = "server\n  port 80)\n    nested\n  host:\"a\\q\"\n  name x\n  bad\n weird\n  ok:1\ntop\nfine";
+		let r = parse_multiline_termpose_recovering(text);
+		assert_eq!(
+			vec![(2, 10, "unmatched paren"), (4, 11, "escape slash must be followed by a valid escape character code"), (7, 2, "inconsistent indentation")],
+			r.errors.iter().map(|e| (e.line, e.column, e.msg.as_str())).collect::<Vec<_>>());
+		assert_eq!(
+			&woods!(woods!("server", woods!("port", "80"), woods!("host", "a"), woods!("name", "x"), "bad"), "top", "fine"),
+			&r.wood);
+		let server = r.wood.head().unwrap();
+		let recovered = server.tail().map(|w| r.is_recovered(w)).collect::<Vec<_>>();
+		assert_eq!(vec![true, true, false, false], recovered);
+		assert!(!r.is_recovered(server));
+		assert!(!r.is_recovered(r.wood.second().unwrap()));
+		assert_eq!(" weird\n  ok:1\n", &text[r.recovered_spans()[2].start .. r.recovered_spans()[2].end]);
+	}
+	
+	#[test]
+	fn recovering_from_an_error_before_the_first_line(){
+		let style = commented_termpose(false);
+		let r = parse_multiline_termpose_style_recovering("/* a", style.clone());
+		assert_eq!((1, Some(&[][..])), (r.errors.len(), r.wood.get_branch()));
+		//there's nothing for the next line to be indented under, however deep it is
+		let r = parse_multiline_termpose_style_recovering("  /* a\n    b\n  c", style);
+		assert_eq!((1, &woods!("c")), (r.errors.len(), &r.wood));
+	}
+	
+	#[test]
+	fn recovering_parse_resumes_under_the_right_parent(){
+		//an error at the end of the text, and a resumed line that is a child of the line before the bad one
+		let r = parse_multiline_termpose_recovering("a\n\tb\n  c\n\td\ne f)");
+		assert_eq!(vec![3, 5], r.errors.iter().map(|e| e.line).collect::<Vec<_>>());
+		assert_eq!(&woods!(woods!("a", "b", "d"), woods!("e", "f")), &r.wood);
+		assert!(r.is_recovered(r.wood.second().unwrap()));
+	}
+	
+	fn commented_termpose(attach:bool)-> TermposeStyle {
+		TermposeStyle{ comments:CommentStyle{ line:Some("//".into()), block:Some(("/*".into(), "*/".into())), attach }, ..DEFAULT_STYLE.clone() }
+	}
+	fn commented_woodslist(attach:bool)-> WoodslistStyle {
+		WoodslistStyle{ comments:CommentStyle{ line:Some(";".into()), block:Some(("#|".into(), "|#".into())), attach }, ..DEFAULT_WOODSLIST_STYLE.clone() }
+	}
+
+	#[test]
+	fn comments_are_skipped(){
+		let text = "// a header\nserver /* inline */ port:80 // trailing\n  // shallow\n\t\t// deep\n  host \"a\"/* after a quote */(b)\n /* odd\n   indentation */ \n  doc \"\n    text\n   // too shallow to be content\n    // deep enough to be content\n  /* ends\n the string */ name//not a comment\nlast";
+		let w = parse_multiline_termpose_style(text, commented_termpose(false)).unwrap();
+		assert_eq!(
+			&woods!(
+				woods!(woods!("server", woods!("port", "80")), woods!("host", "a", woods!("b")), woods!("doc", "text\n// deep enough to be content"), woods!("name//not", "a", "comment")),
+				"last"),
+			&w);
+		//without comments in the style, they're just leaves
+		assert_eq!("//", parse_multiline_termpose("// a").unwrap().initial_str());
+
+		let text = "; a header\n(a #| inline\n |# b;not a comment\n  ; end\n  c)\n#||#";
+		let w = parse_multiline_woodslist_style(text, commented_woodslist(false)).unwrap();
+		assert_eq!(&woods!(woods!("a", "b;not", "a", "comment", "c")), &w);
+
+		let e = parse_termpose_style_unterminated();
+		assert_eq!((2, 3, "unterminated block comment"), (e.line, e.column, e.msg.as_str()));
+		let e = parse_multiline_woodslist_style("a\n #| b", commented_woodslist(false)).unwrap_err();
+		assert_eq!((2, 2, "unterminated block comment"), (e.line, e.column, e.msg.as_str()));
+	}
+	fn parse_termpose_style_unterminated()-> Box<WoodError> {
+		parse_multiline_termpose_style("a\n  /* b\n c", commented_termpose(false)).unwrap_err()
+	}
+
+	fn assert_same_comments(a:&Wood, b:&Wood){
+		assert_eq!(a.comments(), b.comments(), "at {}", a.to_string());
+		if let (Some(ac), Some(bc)) = (a.get_branch(), b.get_branch()) {
+			ac.iter().zip(bc).for_each(|(a, b)| assert_same_comments(a, b));
+		}
+	}
+
+	#[test]
+	fn attached_comments_print_back(){
+		let style = commented_termpose(true);
+		let text = "// about the server\n// (there's only one)\nserver port:80\n  /* where it is */ host \"a\" /* the name */ name\n  ports\n    80 // no, the next one\n    /*\n    multiline\n    */ 443\n/* nothing follows this */";
+		let w = parse_multiline_termpose_style(text, style.clone()).unwrap();
+		let server = w.head().unwrap();
+		assert_eq!(["about the server", "(there's only one)"], server.comments());
+		assert_eq!(["where it is"], server.seek("host").unwrap().comments());
+		assert_eq!(["the name"], server.seek("host").unwrap().tail().nth(1).unwrap().comments());
+		let ports = server.find("ports").unwrap();
+		let p443 = ports.tail().nth(1).unwrap();
+		assert_eq!(["no, the next one", "multiline"], p443.comments());
+		assert_eq!("443", p443.initial_str());
+
+		let printed = pretty_termpose_detail(&w, false, 2, 73, &style);
+		let reparsed = parse_termpose_style(&printed, style.clone()).unwrap();
+		assert_eq!(&w, &reparsed, "{}", printed);
+		assert_same_comments(&w, &reparsed);
+		//printers without comment markers leave them out
+		assert_eq!(&pretty_termpose(&w), &pretty_termpose_detail(&w, false, 2, 73, &DEFAULT_STYLE));
+		assert!(!pretty_termpose(&w).contains("about"));
+
+		let sstyle = commented_woodslist(true);
+		let w = parse_multiline_woodslist_style("; the list\n(a #| first |# b\n  (c ; of the list\n   d))", sstyle.clone()).unwrap();
+		assert_eq!(["first"], w.head().unwrap().second().unwrap().comments());
+		let printed = indented_woodslist_style(&w, false, 2, 73, &sstyle);
+		let reparsed = parse_woodslist_style(&printed, sstyle.clone()).unwrap();
+		assert_eq!(&w, &reparsed, "{}", printed);
+		assert_same_comments(&w, &reparsed);
+	}
+
 	fn windowsify(v:&str)-> String {
 		let mut out = String::new();
 		let mut vc = v.chars().peekable();
@@ -290,4 +716,274 @@ b\")
 		let w = parse_woodslist("aaa \"aa sdi \n  idj\" a").unwrap();
 		assert_eq!(&woods!("aaa", "aa sdi \n  idj", "a"), &w, "uh");
 	}
+	
+	#[test]
+	fn deep_nesting_doesnt_overflow() {
+		let depth = if cfg!(miri) { 1_000 } else { 300_000 };
+		let text = format!("{}a{}", "(".repeat(depth), ")".repeat(depth));
+		let w = parse_woodslist(&text).unwrap();
+		let copy = w.clone();
+		assert_eq!(w, copy);
+		drop(copy);
+		assert_eq!(depth, w.fold(&mut |_| 0, &mut |_, contents:Vec<usize>| 1 + contents[0]));
+		let streamed:Vec<Wood> = stream_woodslist(text.as_bytes()).collect::<Result<_, _>>().unwrap();
+		assert_eq!(streamed, vec![w.clone()]);
+		assert_eq!("a", w.initial_str());
+		assert_eq!(text, to_woodslist(&w));
+		assert_eq!(w, parse_woodslist(&indented_woodslist_detail(&w, false, 2, usize::MAX)).unwrap());
+		let e = parse_woodslist(&text[..text.len() - 1]).unwrap_err();
+		assert_eq!((ErrorKind::Syntax, 1, 1, "unmatched opening paren"), (e.kind, e.line, e.column, &e.msg[..]));
+		
+		let text = format!("a{}", ":a".repeat(depth));
+		let w = parse_termpose(&text).unwrap();
+		assert_eq!(w, w.clone());
+		let streamed:Vec<Wood> = stream_termpose(text.as_bytes()).collect::<Result<_, _>>().unwrap();
+		assert_eq!(streamed, vec![w.clone()]);
+		assert_eq!(w, parse_termpose(&pretty_termpose_detail(&w, false, 2, usize::MAX, &DEFAULT_STYLE)).unwrap());
+		
+		//with a line for every level, the indentation alone grows with the square of the depth, so the printers that break lines get a shallower tree
+		let depth = depth/50;
+		let w = parse_woodslist(&format!("{}a{}", "(".repeat(depth), ")".repeat(depth))).unwrap();
+		let commented = WoodslistStyle{ comments:CommentStyle{ line:Some(";".into()), ..NO_COMMENTS }, ..DEFAULT_WOODSLIST_STYLE.clone() };
+		assert_eq!(w, parse_woodslist(&indented_woodslist_style(&w, true, 1, 0, &commented)).unwrap());
+		assert_eq!(w, parse_termpose(&pretty_termpose_detail(&w, true, 1, 0, &DEFAULT_STYLE)).unwrap());
+	}
+	
+	#[test]
+	fn limits_are_enforced() {
+		let limits = |max_depth, max_leaf_len, max_nodes| ParseLimits{ max_depth, max_leaf_len, max_nodes };
+		let woodslist = |text:&str, limits|-> Result<Wood, Box<WoodError>> {
+			parse_multiline_woodslist_style(text, WoodslistStyle{ limits, ..DEFAULT_WOODSLIST_STYLE.clone() })
+		};
+		let termpose = |text:&str, limits|-> Result<Wood, Box<WoodError>> {
+			parse_multiline_termpose_style(text, TermposeStyle{ limits, ..DEFAULT_STYLE.clone() })
+		};
+		let located = |r:Result<Wood, Box<WoodError>>|{
+			let e = r.unwrap_err();
+			assert_eq!(ErrorKind::LimitExceeded, e.kind, "{}", e);
+			(e.line, e.column, e.msg)
+		};
+		
+		assert!(woodslist("a (b (c))", limits(2, 1, 5)).is_ok());
+		assert_eq!((1, 6, "this is nested deeper than the limit of 1 branches".to_string()), located(woodslist("a (b (c))", limits(1, 1, 5))));
+		assert_eq!((1, 6, "this leaf is longer than the limit of 1 bytes".to_string()), located(woodslist("a (b \"cc\")", limits(2, 1, 5))));
+		assert_eq!((1, 7, "there are more woods than the limit of 4".to_string()), located(woodslist("a (b (c))", limits(2, 1, 4))));
+		
+		let text = "a\n  b c:d\n  e";
+		assert!(termpose(text, limits(3, 1, 8)).is_ok());
+		assert_eq!((2, 5, "this is nested deeper than the limit of 2 branches".to_string()), located(termpose(text, limits(2, 1, 8))));
+		assert_eq!((3, 3, "there are more woods than the limit of 7".to_string()), located(termpose(text, limits(3, 1, 7))));
+		assert_eq!((1, 1, "this leaf is longer than the limit of 0 bytes".to_string()), located(termpose(text, limits(3, 0, 8))));
+		
+		//limits are hit while reading, so text past the point where one is exceeded, even malformed text, is never looked at
+		let long = format!("\"{}", "x".repeat(10000));
+		assert_eq!(ErrorKind::LimitExceeded, woodslist(&long, limits(2, 3, 5)).unwrap_err().kind);
+		assert_eq!(ErrorKind::LimitExceeded, termpose(&long, limits(2, 3, 5)).unwrap_err().kind);
+		let many = format!("{}(", "a\n".repeat(10000));
+		assert_eq!(3, located(woodslist(&many, limits(2, 1, 2))).0);
+		assert_eq!(3, located(termpose(&many, limits(2, 1, 2))).0);
+		
+		let r = parse_multiline_termpose_style_recovering(text, TermposeStyle{ limits:limits(2, 1, 8), ..DEFAULT_STYLE.clone() });
+		assert_eq!((1, Some(&[][..])), (r.errors.len(), r.wood.get_branch()));
+	}
+	
+	#[test]
+	fn a_line_of_one_open_term_is_that_term(){
+		//a line with just one thing on it is that thing, even when what's indented beneath goes into it
+		assert_eq!(woods!("a", "b"), parse_termpose("(\n  a\n  b").unwrap());
+		assert_eq!(woods!("x", woods!("a", "b")), parse_termpose("x\n  (a\n    b").unwrap());
+		assert_eq!(woods!("a", "b"), parse_termpose("a:\n  b").unwrap());
+		assert_eq!(woods!(woods!(woods!("a", "c")), "d"), parse_termpose("(a:\n  c\nd").unwrap());
+		//but a line with more on it is still a branch of all of it, followed by what's beneath
+		assert_eq!(woods!("a", woods!("b", "c")), parse_termpose("a b:\n  c").unwrap());
+	}
+	
+	#[test]
+	fn escapes(){
+		assert_eq!(Wood::from("a\u{1}😀\\\r"), parse_woodslist("a\\u{1}\\u{1F600}\\\\\\r").unwrap());
+		assert_eq!(Wood::from("😀 \u{7f}"), parse_termpose("\"\\u{1f600} \\u{7F}\"").unwrap());
+		for bad in ["\\u1", "\\u{}", "\\u{1234567}", "\\u{110000}", "\\u{d800}", "\\u{g}", "\\u{1"] {
+			let e = parse_woodslist(bad).unwrap_err();
+			assert_eq!("escape slash must be followed by a valid escape character code", e.msg, "{}", bad);
+			assert!(parse_termpose(bad).is_err(), "{}", bad);
+		}
+		
+		let w = woods!("", "\"quoted\"", "back\\slash\r", "\u{0}\u{85}", "(", ":a", "//b");
+		assert_eq!(r#"("" "\"quoted\"" back\\slash\r \u{0}\u{85} "(" :a //b)"#, to_woodslist(&w));
+		assert_eq!(r#""" "\"quoted\"" back\\slash\r \u{0}\u{85} "(" ":a" //b"#, pretty_termpose(&w));
+		assert_eq!(r#""" "\"quoted\"" back\\slash\r \u{0}\u{85} "(" ":a" "//b""#, pretty_termpose_detail(&w, false, 2, 73, &commented_termpose(false)));
+	}
+	
+	use ::proptest::prelude::*;
+	use crate::proptest::{text, wood};
+	
+	fn column_limit()-> impl Strategy<Value=usize> {
+		prop_oneof![Just(0), 1..16usize, Just(73)]
+	}
+	
+	fn reads_back(w:&Wood, printed:&str, reread:Result<Wood, Box<WoodError>>)-> Result<Wood, TestCaseError> {
+		match reread {
+			Ok(r)=> {
+				prop_assert!(w == &r, "{:?} was read back as {}", printed, to_woodslist(&r));
+				Ok(r)
+			}
+			Err(e)=> Err(TestCaseError::fail(format!("{:?} couldn't be read back: {}", printed, e))),
+		}
+	}
+	
+	fn same_error(a:&WoodError, b:&WoodError)-> Result<(), TestCaseError> {
+		prop_assert_eq!((a.line, a.column, &a.msg), (b.line, b.column, &b.msg));
+		Ok(())
+	}
+	
+	///what the limits come to when checked against a parse that's already been made: fails at the first wood, in document order, that goes beyond them
+	fn check_limits(limits:&ParseLimits, root:&Wood)-> Result<(), Box<WoodError>> {
+		let mut nodes = 0;
+		let mut pending:Vec<(usize, &Wood)> = root.get_branch().unwrap_or(&[]).iter().rev().map(|c| (1, c)).collect();
+		while let Some((depth, w)) = pending.pop() {
+			limits.count_node(&mut nodes, w.line(), w.col(), w.span())?;
+			match *w {
+				Branchv(ref b)=> {
+					if depth > limits.max_depth { return Err(limits.depth_error(b.line, b.column, b.span)); }
+					pending.extend(b.v.iter().rev().map(|c| (depth + 1, c)));
+				}
+				Leafv(ref l)=> limits.check_leaf(l)?,
+			}
+		}
+		Ok(())
+	}
+	
+	///The parsers enforce the limits as they read, which has to come to the same thing as checking what the whole text comes to. `same_limit_error` compares the errors when both fail
+	fn limited_as_checked(limits:&ParseLimits, unlimited:Result<Wood, Box<WoodError>>, limited:Result<Wood, Box<WoodError>>, same_limit_error:impl Fn(&WoodError, &WoodError)-> Result<(), TestCaseError>)-> Result<(), TestCaseError> {
+		match (unlimited, limited) {
+			(Ok(w), limited)=> match (check_limits(limits, &w), limited) {
+				(Ok(()), Ok(l))=> prop_assert_eq!(w, l),
+				(Err(a), Err(b))=> same_limit_error(&a, &b)?,
+				(a, b)=> return Err(TestCaseError::fail(format!("checking the parse gave {:?}, but the limited parse gave {:?}", a, b.map(|w| w.to_string())))),
+			},
+			//a limit might be exceeded before the text goes wrong
+			(Err(a), Err(b))=> if b.kind != ErrorKind::LimitExceeded { same_error(&a, &b)? },
+			(Err(a), Ok(_))=> return Err(TestCaseError::fail(format!("the limited parse got past {}", a))),
+		}
+		Ok(())
+	}
+	
+	fn limits()-> impl Strategy<Value=ParseLimits> {
+		(0..6usize, 0..6usize, 0..40usize).prop_map(|(max_depth, max_leaf_len, max_nodes)| ParseLimits{ max_depth, max_leaf_len, max_nodes })
+	}
+	
+	///the printers put each line of a comment on a line of its own, which comes back as a comment of its own
+	fn same_comment_lines(a:&Wood, b:&Wood)-> Result<(), TestCaseError> {
+		let lines = |w:&Wood| w.comments().iter().flat_map(|c| c.split('\n').flat_map(|l| l.strip_suffix('\r').unwrap_or(l).split('\r')).map(str::trim)).map(String::from).collect::<Vec<_>>();
+		prop_assert_eq!(lines(a), lines(b), "at {}", a.to_string());
+		if let (Some(ac), Some(bc)) = (a.get_branch(), b.get_branch()) {
+			ac.iter().zip(bc).try_for_each(|(a, b)| same_comment_lines(a, b))?;
+		}
+		Ok(())
+	}
+	
+	proptest! {
+		//miri is thousands of times slower, and a few cases are enough to reach most of the parsers' unsafe code
+		#![proptest_config(ProptestConfig{ cases: if cfg!(miri) { 4 } else { 256 }, ..ProptestConfig::default() })]
+		
+		#[test]
+		fn printers_round_trip(w in wood(), column_limit in column_limit()) {
+			let printed = to_woodslist(&w);
+			reads_back(&w, &printed, parse_woodslist(&printed))?;
+			reads_back(&w, &printed, parse_woodslist_borrowed(&printed).map(|w| w.into_owned()))?;
+			let printed = indented_woodslist_detail(&w, false, 2, column_limit);
+			reads_back(&w, &printed, parse_woodslist(&printed))?;
+			let printed = pretty_termpose_detail(&w, true, 1, column_limit, &DEFAULT_STYLE);
+			reads_back(&w, &printed, parse_termpose(&printed))?;
+			reads_back(&w, &printed, parse_termpose_borrowed(&printed).map(|w| w.into_owned()))?;
+			//leaves that would begin comments are quoted when the style has comment markers
+			let style = commented_woodslist(false);
+			let printed = indented_woodslist_style(&w, false, 2, column_limit, &style);
+			reads_back(&w, &printed, parse_woodslist_style(&printed, style))?;
+			let style = commented_termpose(false);
+			let printed = pretty_termpose_detail(&w, false, 2, column_limit, &style);
+			reads_back(&w, &printed, parse_termpose_style(&printed, style))?;
+		}
+		
+		#[test]
+		fn serializers_round_trip(items in ::proptest::collection::vec(wood(), 0..4), column_limit in column_limit()) {
+			let mut serializer = WoodSerializer::termpose_detail(Vec::new(), false, 2, column_limit, DEFAULT_STYLE.clone());
+			items.iter().try_for_each(|w| serializer.emit(w)).unwrap();
+			let printed = serializer.into_inner();
+			let reread = stream_termpose(&printed[..]).collect::<Result<Vec<Wood>, _>>();
+			reads_back(&Wood::branch(items.clone()), &String::from_utf8_lossy(&printed), reread.map(Wood::branch))?;
+			
+			let mut serializer = WoodSerializer::woodslist_detail(Vec::new(), false, 2, column_limit, DEFAULT_WOODSLIST_STYLE.clone());
+			items.iter().try_for_each(|w| serializer.emit(w)).unwrap();
+			let printed = serializer.into_inner();
+			let reread = stream_woodslist(&printed[..]).collect::<Result<Vec<Wood>, _>>();
+			reads_back(&Wood::branch(items), &String::from_utf8_lossy(&printed), reread.map(Wood::branch))?;
+		}
+		
+		#[test]
+		fn limits_are_enforced_as_if_checked_afterwards(w in wood(), text in text(), limits in limits(), column_limit in column_limit()) {
+			//termpose doesn't know how deep a line's own branch is until the line is over, so when more than one limit is exceeded, it might not come to the one that a check in document order would first
+			let termpose = |text:&str| limited_as_checked(&limits, parse_multiline_termpose(text), parse_multiline_termpose_style(text, TermposeStyle{ limits, ..DEFAULT_STYLE.clone() }), |_, b|{
+				prop_assert_eq!(ErrorKind::LimitExceeded, b.kind, "{}", b);
+				Ok(())
+			});
+			let woodslist = |text:&str| limited_as_checked(&limits, parse_multiline_woodslist(text), parse_multiline_woodslist_style(text, WoodslistStyle{ limits, ..DEFAULT_WOODSLIST_STYLE.clone() }), same_error);
+			termpose(&text)?;
+			termpose(&pretty_termpose_detail(&w, false, 2, column_limit, &DEFAULT_STYLE))?;
+			woodslist(&text)?;
+			woodslist(&indented_woodslist_detail(&w, false, 2, column_limit))?;
+		}
+		
+		#[test]
+		fn parsed_termpose_prints_back(text in text()) {
+			let recovered = parse_multiline_termpose_recovering(&text);
+			match parse_multiline_termpose(&text) {
+				Ok(w)=> {
+					let printed = pretty_termpose(&w);
+					reads_back(&w, &printed, parse_termpose(&printed))?;
+					reads_back(&w, &text, parse_multiline_termpose_borrowed(&text).map(|w| w.into_owned()))?;
+					reads_back(&w, &text, stream_termpose(text.as_bytes()).collect::<Result<Vec<Wood>, _>>().map(Wood::branch))?;
+					prop_assert!(recovered.errors.is_empty());
+					reads_back(&w, &text, Ok(recovered.wood))?;
+					let file = crate::termpose::cst::parse(&text).unwrap();
+					prop_assert_eq!(&text, &file.to_string());
+					reads_back(&w, &text, file.lower())?;
+				}
+				Err(e)=> {
+					same_error(&e, &recovered.errors[0])?;
+					same_error(&e, &stream_termpose(text.as_bytes()).find_map(Result::err).unwrap())?;
+					//the cst doesn't always place its errors where the parser does
+					prop_assert_eq!(&e.msg, &crate::termpose::cst::parse(&text).unwrap_err().msg);
+				}
+			}
+			let style = commented_termpose(true);
+			let streamed = TermposeStream::new(text.as_bytes(), style.clone()).collect::<Result<Vec<Wood>, _>>();
+			match parse_multiline_termpose_style(&text, style.clone()) {
+				Ok(w)=> {
+					let printed = pretty_termpose_detail(&w, false, 2, 73, &style);
+					let reread = reads_back(&w, &printed, parse_termpose_style(&printed, style))?;
+					same_comment_lines(&w, &reread)?;
+					let streamed = reads_back(&w, &text, streamed.map(Wood::branch))?;
+					same_comment_lines(&w, &streamed)?;
+				}
+				Err(e)=> same_error(&e, &streamed.unwrap_err())?,
+			}
+		}
+		
+		#[test]
+		fn parsed_woodslist_prints_back(text in text()) {
+			if let Ok(w) = parse_multiline_woodslist(&text) {
+				let printed = to_woodslist(&w);
+				reads_back(&w, &printed, parse_woodslist(&printed))?;
+				reads_back(&w, &text, parse_multiline_woodslist_borrowed(&text).map(|w| w.into_owned()))?;
+				reads_back(&w, &text, stream_woodslist(text.as_bytes()).collect::<Result<Vec<Wood>, _>>().map(Wood::branch))?;
+			}
+			let style = commented_woodslist(true);
+			if let Ok(w) = parse_multiline_woodslist_style(&text, style.clone()) {
+				let printed = indented_woodslist_style(&w, false, 2, 73, &style);
+				let reread = reads_back(&w, &printed, parse_woodslist_style(&printed, style))?;
+				same_comment_lines(&w, &reread)?;
+			}
+		}
+	}
 }
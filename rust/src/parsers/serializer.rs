@@ -0,0 +1,256 @@
+use super::*;
+use std::io;
+
+/// lets the printers, which write to a `fmt::Write`, write to an `io::Write`, holding on to the io error that `fmt::Error` can't carry
+pub(crate) struct IoAdapter<W> {
+    out: W,
+    error: Option<io::Error>,
+}
+impl<W: io::Write> Write for IoAdapter<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.out.write_all(s.as_bytes()).map_err(|e| {
+            self.error = Some(e);
+            fmt::Error
+        })
+    }
+}
+
+/// runs a printer into an `io::Write`
+pub(crate) fn write_through<W: io::Write>(
+    out: W,
+    print: impl FnOnce(&mut IoAdapter<W>) -> fmt::Result,
+) -> io::Result<()> {
+    let mut adapter = IoAdapter { out, error: None };
+    print(&mut adapter).map_err(|_| {
+        adapter
+            .error
+            .take()
+            .unwrap_or_else(|| io::Error::other("the printer failed"))
+    })
+}
+
+/// the printers begin every item with a newline, this drops the first one, so that output doesn't begin with a blank line
+struct NewlineDropped<'a, O> {
+    out: &'a mut O,
+    dropped: bool,
+}
+impl<O: Write> Write for NewlineDropped<'_, O> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if !self.dropped {
+            self.dropped = true;
+            self.out.write_str(s.strip_prefix('\n').unwrap_or(s))
+        } else {
+            self.out.write_str(s)
+        }
+    }
+}
+
+enum Format {
+    Termpose(TermposeStyle),
+    Woodslist(WoodslistStyle),
+}
+
+/// Writes root items out one at a time, so that a file of them can be produced without ever building a tree of all of them. Each item is printed the way the indenting printers would print it, followed by a newline. The output reads back with `parse_multiline_termpose` or `parse_multiline_woodslist`, or item by item with `stream_termpose` or `stream_woodslist`.
+///
+/// Like the other writing functions, the serializer writes in many small pieces, so files and sockets should be wrapped in a `BufWriter`.
+/// ```
+/// let mut out = Vec::new();
+/// let mut serializer = wood::WoodSerializer::termpose(&mut out);
+/// for i in 0..3 {
+///     serializer.emit(&wood::woods!("item", i.to_string())).unwrap();
+/// }
+/// assert_eq!("item 0\nitem 1\nitem 2\n", String::from_utf8(out).unwrap());
+/// ```
+pub struct WoodSerializer<W> {
+    out: W,
+    format: Format,
+    indent: Cow<'static, str>,
+    column_limit: usize,
+}
+
+impl<W: io::Write> WoodSerializer<W> {
+    /// Indents and breaks lines the way `pretty_termpose_detail` does. Any comments the woods have are written out if the style has comment markers.
+    pub fn termpose_detail(
+        out: W,
+        indent_is_tab: bool,
+        tab_size: usize,
+        column_limit: usize,
+        style: TermposeStyle,
+    ) -> Self {
+        WoodSerializer {
+            out,
+            format: Format::Termpose(style),
+            indent: indent_str(indent_is_tab, tab_size),
+            column_limit,
+        }
+    }
+    /// `WoodSerializer::termpose_detail(out, false, 2, 73, DEFAULT_STYLE.clone())`
+    pub fn termpose(out: W) -> Self {
+        Self::termpose_detail(out, false, 2, 73, DEFAULT_STYLE.clone())
+    }
+    /// Indents and breaks lines the way `indented_woodslist_style` does
+    pub fn woodslist_detail(
+        out: W,
+        indent_is_tab: bool,
+        tab_size: usize,
+        column_limit: usize,
+        style: WoodslistStyle,
+    ) -> Self {
+        WoodSerializer {
+            out,
+            format: Format::Woodslist(style),
+            indent: indent_str(indent_is_tab, tab_size),
+            column_limit,
+        }
+    }
+    /// `WoodSerializer::woodslist_detail(out, false, 2, 73, DEFAULT_WOODSLIST_STYLE.clone())`
+    pub fn woodslist(out: W) -> Self {
+        Self::woodslist_detail(out, false, 2, 73, DEFAULT_WOODSLIST_STYLE.clone())
+    }
+
+    /// writes `w` out as the next root item
+    pub fn emit(&mut self, w: &Wood) -> io::Result<()> {
+        let WoodSerializer {
+            ref mut out,
+            ref format,
+            ref indent,
+            column_limit,
+        } = *self;
+        write_through(out, |out| {
+            let mut out = NewlineDropped {
+                out,
+                dropped: false,
+            };
+            match *format {
+                Format::Termpose(ref style) => {
+                    do_termpose_stringification(w, indent, 0, column_limit, &mut out, style)?
+                }
+                Format::Woodslist(ref style) => do_woodslist_stringification(
+                    w,
+                    indent,
+                    0,
+                    column_limit,
+                    &mut out,
+                    &style.comments,
+                )?,
+            }
+            out.write_char('\n')
+        })
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.out
+    }
+
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::read_file_from_root;
+
+    fn serialize(items: &[Wood], mut serializer: WoodSerializer<Vec<u8>>) -> String {
+        for w in items {
+            serializer.emit(w).unwrap();
+        }
+        String::from_utf8(serializer.into_inner()).unwrap()
+    }
+
+    /// accepts a few bytes, then fails
+    struct Full(usize);
+    impl io::Write for Full {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            if self.0 == 0 {
+                return Err(io::Error::new(io::ErrorKind::WriteZero, "full"));
+            }
+            let n = buf.len().min(self.0);
+            self.0 -= n;
+            Ok(n)
+        }
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn serialized_items_read_back() {
+        let whole = parse_multiline_termpose(&read_file_from_root("longterm.term")).unwrap();
+        let items = whole.get_branch().unwrap();
+        let text = serialize(items, WoodSerializer::termpose(Vec::new()));
+        assert!(text.ends_with('\n') && !text.starts_with('\n'));
+        assert_eq!(whole, parse_multiline_termpose(&text).unwrap(), "{}", text);
+        let streamed: Vec<Wood> = stream_termpose(text.as_bytes())
+            .map(|w| w.unwrap())
+            .collect();
+        assert_eq!(items, &streamed[..]);
+
+        let whole = parse_multiline_woodslist(&read_file_from_root("big curvy.sli")).unwrap();
+        let items = whole.get_branch().unwrap();
+        let text = serialize(
+            items,
+            WoodSerializer::woodslist_detail(
+                Vec::new(),
+                true,
+                4,
+                40,
+                DEFAULT_WOODSLIST_STYLE.clone(),
+            ),
+        );
+        assert_eq!(whole, parse_multiline_woodslist(&text).unwrap(), "{}", text);
+    }
+
+    #[test]
+    fn serializer_writes_comments() {
+        let style = TermposeStyle {
+            comments: CommentStyle {
+                line: Some("//".into()),
+                block: None,
+                attach: true,
+            },
+            ..DEFAULT_STYLE.clone()
+        };
+        let whole = parse_multiline_termpose_style(
+            "// first\na b\n// second\n// of two\nc\n  d",
+            style.clone(),
+        )
+        .unwrap();
+        let text = serialize(
+            whole.get_branch().unwrap(),
+            WoodSerializer::termpose_detail(Vec::new(), false, 2, 73, style),
+        );
+        assert_eq!("// first\na b\n// second\n// of two\nc d\n", text);
+    }
+
+    #[test]
+    fn writers_write_what_the_string_printers_return() {
+        let w = parse_multiline_termpose(&read_file_from_root("tests.term")).unwrap();
+        let mut out = Vec::new();
+        write_termpose(&w, &mut out).unwrap();
+        assert_eq!(pretty_termpose(&w).as_bytes(), &out[..]);
+        let mut out = Vec::new();
+        write_indented_woodslist(&w, &mut out).unwrap();
+        assert_eq!(indented_woodslist(&w).as_bytes(), &out[..]);
+        let mut out = Vec::new();
+        write_woodslist(&w, &mut out).unwrap();
+        assert_eq!(to_woodslist(&w).as_bytes(), &out[..]);
+    }
+
+    #[test]
+    fn io_errors_come_through() {
+        let w = parse_multiline_termpose(&read_file_from_root("tests.term")).unwrap();
+        let e = write_termpose(&w, Full(10)).unwrap_err();
+        assert_eq!(io::ErrorKind::WriteZero, e.kind());
+        let mut serializer = WoodSerializer::woodslist(Full(3));
+        assert_eq!(
+            io::ErrorKind::WriteZero,
+            serializer.emit(&w).unwrap_err().kind()
+        );
+    }
+}
@@ -0,0 +1,814 @@
+use super::*;
+use std::{
+    collections::VecDeque,
+    io::{BufRead, BufReader, Read},
+};
+
+/// a point in the stream: the number of lines before it, its column (0-based) and its byte offset
+#[derive(Clone, Copy)]
+struct Position {
+    line: isize,
+    column: isize,
+    offset: usize,
+}
+impl Position {
+    const START: Position = Position {
+        line: 0,
+        column: 0,
+        offset: 0,
+    };
+    fn after(self, text: &str) -> Position {
+        let starts = line_starts(text);
+        let last_line_start = starts[starts.len() - 1];
+        let columns = text[last_line_start..].chars().count() as isize;
+        Position {
+            line: self.line + starts.len() as isize - 1,
+            column: if starts.len() > 1 {
+                columns
+            } else {
+                self.column + columns
+            },
+            offset: self.offset + text.len(),
+        }
+    }
+    /// moves a position in a chunk of text that began here to where it is in the whole stream
+    fn relocate(self, line: &mut isize, column: &mut isize, span: &mut Option<Span>) {
+        if *line >= 0 {
+            if *line == 1 {
+                *column += self.column;
+            }
+            *line += self.line;
+        }
+        if let Some(ref mut sp) = *span {
+            sp.start += self.offset;
+            sp.end += self.offset;
+        }
+    }
+    fn relocate_wood<S>(self, w: &mut Wood<S>) {
+        struct Relocating(Position);
+        impl<S> WoodVisitorMut<S> for Relocating {
+            fn leave_branch(&mut self, b: &mut Branch<S>) {
+                self.0.relocate(&mut b.line, &mut b.column, &mut b.span);
+            }
+            fn visit_leaf(&mut self, l: &mut Leaf<S>) {
+                self.0.relocate(&mut l.line, &mut l.column, &mut l.span);
+            }
+        }
+        //visit_mut doesn't recurse, so this is fine for trees of any depth
+        w.visit_mut(&mut Relocating(self));
+    }
+}
+
+/// The parts shared by both streams: reading lines, and handing out the woods parsed from each chunk one at a time
+struct Lines<R> {
+    reader: R,
+    /// the position of the beginning of the next line
+    at: Position,
+    ready: VecDeque<Result<Wood, Box<WoodError>>>,
+    /// a read error, to be given after whatever was read before it
+    failed: Option<Box<WoodError>>,
+    done: bool,
+    /// what was read after a lone `\r`, which ends a line as well as a `\n` does
+    after_cr: String,
+}
+impl<R: BufRead> Lines<R> {
+    fn new(reader: R) -> Self {
+        Lines {
+            reader,
+            at: Position::START,
+            ready: VecDeque::new(),
+            failed: None,
+            done: false,
+            after_cr: String::new(),
+        }
+    }
+    /// the next line, including its line ending, and where it began. None at the end of the input, or after a read error
+    fn next_line(&mut self) -> Option<(String, Position)> {
+        let mut line = std::mem::take(&mut self.after_cr);
+        if line.is_empty() {
+            match self.reader.read_line(&mut line) {
+                Ok(0) => return None,
+                Ok(_) => {}
+                Err(e) => {
+                    self.failed = Some(Box::new(WoodError {
+                        line: -1,
+                        column: -1,
+                        span: None,
+                        kind: ErrorKind::Other,
+                        path: Vec::new(),
+                        msg: "couldn't read the input".into(),
+                        cause: Some(Box::new(e)),
+                    }));
+                    return None;
+                }
+            }
+        }
+        let bytes = line.as_bytes();
+        if let Some(cr) =
+            (0..bytes.len()).find(|&i| bytes[i] == b'\r' && bytes.get(i + 1) != Some(&b'\n'))
+        {
+            self.after_cr = line.split_off(cr + 1);
+        }
+        let start = self.at;
+        self.at = start.after(&line);
+        Some((line, start))
+    }
+    /// queues the root level woods of a chunk's parse, with their positions in the whole stream
+    fn queue(&mut self, parsed: Result<Wood, Box<WoodError>>, start: Position) {
+        match parsed {
+            Ok(Branchv(mut b)) => {
+                for mut w in std::mem::take(&mut b.v) {
+                    start.relocate_wood(&mut w);
+                    self.ready.push_back(Ok(w));
+                }
+            }
+            Ok(w) => self.ready.push_back(Ok(w)), //multiline parses only produce branches
+            Err(mut e) => {
+                start.relocate(&mut e.line, &mut e.column, &mut e.span);
+                self.ready.push_back(Err(e));
+            }
+        }
+    }
+}
+
+/// The text that has been read but not yet parsed
+struct Chunk {
+    text: String,
+    start: Position,
+}
+impl Chunk {
+    fn new() -> Self {
+        Chunk {
+            text: String::new(),
+            start: Position::START,
+        }
+    }
+    fn push(&mut self, line: &str, at: Position) {
+        if self.text.is_empty() {
+            self.start = at;
+        }
+        self.text.push_str(line);
+    }
+    fn take(&mut self) -> Chunk {
+        std::mem::replace(self, Chunk::new())
+    }
+}
+
+/// The modes of the termpose parser, as far as the stream needs to follow them
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum TermposeMode {
+    Beginning,
+    Indentation,
+    Term,
+    AfterThing,
+    Leaf,
+    Quoted,
+    MultilineStart,
+    MultilineIndentation,
+    MultilineContent,
+}
+
+type CharIndices<'a> = std::iter::Peekable<std::str::CharIndices<'a>>;
+
+fn skip_to(cs: &mut CharIndices, end: usize) {
+    while cs.peek().is_some_and(|&(j, _)| j < end) {
+        cs.next();
+    }
+}
+
+/// Where the termpose lexer is, between lines. Only as much of the parser's state is kept as it takes to tell where the root items begin.
+struct TermposeLexer {
+    mode: TermposeMode,
+    in_block_comment: bool,
+    /// the offset in the stream of the beginning of the line that the block comment that's open began on
+    block_comment_line: usize,
+    /// the indentation read so far on the line that a term or multiline string content is about to begin on
+    indentation: String,
+    /// whether a comment has ended the indentation, as the parser's `indentation_end` does
+    indentation_ended: bool,
+    /// the indentation of the line the terms being read began on
+    line_indent: String,
+    multiline_indent: String,
+    /// whether the quoted leaf being read has had nothing but whitespace in it yet, so that a line ending would begin a multiline string
+    quoted_blank: bool,
+}
+
+impl TermposeLexer {
+    fn new() -> Self {
+        TermposeLexer {
+            mode: TermposeMode::Beginning,
+            in_block_comment: false,
+            block_comment_line: 0,
+            indentation: String::new(),
+            indentation_ended: false,
+            line_indent: String::new(),
+            multiline_indent: String::new(),
+            quoted_blank: false,
+        }
+    }
+
+    fn begin_indentation(&mut self, mode: TermposeMode) {
+        self.mode = mode;
+        self.indentation.clear();
+        self.indentation_ended = false;
+    }
+
+    /// skips over the comment at `i`, if there is one. `comment_line` is what `block_comment_line` becomes if it's left open.
+    fn skip_comment(
+        &mut self,
+        comments: &CommentStyle,
+        line: &str,
+        comment_line: usize,
+        i: usize,
+        cs: &mut CharIndices,
+    ) -> bool {
+        match comments.comment_at(&line[i..]) {
+            Some(comment) => {
+                if comment.closed {
+                    skip_to(cs, i + comment.len);
+                } else {
+                    self.in_block_comment = true;
+                    self.block_comment_line = comment_line;
+                    skip_to(cs, line.len());
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// reads an escape, so that what it escapes isn't taken for anything else
+    fn read_escape(&mut self, cs: &mut CharIndices) {
+        let escaped = escaped_char(|| cs.next().map(|(_, c)| c));
+        if self.mode == TermposeMode::Quoted {
+            self.quoted_blank &= escaped.is_some_and(is_whitespace);
+        }
+    }
+
+    /// a new line of terms, at the indentation that's been read. Returns whether it's a root item.
+    fn start_line(&mut self, c: char, style: &TermposeStyle, cs: &mut CharIndices) -> bool {
+        std::mem::swap(&mut self.line_indent, &mut self.indentation);
+        self.begin_indentation(self.mode);
+        self.start_reading_thing(c, style, cs);
+        self.line_indent.is_empty()
+    }
+
+    fn start_reading_thing(&mut self, c: char, style: &TermposeStyle, cs: &mut CharIndices) {
+        self.mode = match c {
+            c if c == style.close => TermposeMode::AfterThing,
+            c if c == style.open || c == style.pairing => TermposeMode::Term,
+            '"' => self.begin_quoted_leaf(),
+            _ => self.begin_leaf(c, cs),
+        }
+    }
+
+    fn begin_leaf(&mut self, c: char, cs: &mut CharIndices) -> TermposeMode {
+        if c == '\\' {
+            self.read_escape(cs);
+        }
+        TermposeMode::Leaf
+    }
+
+    fn begin_quoted_leaf(&mut self) -> TermposeMode {
+        self.quoted_blank = true;
+        TermposeMode::Quoted
+    }
+
+    /// follows the lexer through a line that begins at `offset` in the stream, the way the parser would. Returns whether a root item begins on it.
+    fn scan_line(&mut self, line: &str, offset: usize, style: &TermposeStyle) -> bool {
+        use TermposeMode::*;
+        let comments = &style.comments;
+        let mut root_item = false;
+        //comments that follow on from one another count as one, which began with the first
+        let offset = if self.in_block_comment {
+            self.block_comment_line
+        } else {
+            offset
+        };
+        let mut cs = line.char_indices().peekable();
+        while let Some((i, c)) = cs.next() {
+            if self.in_block_comment {
+                let (_, close) = comments.block_markers().unwrap(); //can't be in one otherwise
+                match line[i..].find(close) {
+                    Some(e) => {
+                        self.in_block_comment = false;
+                        skip_to(&mut cs, i + e + close.len());
+                    }
+                    None => break,
+                }
+                continue;
+            }
+            let c = if c == '\r' {
+                if cs.peek().is_some_and(|&(_, n)| n == '\n') {
+                    cs.next();
+                }
+                '\n'
+            } else {
+                c
+            };
+            match self.mode {
+                Beginning => match c {
+                    ' ' | '\t' | '\n' => {}
+                    _ => {
+                        if !self.skip_comment(comments, line, offset, i, &mut cs) {
+                            //the indentation of the first line doesn't matter
+                            self.indentation.clear();
+                            root_item = self.start_line(c, style, &mut cs);
+                        }
+                    }
+                },
+                Indentation | MultilineStart | MultilineIndentation => match c {
+                    ' ' | '\t' => {
+                        if !self.indentation_ended {
+                            self.indentation.push(c);
+                            if self.mode == MultilineIndentation
+                                && self.indentation.len() == self.multiline_indent.len()
+                            {
+                                //the content goes on from here
+                                self.mode = MultilineContent;
+                            }
+                        }
+                    }
+                    '\n' => self.begin_indentation(self.mode),
+                    _ => {
+                        let is_content = self.mode == MultilineStart
+                            && self.indentation.len() > self.line_indent.len()
+                            && self.indentation.starts_with(&self.line_indent[..]);
+                        if is_content {
+                            self.multiline_indent = std::mem::take(&mut self.indentation);
+                            self.mode = MultilineContent;
+                        } else if self.skip_comment(comments, line, offset, i, &mut cs) {
+                            self.indentation_ended = true;
+                        } else {
+                            root_item = self.start_line(c, style, &mut cs);
+                        }
+                    }
+                },
+                MultilineContent => {
+                    if c == '\n' {
+                        self.begin_indentation(MultilineIndentation);
+                    }
+                }
+                Term | AfterThing => match c {
+                    ' ' | '\t' => self.mode = Term,
+                    '\n' => self.begin_indentation(Indentation),
+                    '"' if self.mode == AfterThing => self.mode = self.begin_quoted_leaf(),
+                    c if c == style.pairing => self.mode = Term,
+                    c if self.mode == AfterThing && c == style.open => self.mode = Term,
+                    c if self.mode == AfterThing && c == style.close => {}
+                    _ => {
+                        if self.skip_comment(comments, line, offset, i, &mut cs) {
+                            self.mode = Term;
+                        } else if self.mode == AfterThing {
+                            self.mode = self.begin_leaf(c, &mut cs);
+                        } else {
+                            self.start_reading_thing(c, style, &mut cs);
+                        }
+                    }
+                },
+                Leaf => match c {
+                    ' ' | '\t' => self.mode = Term,
+                    '\n' => self.begin_indentation(Indentation),
+                    '\\' => self.read_escape(&mut cs),
+                    '"' => self.mode = self.begin_quoted_leaf(),
+                    c if c == style.close => self.mode = AfterThing,
+                    c if c == style.pairing || c == style.open => self.mode = Term,
+                    _ => {}
+                },
+                Quoted => match c {
+                    '\n' => self.begin_indentation(if self.quoted_blank {
+                        MultilineStart
+                    } else {
+                        Indentation
+                    }),
+                    '\\' => self.read_escape(&mut cs),
+                    '"' => self.mode = AfterThing,
+                    _ => self.quoted_blank &= is_whitespace(c),
+                },
+            }
+        }
+        root_item
+    }
+}
+
+/// Reads termpose from a `BufRead` one root level wood at a time, so that memory use is bounded by the largest root item rather than by the size of the whole input. Positions are given relative to the whole input.
+///
+/// Text is buffered until the next line where a term begins with nothing before it but comments, where the previous root item must have ended.
+/// ```
+/// let text = "a b\n  c\nd\n";
+/// let mut woods = wood::stream_termpose(text.as_bytes());
+/// assert_eq!(wood::woods!(wood::woods!("a", "b"), "c"), woods.next().unwrap().unwrap());
+/// assert_eq!(3, woods.next().unwrap().unwrap().line());
+/// assert!(woods.next().is_none());
+/// ```
+pub struct TermposeStream<R> {
+    lines: Lines<R>,
+    style: TermposeStyle,
+    lexer: TermposeLexer,
+    /// the lines of the root item being read
+    chunk: Chunk,
+    /// whether the root item has begun, or the chunk is only comments and blank lines so far
+    item_begun: bool,
+    /// comments at the end of the last item, which go to the first wood of the next one
+    pending_comments: Vec<String>,
+}
+
+impl<R: BufRead> TermposeStream<R> {
+    pub fn new(reader: R, style: TermposeStyle) -> Self {
+        TermposeStream {
+            lines: Lines::new(reader),
+            style,
+            lexer: TermposeLexer::new(),
+            chunk: Chunk::new(),
+            item_begun: false,
+            pending_comments: Vec::new(),
+        }
+    }
+
+    fn parse_chunk(&mut self, chunk: Chunk) {
+        let parsed = parse_multiline_termpose_part(
+            &chunk.text,
+            self.style.clone(),
+            &mut self.pending_comments,
+        );
+        self.lines.queue(parsed, chunk.start);
+    }
+
+    /// reads lines until the root item being read is known to be over, and queues what it parses to. Returns false at the end of the input.
+    fn read_item(&mut self) -> bool {
+        while let Some((line, at)) = self.lines.next_line() {
+            let in_block_comment = self.lexer.in_block_comment;
+            let block_comment_line = self.lexer.block_comment_line;
+            let root_item = self.lexer.scan_line(&line, at.offset, &self.style);
+            if root_item && self.item_begun {
+                let mut item = self.chunk.take();
+                if in_block_comment {
+                    //the new item begins with the comment it was in
+                    let rest = item.text.split_off(block_comment_line - item.start.offset);
+                    self.chunk.push(&rest, item.start.after(&item.text));
+                }
+                self.chunk.push(&line, at);
+                self.parse_chunk(item);
+                return true;
+            }
+            self.item_begun |= root_item;
+            self.chunk.push(&line, at);
+        }
+        if !self.chunk.text.is_empty() {
+            let chunk = self.chunk.take();
+            self.parse_chunk(chunk);
+        }
+        false
+    }
+}
+
+impl<R: BufRead> Iterator for TermposeStream<R> {
+    type Item = Result<Wood, Box<WoodError>>;
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(r) = self.lines.ready.pop_front() {
+                return Some(r);
+            }
+            if self.lines.done {
+                return self.lines.failed.take().map(Err);
+            }
+            if !self.read_item() {
+                self.lines.done = true;
+            }
+        }
+    }
+}
+
+/// A `TermposeStream` in the default style, over any `Read`
+pub fn stream_termpose<R: Read>(reader: R) -> TermposeStream<BufReader<R>> {
+    TermposeStream::new(BufReader::new(reader), DEFAULT_STYLE.clone())
+}
+
+/// Where the woodslist lexer is, between lines
+#[derive(Default)]
+struct Depth {
+    parens: usize,
+    in_string: bool,
+    in_block_comment: bool,
+}
+
+/// Reads woodslist from a `BufRead` one root level wood at a time, so that memory use is bounded by the largest root item rather than by the size of the whole input. Positions are given relative to the whole input.
+/// ```
+/// let text = "(a b)\n(c\n  d) e\n";
+/// let woods: Vec<_> = wood::stream_woodslist(text.as_bytes()).map(|w| w.unwrap()).collect();
+/// assert_eq!(vec![wood::woods!("a", "b"), wood::woods!("c", "d"), "e".into()], woods);
+/// ```
+pub struct WoodslistStream<R> {
+    lines: Lines<R>,
+    style: WoodslistStyle,
+    chunk: Chunk,
+    depth: Depth,
+}
+
+impl<R: BufRead> WoodslistStream<R> {
+    pub fn new(reader: R, style: WoodslistStyle) -> Self {
+        WoodslistStream {
+            lines: Lines::new(reader),
+            style,
+            chunk: Chunk::new(),
+            depth: Depth::default(),
+        }
+    }
+
+    /// follows the lexer through some text the way the parser would, stopping just after the end of a root item. Returns how much of the text the item took up, if it ended in it.
+    fn scan(&mut self, text: &str) -> Option<usize> {
+        let d = &mut self.depth;
+        let mut cs = text.char_indices().peekable();
+        let skip_to = |cs: &mut std::iter::Peekable<std::str::CharIndices>, end: usize| {
+            while cs.peek().is_some_and(|&(j, _)| j < end) {
+                cs.next();
+            }
+        };
+        while let Some((i, c)) = cs.next() {
+            if d.in_block_comment {
+                let (_, close) = self.style.comments.block_markers().unwrap(); //can't be in one otherwise
+                if text[i..].starts_with(close) {
+                    d.in_block_comment = false;
+                    skip_to(&mut cs, i + close.len());
+                }
+                continue;
+            }
+            if d.in_string {
+                match c {
+                    '\\' => {
+                        cs.next();
+                    }
+                    '"' => {
+                        d.in_string = false;
+                        if d.parens == 0 {
+                            return Some(i + 1);
+                        }
+                    }
+                    _ => {}
+                }
+                continue;
+            }
+            match c {
+                ' ' | '\t' | '\n' | '\r' => {}
+                '(' => d.parens += 1,
+                ')' => {
+                    //an unmatched paren is an item of its own, so that the parser will report it
+                    d.parens = d.parens.saturating_sub(1);
+                    if d.parens == 0 {
+                        return Some(i + 1);
+                    }
+                }
+                _ if self.style.comments.comment_at(&text[i..]).is_some() => {
+                    let comment = self.style.comments.comment_at(&text[i..]).unwrap();
+                    if comment.closed {
+                        skip_to(&mut cs, i + comment.len);
+                    } else {
+                        d.in_block_comment = true;
+                        let (open, _) = self.style.comments.block_markers().unwrap();
+                        skip_to(&mut cs, i + open.len());
+                    }
+                }
+                '"' => d.in_string = true,
+                _ => {
+                    //the rest of the leaf
+                    let mut escaped = c == '\\';
+                    while let Some(&(_, nc)) = cs.peek() {
+                        if !escaped && matches!(nc, ' ' | '\t' | '\n' | '\r' | '"' | '(' | ')') {
+                            break;
+                        }
+                        escaped = !escaped && nc == '\\';
+                        cs.next();
+                    }
+                    if d.parens == 0 {
+                        return Some(cs.peek().map_or(text.len(), |&(j, _)| j));
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    fn parse_chunk(&mut self) {
+        let chunk = self.chunk.take();
+        let parsed = parse_multiline_woodslist_style(&chunk.text, self.style.clone());
+        self.lines.queue(parsed, chunk.start);
+    }
+
+    /// reads lines until a root item ends, and queues what it parses to. Returns false at the end of the input.
+    fn read_items(&mut self) -> bool {
+        while let Some((line, mut at)) = self.lines.next_line() {
+            let mut rest = &line[..];
+            let mut found = false;
+            while let Some(end) = self.scan(rest) {
+                self.chunk.push(&rest[..end], at);
+                at = at.after(&rest[..end]);
+                rest = &rest[end..];
+                self.parse_chunk();
+                found = true;
+            }
+            self.chunk.push(rest, at);
+            if found {
+                return true;
+            }
+        }
+        if !self.chunk.text.is_empty() {
+            self.parse_chunk();
+        }
+        false
+    }
+}
+
+impl<R: BufRead> Iterator for WoodslistStream<R> {
+    type Item = Result<Wood, Box<WoodError>>;
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(r) = self.lines.ready.pop_front() {
+                return Some(r);
+            }
+            if self.lines.done {
+                return self.lines.failed.take().map(Err);
+            }
+            if !self.read_items() {
+                self.lines.done = true;
+            }
+        }
+    }
+}
+
+/// A `WoodslistStream` in the default style, over any `Read`
+pub fn stream_woodslist<R: Read>(reader: R) -> WoodslistStream<BufReader<R>> {
+    WoodslistStream::new(BufReader::new(reader), DEFAULT_WOODSLIST_STYLE.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{assert_same_positions, read_file_from_root};
+
+    /// hands out a few bytes at a time, to make sure nothing depends on how the reads fall
+    struct Trickle<'a>(&'a [u8]);
+    impl<'a> Read for Trickle<'a> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let n = buf.len().min(3).min(self.0.len());
+            buf[..n].copy_from_slice(&self.0[..n]);
+            self.0 = &self.0[n..];
+            Ok(n)
+        }
+    }
+
+    /// an endless series of root items
+    struct Endless(usize);
+    impl Read for Endless {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let item = format!("item{}\n  child\n", self.0);
+            self.0 += 1;
+            let n = item.len().min(buf.len());
+            buf[..n].copy_from_slice(&item.as_bytes()[..n]);
+            Ok(n)
+        }
+    }
+
+    fn assert_streams_like_whole(whole: Wood, streamed: Vec<Result<Wood, Box<WoodError>>>) {
+        let streamed: Vec<Wood> = streamed.into_iter().map(|w| w.unwrap()).collect();
+        let whole = whole.get_branch().unwrap();
+        assert_eq!(whole, &streamed[..]);
+        whole
+            .iter()
+            .zip(&streamed)
+            .for_each(|(a, b)| assert_same_positions(a, b));
+    }
+
+    #[test]
+    fn streams_agree_with_whole_parses() {
+        for name in ["longterm.term", "tests.term", "shortterm.term"] {
+            let text = read_file_from_root(name);
+            for text in [
+                text.clone(),
+                text.replace('\n', "\r\n"),
+                text.replace('\n', "\r"),
+            ] {
+                let streamed = TermposeStream::new(
+                    BufReader::with_capacity(4, Trickle(text.as_bytes())),
+                    DEFAULT_STYLE.clone(),
+                );
+                assert_streams_like_whole(
+                    parse_multiline_termpose(&text).unwrap(),
+                    streamed.collect(),
+                );
+            }
+        }
+        //the blank lines at the end of a multiline string are part of it, though they come before the next item
+        let text = "a \"\n  b\n  \n\nc";
+        assert_streams_like_whole(
+            parse_multiline_termpose(text).unwrap(),
+            stream_termpose(text.as_bytes()).collect(),
+        );
+        assert_eq!(
+            woods!(woods!("a", "b\n"), "c"),
+            parse_multiline_termpose(text).unwrap()
+        );
+        let text = read_file_from_root("big curvy.sli");
+        let streamed = WoodslistStream::new(
+            BufReader::with_capacity(4, Trickle(text.as_bytes())),
+            DEFAULT_WOODSLIST_STYLE.clone(),
+        );
+        assert_streams_like_whole(
+            parse_multiline_woodslist(&text).unwrap(),
+            streamed.collect(),
+        );
+    }
+
+    #[test]
+    fn comments_go_with_the_next_item() {
+        let style = TermposeStyle {
+            comments: CommentStyle {
+                line: Some("//".into()),
+                block: Some(("/*".into(), "*/".into())),
+                attach: true,
+            },
+            ..DEFAULT_STYLE.clone()
+        };
+        let text = "a\n  b\n// about c\n\nc /* a comment\nthat looks like\nan item */ d\ne \"\n  multi\n  \n  line\nf";
+        let streamed: Vec<Wood> = TermposeStream::new(text.as_bytes(), style.clone())
+            .map(|w| w.unwrap())
+            .collect();
+        let whole = parse_multiline_termpose_style(text, style.clone()).unwrap();
+        assert_eq!(whole.get_branch().unwrap(), &streamed[..]);
+        assert_eq!(["about c"], streamed[1].comments());
+        assert_eq!("multi\n\nline", streamed[2].second().unwrap().initial_str());
+        //comments after the last wood of an item, and comments that run on into the line the next item begins on
+        for text in [
+            "(a /* about b */)\nb",
+            "a\n/* about\n*/ /* b\n*/ b",
+            "a \"\n/* about b */\n  not b\nb",
+        ] {
+            let streamed: Vec<Wood> = TermposeStream::new(text.as_bytes(), style.clone())
+                .map(|w| w.unwrap())
+                .collect();
+            let whole = parse_multiline_termpose_style(text, style.clone()).unwrap();
+            assert_eq!(whole.get_branch().unwrap(), &streamed[..]);
+            assert_eq!(whole.second().unwrap().comments(), streamed[1].comments());
+            assert!(!streamed[1].comments().is_empty());
+        }
+        //each line is only looked at once, however long the comment
+        let text = format!("a\n/*{}*/\nb", "\nnot an item".repeat(100_000));
+        let streamed: Vec<Wood> = TermposeStream::new(text.as_bytes(), style.clone())
+            .map(|w| w.unwrap())
+            .collect();
+        assert_eq!(vec![Wood::from("a"), "b".into()], streamed);
+
+        let style = WoodslistStyle {
+            comments: CommentStyle {
+                line: Some(";".into()),
+                block: Some(("#|".into(), "|#".into())),
+                attach: true,
+            },
+            ..DEFAULT_WOODSLIST_STYLE.clone()
+        };
+        let text = "(a \"b\n)\" c) ; about d\n#| still\n(about d) |#\nd\\\"e (f\n)";
+        let streamed: Vec<Wood> = WoodslistStream::new(text.as_bytes(), style.clone())
+            .map(|w| w.unwrap())
+            .collect();
+        assert_eq!(
+            parse_multiline_woodslist_style(text, style)
+                .unwrap()
+                .get_branch()
+                .unwrap(),
+            &streamed[..]
+        );
+        assert_eq!(["about d", "still\n(about d)"], streamed[1].comments());
+    }
+
+    #[test]
+    fn errors_are_positioned_in_the_whole_input_and_reading_continues() {
+        let mut s = stream_termpose("a\nb c)\nd".as_bytes());
+        assert_eq!(Some("a"), s.next().unwrap().unwrap().get_leaf());
+        let e = s.next().unwrap().unwrap_err();
+        assert_eq!(
+            (2, 4, Some(Span::new(None, 5, 6))),
+            (e.line, e.column, e.span)
+        );
+        assert_eq!(Some("d"), s.next().unwrap().unwrap().get_leaf());
+        assert!(s.next().is_none());
+
+        let mut s = stream_woodslist("(a)\n(b\n".as_bytes());
+        assert!(s.next().unwrap().is_ok());
+        assert_eq!(2, s.next().unwrap().unwrap_err().line);
+        assert!(s.next().is_none());
+
+        let mut s = stream_termpose(&[b'a', b'\n', 0xff, b'\n'][..]);
+        assert_eq!(Some("a"), s.next().unwrap().unwrap().get_leaf());
+        assert!(s.next().unwrap().is_err());
+        assert!(s.next().is_none());
+    }
+
+    #[test]
+    fn reads_no_further_than_it_needs_to() {
+        let items: Vec<Wood> = stream_termpose(Endless(0))
+            .take(3)
+            .map(|w| w.unwrap())
+            .collect();
+        assert_eq!(woods!("item2", "child"), items[2]);
+        assert_eq!(5, items[2].line());
+    }
+}
@@ -1,24 +1,34 @@
-use std::mem::replace;
+use std::{io, mem::replace, ptr::null};
 use super::*;
 
-struct TermposeParserState<'a>{
-	root: Wood,
+type Mode<'a, S> = fn(&mut TermposeParserState<'a, S>, Option<char>)-> Result<(), Box<WoodError>>;
+
+struct TermposeParserState<'a, S>{
+	source: &'a str,
+	file: Option<FileId>,
 	indent_stack: Vec<&'a str>,
-	indent_branch_stack: Vec<*mut Vec<Wood>>, //the branches corresponding to each indent level, into which new lines on that level are inserted
-	line_paren_stack: Vec<*mut Wood>,
+	indent_branch_stack: Vec<*mut Vec<Wood<S>>>, //the branches corresponding to each indent level, into which new lines on that level are inserted
+	indent_depths: Vec<usize>, //how deep each of the branches in indent_branch_stack is, for ParseLimits::max_depth. Only found when there's a limit
+	line_depth: usize, //how deep the line being read is, counting its own branch
+	line_undecided: bool, //whether the line's own branch might yet be collapsed away
+	nodes: usize, //how many woods have been made, for ParseLimits::max_nodes
+	quoted_blank: bool, //whether the quoted leaf being read has only had whitespace so far, which is dropped if the line ends there, beginning a multiline string
+	line_paren_stack: Vec<*mut Wood<S>>,
 	cur_char_ptr: *const u8,
 	//optimization: Consider making these three an untagged union, since only one is used at a time?:
 	stretch_reading_start: *const u8, //used when taking an indent
-	leaf_being_read_into: *mut String,
-	colon_receptacle: *mut Vec<Wood>,
-	last_completed_term_on_line: *mut Wood, //for attaching the next pairing
+	indentation_end: *const u8, //where the first comment on an indentation line began, if there was one. Comments don't count as indentation
+	leaf_being_read_into: *mut Leaf<S>,
+	colon_receptacle: *mut Vec<Wood<S>>,
+	last_completed_term_on_line: *mut Wood<S>, //for attaching the next pairing
 	multilines_indent: &'a str,
 	// previous_line_hanging_term: *mut Wood, //this is the term things will be inserted into if there's an indent.
 	iter: std::str::Chars<'a>,
 	line: isize,
 	column: isize,
-	mode: fn(&mut TermposeParserState<'a>, Option<char>)-> Result<(), Box<WoodError>>,
+	mode: Mode<'a, S>,
 	chosen_style: TermposeStyle,
+	pending_comments: Vec<String>, //comments read since the last wood was created, for attaching to the next one
 }
 
 #[derive(Clone)]
@@ -26,67 +36,211 @@ pub struct TermposeStyle {
 	pub open:char,
 	pub close:char,
 	pub pairing:char,
+	///The default style has no comments, so that nothing that used to be read as a leaf will be skipped. `//` line comments and `/* */` block comments suit termpose well.
+	pub comments:CommentStyle,
+	///The default has no limits. The printers ignore them.
+	pub limits:ParseLimits,
 }
 
-pub static DEFAULT_STYLE:TermposeStyle = TermposeStyle{ open:'(', close:')', pairing:':' };
+pub static DEFAULT_STYLE:TermposeStyle = TermposeStyle{ open:'(', close:')', pairing:':', comments:NO_COMMENTS, limits:NO_LIMITS };
 
-impl<'a> TermposeParserState<'a> {
+impl<'a, S:LeafStorage<'a>> TermposeParserState<'a, S> {
 	
 	fn style(&self)-> &TermposeStyle { &self.chosen_style }
 	
+	fn offset_of(&self, p:*const u8)-> usize { p as usize - self.source.as_ptr() as usize }
+	
+	///pushes the char most recently taken from iter, c, into the leaf being read. c may have been translated from the source text
+	fn push_char(&mut self, c:char)-> Result<(), Box<WoodError>> {
+		let at = self.offset_of(self.cur_char_ptr);
+		let end = self.offset_of(self.next_char_ptr());
+		let leaf = unsafe{&mut *self.leaf_being_read_into}; //safe: leaf_being_read_into must have been validated before this mode could have been entered
+		leaf.v.push_from(self.source, at, c);
+		extend_span(&mut leaf.span, end);
+		if self.quoted_blank {
+			//it might yet be dropped, so it isn't held to the limit until it can't be (see end_quoted_leaf)
+			if is_whitespace(c) { return Ok(()); }
+			self.quoted_blank = false;
+		}
+		self.style().limits.check_leaf(leaf)
+	}
+	///checks a quoted leaf that's over, in case it was all whitespace, which push_char doesn't check
+	fn end_quoted_leaf(&mut self)-> Result<(), Box<WoodError>> {
+		if !replace(&mut self.quoted_blank, false) { return Ok(()); }
+		self.style().limits.check_leaf(unsafe{&*self.leaf_being_read_into})
+	}
+	
+	///counts a wood that's just been made at the char just taken, failing if there are now too many. The line's own branch is made before anything else on it, but it's collapsed away if it ends up holding only one thing, so it isn't counted against the limit until it holds more.
+	fn count_node(&mut self)-> Result<(), Box<WoodError>> {
+		self.nodes += 1;
+		let line_term_may_go = self.line_undecided && match unsafe{&*self.line_paren_stack[0]} { Branchv(ref b)=> b.v.len() < 2, Leafv(_)=> false };
+		if self.nodes - line_term_may_go as usize > self.style().limits.max_nodes {
+			return Err(self.style().limits.nodes_error(self.line, self.column, self.span_from_here()));
+		}
+		Ok(())
+	}
+	
+	///fails early if the paren just pushed onto line_paren_stack is too deep. Each is within the one before it, so however the line's shape changes, it can't end up any shallower than this
+	fn check_paren_depth(&self)-> Result<(), Box<WoodError>> {
+		//the line's own branch might be collapsed away, so it isn't counted
+		if self.line_depth - 1 + (self.line_paren_stack.len() - 1) > self.style().limits.max_depth {
+			return Err(self.style().limits.depth_error(self.line, self.column, self.span_from_here()));
+		}
+		Ok(())
+	}
+	
+	///Checks the depth of the woods on the line that was read last, now that nothing more can change their shape, and finds how deep the branch is that any lines indented beneath it go into. The termpose parser doesn't know how deep a wood is until then, as pairings and indented lines can wrap what's already been read in another branch.
+	fn check_line_depth(&mut self)-> Result<(), Box<WoodError>> {
+		let max_depth = self.style().limits.max_depth;
+		if self.line_paren_stack.is_empty() || max_depth == usize::MAX { return Ok(()); }
+		let hanging: *const Vec<Wood<S>> = *get_back_mut(&mut self.indent_branch_stack);
+		let mut pending:Vec<(usize, &Wood<S>)> = vec![(self.line_depth, unsafe{&*self.line_paren_stack[0]})];
+		while let Some((depth, w)) = pending.pop() {
+			if let Branchv(ref b) = *w {
+				if depth > max_depth { return Err(self.style().limits.depth_error(b.line, b.column, b.span)); }
+				if std::ptr::eq(&b.v, hanging) { *get_back_mut(&mut self.indent_depths) = depth; }
+				pending.extend(b.v.iter().rev().map(|c| (depth + 1, c)));
+			}
+		}
+		Ok(())
+	}
+	
+	///a span beginning at the char most recently taken from iter
+	fn span_from_here(&self)-> Option<Span> {
+		Some(Span::new(self.file, self.offset_of(self.cur_char_ptr), self.offset_of(self.next_char_ptr())))
+	}
+	
 	fn a_fail(&self, message:String)-> Result<(), Box<WoodError>> { Err(Box::new(WoodError{
 		line: self.line,
 		column: self.column,
+		span: self.span_from_here(),
+		kind: ErrorKind::Syntax,
+		path: Vec::new(),
 		msg: message,
 		cause: None,
 	})) }
 	
-	fn mkbranch(&self)-> Wood { Branchv(Branch{ line:self.line, column:self.column, v:Vec::new() }) }
+	fn mkbranch(&mut self)-> Wood<S> { Branchv(Branch{ line:self.line, column:self.column, span:self.span_from_here(), comments:self.take_pending_comments(), v:Vec::new() }) }
+	fn mkleaf(&mut self, at:*const u8)-> Wood<S> { Leafv(Leaf{ line:self.line, column:self.column, span:self.span_from_here(), comments:self.take_pending_comments(), v:S::starting_at(self.source, self.offset_of(at)) }) }
+	
+	fn take_pending_comments(&mut self)-> Vec<String> {
+		std::mem::take(&mut self.pending_comments)
+	}
+	
+	///if a comment begins at the char just taken, skips over it, keeping its text if comments are being attached. Returns whether there was one.
+	fn skip_comment(&mut self)-> Result<bool, Box<WoodError>> {
+		let start = self.offset_of(self.cur_char_ptr);
+		let comment = match self.style().comments.comment_at(&self.source[start..]) {
+			Some(c)=> c,
+			None=> return Ok(false),
+		};
+		if !comment.closed {
+			self.a_fail(UNTERMINATED_BLOCK_COMMENT.into())?;
+		}
+		if self.style().comments.attach {
+			self.pending_comments.push(comment.text.trim().to_string());
+		}
+		while self.offset_of(self.next_char_ptr()) < start + comment.len {
+			self.move_char_ptr_and_update_line_col();
+		}
+		Ok(true)
+	}
+	
+	///skip_comment, for when the comment might be on a line that has only had indentation so far
+	fn skip_comment_in_indentation(&mut self)-> Result<bool, Box<WoodError>> {
+		let comment_start = self.cur_char_ptr;
+		let skipped = self.skip_comment()?;
+		if skipped && self.indentation_end.is_null() {
+			self.indentation_end = comment_start;
+		}
+		Ok(skipped)
+	}
+	
+	///the indentation of the line that's being read, which ends at the first comment on it, if there was one
+	fn indentation(&self)-> &'a str {
+		let end = if self.indentation_end.is_null() { self.cur_char_ptr }else{ self.indentation_end };
+		unsafe{str_from_bounds(self.stretch_reading_start, end)}
+	}
+	
+	fn begin_indentation(&mut self){
+		self.stretch_reading_start = self.next_char_ptr();
+		self.indentation_end = null();
+	}
 	
 	fn start_line(&mut self, c:char)-> Result<(), Box<WoodError>> {
-		let bin:*mut Vec<Wood> = *get_back_mut(&mut self.indent_branch_stack); //there is always at least root in the indent_branch_stack
+		self.check_line_depth()?;
+		let bin:*mut Vec<Wood<S>> = *get_back_mut(&mut self.indent_branch_stack); //there is always at least root in the indent_branch_stack
 		unsafe{
 			(*bin).push(self.mkbranch());
 		}
 		self.line_paren_stack.clear();
 		self.line_paren_stack.push(get_back_mut(unsafe{ &mut *bin }));
+		self.line_depth = *get_back_mut(&mut self.indent_depths) + 1;
+		self.line_undecided = true;
+		self.count_node()?;
 		self.last_completed_term_on_line = null_mut();
 		self.start_reading_thing(c)
 	}
 	
 	fn consider_collapsing_outer_branch_of_previous_line(&mut self){
-		let line_term: *mut Wood = self.line_paren_stack[0];
+		let line_term: *mut Wood<S> = self.line_paren_stack[0];
 		let line_branch_length:usize = assume_branch_mut(unsafe{ &mut*line_term }).v.len(); //line_term is always a branch
+		self.line_undecided = false;
 		if line_branch_length == 1 {
+			self.nodes -= 1;
 			unsafe{
 				replace_self(&mut*line_term, |l|{
-					let Branch{ v, .. } = assume_branch(l);
-					yank_first(v)
+					let mut b = assume_branch(l);
+					let comments = std::mem::take(&mut b.comments);
+					let mut only = yank_first(std::mem::take(&mut b.v));
+					if !comments.is_empty() { only.comments_mut().splice(0..0, comments); }
+					only
 				}) //safe: this_line has been proven to be a branch in assume_branch_mut, so assume_branch cannot panic
 			}
 		}
 	}
 	
-	fn take_hanging_branch_for_insert(&mut self)-> *mut Vec<Wood> {
+	fn take_hanging_branch_for_insert(&mut self)-> *mut Vec<Wood<S>> {
 		if self.colon_receptacle != null_mut() {
 			replace(&mut self.colon_receptacle, null_mut())
 		}else{
 			&mut unsafe{assume_branch_mut(&mut**get_back_mut(&mut self.line_paren_stack))}.v //safe; always something in parenstack, and it's always a branch
 		}
 	}
-	fn take_hanging_branch_for_new_line(&mut self)-> *mut Vec<Wood> {
+	///when the indented lines are going into a paren or pairing that's still open, and it's the only thing on the line, the line is just that thing, as it would be if nothing were indented beneath it
+	fn collapse_line_onto_hanging_term(&mut self){
+		let line_term: *mut Wood<S> = self.line_paren_stack[0];
+		let line_contents = &mut assume_branch_mut(unsafe{ &mut*line_term }).v; //line_term is always a branch
+		if line_contents.len() != 1 { return; }
+		let only: *mut Wood<S> = &mut line_contents[0];
+		//only is a branch, since the hanging branch is it or is within it
+		let only_contents: *mut Vec<Wood<S>> = &mut assume_branch_mut(unsafe{ &mut*only }).v;
+		self.consider_collapsing_outer_branch_of_previous_line();
+		//only has moved to where line_term was, and what pointed at it has to follow. What's within it hasn't moved
+		let moved_contents: *mut Vec<Wood<S>> = &mut assume_branch_mut(unsafe{ &mut*line_term }).v;
+		if self.colon_receptacle == only_contents { self.colon_receptacle = moved_contents; }
+		if self.line_paren_stack.len() > 1 && self.line_paren_stack[1] == only { self.line_paren_stack[1] = line_term; }
+		if self.last_completed_term_on_line == only { self.last_completed_term_on_line = line_term; }
+	}
+	fn take_hanging_branch_for_new_line(&mut self)-> Result<*mut Vec<Wood<S>>, Box<WoodError>> {
+		if !self.colon_receptacle.is_null() || self.line_paren_stack.len() > 1 {
+			self.collapse_line_onto_hanging_term();
+		}
 		if self.colon_receptacle != null_mut() {
-			replace(&mut self.colon_receptacle, null_mut())
+			Ok(replace(&mut self.colon_receptacle, null_mut()))
 		}else{
 			if self.line_paren_stack.len() > 1 { //then there's an open paren
-				&mut unsafe{assume_branch_mut(&mut**get_back_mut(&mut self.line_paren_stack))}.v //safe; always something in parenstack, and it's always a branch
+				Ok(&mut unsafe{assume_branch_mut(&mut**get_back_mut(&mut self.line_paren_stack))}.v) //safe; always something in parenstack, and it's always a branch
 			}else{ //it's the root line paren stack. A modification may need to be made.
 				let rpl = unsafe{&mut*self.line_paren_stack[0]};
 				let root_pl_len = assume_branch_mut(rpl).v.len();
+				//the line is getting the indented lines, so it stays
+				self.line_undecided = false;
 				if root_pl_len > 1 { //then it needs to be its own branch
 					accrete_branch(rpl);
+					self.count_node()?;
 				}
-				&mut assume_branch_mut(rpl).v
+				Ok(&mut assume_branch_mut(rpl).v)
 			}
 		}
 	}
@@ -99,11 +253,11 @@ impl<'a> TermposeParserState<'a> {
 					self.iter.next();
 				}
 				self.line += 1;
-				self.column = 1;
+				self.column = 0;
 				Some('\n') //if it was a pesky '\r', it wont come through that way
 			}else if c == '\n' {
 				self.line += 1;
-				self.column = 1;
+				self.column = 0;
 				Some(c)
 			}else{
 				self.column += 1;
@@ -116,24 +270,28 @@ impl<'a> TermposeParserState<'a> {
 	// fn next_col(&mut self){ self.column += 1; }
 	// fn next_line(&mut self){ self.line += 1; self.column = 0; }
 	
-	fn open_paren(&mut self) {
+	fn open_paren(&mut self)-> Result<(), Box<WoodError>> {
 		let lti = self.mkbranch();
 		let branch_for_insert = self.take_hanging_branch_for_insert();
 		unsafe{(*branch_for_insert).push(lti)};
 		self.line_paren_stack.push(unsafe{&mut *get_back_mut(&mut*branch_for_insert)});
 		self.last_completed_term_on_line = null_mut();
+		self.count_node()?;
+		self.check_paren_depth()
 	}
 	fn close_paren(&mut self)-> Result<(), Box<WoodError>> {
 		self.colon_receptacle = null_mut();
 		if self.line_paren_stack.len() > 1 {
 			self.last_completed_term_on_line = unsafe{ &mut **get_back_mut(&mut self.line_paren_stack)};
+			let end = self.offset_of(self.next_char_ptr());
+			extend_span(&mut assume_branch_mut(unsafe{ &mut *self.last_completed_term_on_line }).span, end);
 			self.line_paren_stack.pop(); //safe: we just checked and confirmed there's something there
 			Ok(())
 		}else{
 			self.a_fail("unmatched paren".into())
 		}
 	}
-	fn take_last_completed_term_on_line(&mut self)-> *mut Wood {
+	fn take_last_completed_term_on_line(&mut self)-> *mut Wood<S> {
 		replace(&mut self.last_completed_term_on_line, null_mut())
 	}
 	fn open_colon(&mut self)-> Result<(), Box<WoodError>> {
@@ -143,28 +301,33 @@ impl<'a> TermposeParserState<'a> {
 			if lt != null_mut() {
 				unsafe{ accrete_branch(&mut *lt) }
 			}else{
-				return Err(Box::new(WoodError{line: self.line, column: self.column, msg:"no previous term, cannot open a colon here".into(), cause:None}));
+				return Err(Box::new(WoodError{line: self.line, column: self.column, span: self.span_from_here(), kind: ErrorKind::Syntax, path: Vec::new(), msg:"no previous term, cannot open a colon here".into(), cause:None}));
 			}
 		};
-		Ok(())
+		self.count_node()
 	}
-	fn begin_leaf(&mut self, branch_for_insert:*mut Vec<Wood>) {
-		let to_push = Leafv(Leaf{line:self.line, column:self.column, v:String::new()});
+	fn begin_leaf(&mut self, branch_for_insert:*mut Vec<Wood<S>>)-> Result<(), Box<WoodError>> {
+		let to_push = self.mkleaf(self.next_char_ptr());
 		unsafe{(*branch_for_insert).push(to_push)};
-		self.last_completed_term_on_line = unsafe{get_back_mut(&mut *branch_for_insert)};
-		self.leaf_being_read_into = &mut unsafe{assume_leaf_mut(get_back_mut(&mut *branch_for_insert))}.v;
+		let leaf: *mut Wood<S> = unsafe{get_back_mut(&mut *branch_for_insert)};
+		self.last_completed_term_on_line = leaf;
+		self.leaf_being_read_into = unsafe{assume_leaf_mut(&mut *leaf)}; //derived from the same pointer, so that taking one doesn't invalidate the other
+		self.quoted_blank = true;
+		self.count_node()
 	}
-	fn begin_leaf_with_char(&mut self, branch_for_insert:*mut Vec<Wood>, c:char)-> Result<(), Box<WoodError>> {
-		let to_push = Leafv(Leaf{line:self.line, column:self.column, v:String::new()});
+	fn begin_leaf_with_char(&mut self, branch_for_insert:*mut Vec<Wood<S>>, c:char)-> Result<(), Box<WoodError>> {
+		let to_push = self.mkleaf(self.cur_char_ptr);
 		unsafe{(*branch_for_insert).push(to_push)};
-		self.last_completed_term_on_line = unsafe{get_back_mut(&mut *branch_for_insert)};
-		self.leaf_being_read_into = &mut unsafe{assume_leaf_mut(get_back_mut(&mut *branch_for_insert))}.v;
+		let leaf: *mut Wood<S> = unsafe{get_back_mut(&mut *branch_for_insert)};
+		self.last_completed_term_on_line = leaf;
+		self.leaf_being_read_into = unsafe{assume_leaf_mut(&mut *leaf)};
+		self.quoted_blank = false;
+		self.count_node()?;
 		if c == '\\' {
-			self.read_escaped_char()?;
+			self.read_escaped_char()
 		}else{
-			unsafe{(*self.leaf_being_read_into).push(c)};
-		};
-		Ok(())
+			self.push_char(c)
+		}
 	}
 	
 	fn start_reading_thing(&mut self, c:char)-> Result<(), Box<WoodError>> {
@@ -174,12 +337,12 @@ impl<'a> TermposeParserState<'a> {
 				self.mode = Self::seeking_immediately_after_thing;
 			},
 			c if c == self.style().open=> {
-				self.open_paren();
+				self.open_paren()?;
 				self.mode = Self::seeking_term;
 			},
 			'"'=> {
 				let l = self.take_hanging_branch_for_insert();
-				self.begin_leaf(l);
+				self.begin_leaf(l)?;
 				self.mode = Self::eating_quoted_string;
 			},
 			c if c == self.style().pairing=> {
@@ -196,11 +359,17 @@ impl<'a> TermposeParserState<'a> {
 	}
 	
 	fn pop_indent_stack_down(&mut self, this_indent:&'a str)-> Result<(), Box<WoodError>> {
+		//the level is found before anything is popped, so that the stacks are left intact if there isn't one (recovery continues from them)
+		let mut level = self.indent_stack.len();
 		loop{
-			let containing_indent = *get_back_mut(&mut self.indent_stack); //we can be assured that there is always something at back, because a str can't be smaller than the root indent "" and not be a prefix of it
+			level -= 1; //we can be assured that there is always something below, because a str can't be smaller than the root indent "" and not be a prefix of it
+			let containing_indent = self.indent_stack[level];
 			if this_indent.len() == containing_indent.len() {
 				if this_indent == containing_indent {
 					//found it
+					self.indent_stack.truncate(level + 1);
+					self.indent_branch_stack.truncate(level + 1);
+					self.indent_depths.truncate(level + 1);
 					return Ok(());
 				}else{
 					return self.a_fail("inconsistent indentation".into());
@@ -209,8 +378,6 @@ impl<'a> TermposeParserState<'a> {
 				//oh no, it's too short to be with the last level and too long to be with the next level, it must not be in the allowed set
 				return self.a_fail("inconsistent indentation".into());
 			}
-			self.indent_stack.pop();
-			self.indent_branch_stack.pop();
 		}
 	}
 	
@@ -225,7 +392,8 @@ impl<'a> TermposeParserState<'a> {
 		OnGreater : FnOnce(&mut Self, &'a str)-> Result<(), Box<WoodError>>,
 	{
 		//there's definitely a thing here, ending indentation
-		let this_indent = unsafe{str_from_bounds(self.stretch_reading_start, self.cur_char_ptr)};
+		let this_indent = self.indentation();
+		self.indentation_end = null();
 		let containing_indent = *get_back_mut(&mut self.indent_stack); //safe: indent function always has something in it
 		if containing_indent.len() == this_indent.len() {
 			if containing_indent != this_indent {
@@ -238,10 +406,10 @@ impl<'a> TermposeParserState<'a> {
 			if !containing_indent.starts_with(this_indent) {
 				return self.a_fail("inconsistent indentation".into());
 			}
-			//no indent:
-			self.end_unindented_line();
 			//pop indent stack until we're on the right level
 			self.pop_indent_stack_down(this_indent)?;
+			//no indent:
+			self.end_unindented_line();
 			sc(self)
 		}else{ //greater
 			if !this_indent.starts_with(containing_indent) {
@@ -264,6 +432,7 @@ impl<'a> TermposeParserState<'a> {
 					Ok(())
 				},
 				_ => {
+					if self.skip_comment()? { return Ok(()); }
 					self.start_line(c)
 				},
 			}
@@ -278,18 +447,20 @@ impl<'a> TermposeParserState<'a> {
 				' ' | '\t' => {
 				},
 				'\n' => {
-					self.stretch_reading_start = self.next_char_ptr();
+					self.begin_indentation();
 					// self.mode = &Self::eating_indentation;
 				},
 				_=> {
+					if self.skip_comment_in_indentation()? { return Ok(()); }
 					//there's definitely a thing here, ending indentation
 					self.notice_this_new_indentation(
 						|_  :&mut Self|{ Ok(()) },
 						|_  :&mut Self|{ Ok(()) },
 						|slf:&mut Self, this_indent:&'a str|{
-							let plhl = slf.take_hanging_branch_for_new_line();
+							let plhl = slf.take_hanging_branch_for_new_line()?;
 							slf.indent_stack.push(this_indent);
 							slf.indent_branch_stack.push(plhl as *mut _);
+							slf.indent_depths.push(0); //found when the line that plhl is in is checked, as the new line starts
 							Ok(())
 						},
 					)?;
@@ -308,14 +479,16 @@ impl<'a> TermposeParserState<'a> {
 				' ' | '\t' => {
 				},
 				'\n' => {
-					self.stretch_reading_start = self.next_char_ptr();
+					self.begin_indentation();
 					self.mode = Self::eating_indentation;
 				},
 				c if c == self.style().pairing => {
 					self.open_colon()?;
 				},
 				_=> {
-					self.start_reading_thing(c)?;
+					if !self.skip_comment()? {
+						self.start_reading_thing(c)?;
+					}
 				}
 			}
 		}else{
@@ -325,35 +498,23 @@ impl<'a> TermposeParserState<'a> {
 	}
 
 	fn read_escaped_char(&mut self)-> Result<(), Box<WoodError>> {
-		let push = |slf:&mut Self, c:char| unsafe{((*slf.leaf_being_read_into)).push(c)}; //safe: leaf_being_read_into must have been validated before this mode could have been entered
-		let match_fail_message = "escape slash must be followed by a valid escape character code";
-		if let Some(nc) = self.move_char_ptr_and_update_line_col() {
-			match nc {
-				'n'=> { push(self, '\n'); }
-				'r'=> { push(self, '\r'); }
-				't'=> { push(self, '\t'); }
-				'h'=> { push(self, '☃'); }
-				'"'=> { push(self, '"'); }
-				'\\'=> { push(self, '\\'); }
-				_=> { return self.a_fail(match_fail_message.into()); }
-			}
-		}else{
-			return self.a_fail(match_fail_message.into());
+		match escaped_char(|| self.move_char_ptr_and_update_line_col()) {
+			Some(c)=> self.push_char(c),
+			None=> self.a_fail("escape slash must be followed by a valid escape character code".into()),
 		}
-		self.column += 2;
-		Ok(())
 	}
 
 	fn eating_quoted_string(&mut self, co:Option<char>)-> Result<(), Box<WoodError>> {
-		let push_char = |slf:&mut Self, c:char| unsafe{((*slf.leaf_being_read_into)).push(c)}; //safe: leaf_being_read_into must have been validated before this mode could have been entered
+		let push_char = |slf:&mut Self, c:char| slf.push_char(c);
 		if let Some(c) = co {
 			match c {
 				'\n'=> {
-					self.stretch_reading_start = self.next_char_ptr();
-					let ar = unsafe{&mut *self.leaf_being_read_into};
-					if ar.chars().all(is_whitespace) {
+					self.begin_indentation();
+					let ar = unsafe{&mut (*self.leaf_being_read_into).v};
+					if ar.as_ref().chars().all(is_whitespace) {
 						//begin multiline string
 						ar.clear();
+						self.quoted_blank = false;
 						self.mode = Self::eating_initial_multline_string_indentation;
 					}else {
 						self.mode = Self::eating_indentation;
@@ -363,34 +524,38 @@ impl<'a> TermposeParserState<'a> {
 					self.read_escaped_char()?;
 				},
 				'"'=> {
+					let end = self.offset_of(self.next_char_ptr());
+					extend_span(unsafe{&mut (*self.leaf_being_read_into).span}, end);
+					self.end_quoted_leaf()?;
 					self.mode = Self::seeking_immediately_after_thing;
 				},
 				_=> {
-					push_char(self, c);
+					push_char(self, c)?;
 				}
 			}
 		}else{
+			self.end_quoted_leaf()?;
 			self.end_unindented_line();
 		}
 		Ok(())
 	}
 
 	fn eating_leaf(&mut self, co:Option<char>)-> Result<(), Box<WoodError>> {
-		let push_char = |slf:&mut Self, c:char| unsafe{((*slf.leaf_being_read_into)).push(c)}; //safe: leaf_being_read_into must have been validated before this mode could have been entered
+		let push_char = |slf:&mut Self, c:char| slf.push_char(c);
 		if let Some(c) = co {
 			match c {
 				' ' | '\t' => {
 					self.mode = Self::seeking_term;
 				},
 				'\n'=> {
-					self.stretch_reading_start = self.next_char_ptr();
+					self.begin_indentation();
 					self.mode = Self::eating_indentation;
 				},
 				'\\'=> {
 					self.read_escaped_char()?;
 				},
 				'"'=> {
-					self.notice_quote_immediately_after_thing();
+					self.notice_quote_immediately_after_thing()?;
 				},
 				c if c == self.style().pairing=> {
 					self.open_colon()?;
@@ -401,10 +566,10 @@ impl<'a> TermposeParserState<'a> {
 					self.mode = Self::seeking_immediately_after_thing;
 				},
 				c if c == self.style().open=> {
-					self.notice_paren_immediately_after_thing();
+					self.notice_paren_immediately_after_thing()?;
 				},
 				_=> {
-					push_char(self, c);
+					push_char(self, c)?;
 				}
 			}
 		}else{
@@ -413,39 +578,46 @@ impl<'a> TermposeParserState<'a> {
 		Ok(())
 	}
 	
-	fn notice_paren_immediately_after_thing(&mut self){
-		let bl: *mut Wood = self.take_last_completed_term_on_line();
+	fn notice_paren_immediately_after_thing(&mut self)-> Result<(), Box<WoodError>> {
+		let bl: *mut Wood<S> = self.take_last_completed_term_on_line();
 		if bl == null_mut() {
 			panic!("notice_paren_immediately_after_thing was called with no previous thing");
 		}
 		accrete_branch(unsafe{&mut *bl});
 		self.line_paren_stack.push(bl);
 		self.mode = Self::seeking_term;
+		self.count_node()?;
+		self.check_paren_depth()
 	}
 	
-	fn notice_quote_immediately_after_thing(&mut self){
-		let lt: *mut Vec<Wood> = unsafe{ &mut assume_branch_mut(&mut **get_back_mut(&mut self.line_paren_stack)).v };
+	fn notice_quote_immediately_after_thing(&mut self)-> Result<(), Box<WoodError>> {
+		let lt: *mut Vec<Wood<S>> = unsafe{ &mut assume_branch_mut(&mut **get_back_mut(&mut self.line_paren_stack)).v };
 		if unsafe{(*lt).len()} == 0 {
 			panic!("notice_quote_immediately_after_thing should not be called after entering an empty paren");
 		}
+		let ql = self.mkleaf(self.next_char_ptr());
 		let bt = get_back_mut(unsafe{ &mut*lt });
 		let nl = accrete_branch(bt);
-		nl.push(Leafv(Leaf{line:self.line, column:self.column, v:String::new()}));
-		self.leaf_being_read_into = &mut assume_leaf_mut(get_back_mut(nl)).v; //safe: just made that
+		nl.push(ql);
+		self.leaf_being_read_into = assume_leaf_mut(get_back_mut(nl)); //safe: just made that
+		self.quoted_blank = true;
 		self.mode = Self::eating_quoted_string;
+		//the branch, then the leaf
+		self.count_node()?;
+		self.count_node()
 	}
 
 	fn seeking_immediately_after_thing(&mut self, co:Option<char>)-> Result<(), Box<WoodError>> { //generally called after ')' or a closing '"', because '"'s and self.style().opens have slightly different meaning in that context
 		if let Some(c) = co {
 			match c {
 				'"'=> {
-					self.notice_quote_immediately_after_thing();
+					self.notice_quote_immediately_after_thing()?;
 				},
 				c if c == self.style().close=> {
 					self.close_paren()?;
 				},
 				c if c == self.style().open=> {
-					self.notice_paren_immediately_after_thing();
+					self.notice_paren_immediately_after_thing()?;
 				},
 				c if c == self.style().pairing=> {
 					self.open_colon()?;
@@ -455,13 +627,17 @@ impl<'a> TermposeParserState<'a> {
 					self.mode = Self::seeking_term;
 				},
 				'\n'=> {
-					self.stretch_reading_start = self.next_char_ptr();
+					self.begin_indentation();
 					self.mode = Self::eating_indentation;
 				},
 				_=> {
-					let il = self.take_hanging_branch_for_insert();
-					self.begin_leaf_with_char(il, c)?;
-					self.mode = Self::eating_leaf;
+					if self.skip_comment()? {
+						self.mode = Self::seeking_term;
+					}else{
+						let il = self.take_hanging_branch_for_insert();
+						self.begin_leaf_with_char(il, c)?;
+						self.mode = Self::eating_leaf;
+					}
 				},
 			}
 		}else{
@@ -475,9 +651,14 @@ impl<'a> TermposeParserState<'a> {
 			match c {
 				' ' | '\t' => {},
 				'\n'=> {
-					self.stretch_reading_start = self.next_char_ptr();
+					self.begin_indentation();
 				},
 				_=> {
+					//a comment on a line that isn't indented enough to be content is skipped like a blank line
+					let this_indent = self.indentation();
+					let containing_indent = *get_back_mut(&mut self.indent_stack);
+					let is_content = this_indent.len() > containing_indent.len() && this_indent.starts_with(containing_indent);
+					if !is_content && self.skip_comment_in_indentation()? { return Ok(()); }
 					//ending indentation
 					self.notice_this_new_indentation(
 						|slf:&mut Self|{
@@ -490,7 +671,7 @@ impl<'a> TermposeParserState<'a> {
 						},
 						|slf:&mut Self, this_indent:&'a str|{
 							slf.multilines_indent = this_indent;
-							unsafe{(*slf.leaf_being_read_into).push(c)};
+							slf.push_char(c)?;
 							slf.mode = Self::eating_multiline_content;
 							Ok(())
 						},
@@ -508,11 +689,11 @@ impl<'a> TermposeParserState<'a> {
 			match c {
 				'\n'=> {
 					// unsafe{(*self.leaf_being_read_into).push(c)}; //actually, we'll only take the character once it's been confirmed that the indent goes all the way up
-					self.stretch_reading_start = self.next_char_ptr();
+					self.begin_indentation();
 					self.mode = Self::eating_multiline_later_indent;
 				},
 				_=> {
-					unsafe{(*self.leaf_being_read_into).push(c)};
+					self.push_char(c)?;
 				}
 			}
 		}else{
@@ -525,12 +706,13 @@ impl<'a> TermposeParserState<'a> {
 		if let Some(c) = co {
 			match c {
 				' ' | '\t' => {
+					if !self.indentation_end.is_null() { return Ok(()); } //there was a comment, so this line is not content
 					let curstr = unsafe{str_from_bounds(self.stretch_reading_start, self.next_char_ptr())}; //safe: these u8 pointers are fine
 					if curstr.len() == self.multilines_indent.len() {
 						if curstr != self.multilines_indent {
 							return self.a_fail("inconsistent indentation".into());
 						}
-						unsafe{(*self.leaf_being_read_into).push('\n')}; //only now do we finalize the newline given
+						self.push_char('\n')?; //only now do we finalize the newline given
 						self.mode = Self::eating_multiline_content;
 					}else{
 						if !self.multilines_indent.starts_with(curstr) {
@@ -540,12 +722,15 @@ impl<'a> TermposeParserState<'a> {
 				},
 				'\n'=> {
 					//does not eat the newline
-					self.stretch_reading_start = self.next_char_ptr();
+					self.begin_indentation();
 				},
 				_=> {
+					//comments on lines too shallow to be content are skipped like blank lines
+					if self.skip_comment_in_indentation()? { return Ok(()); }
 					//ending indentation
 					//since the indentation hasn't ended already, this must be a shorter line than the multiline scope, so we'll pop
-					let this_indent = unsafe{str_from_bounds(self.stretch_reading_start, self.cur_char_ptr)};
+					let this_indent = self.indentation();
+					self.indentation_end = null();
 					self.pop_indent_stack_down(this_indent)?;
 					self.consider_collapsing_outer_branch_of_previous_line();
 					self.start_line(c)?;
@@ -557,38 +742,161 @@ impl<'a> TermposeParserState<'a> {
 		Ok(())
 	}
 
+	//recovery
+	
+	///after an error, forgets the half-read term and skips to the next line that isn't indented deeper than the line the error was on, where parsing can resume as if the skipped text wasn't there. The line that was open when the error occurred is left open, so that it will be ended, or take the resumed line as a child, in the usual way. Returns the offset parsing resumed at, or None if the end of the text was reached.
+	fn resynchronize(&mut self, line_start:usize)-> Option<usize> {
+		self.colon_receptacle = null_mut();
+		self.last_completed_term_on_line = null_mut();
+		self.leaf_being_read_into = null_mut();
+		self.quoted_blank = false;
+		self.line_paren_stack.truncate(1);
+		self.pending_comments.clear();
+		
+		let max_indent = self.source[line_start..].len() - self.source[line_start..].trim_start_matches([' ', '\t']).len();
+		
+		let skip_line = |slf:&mut Self|-> bool { //true if there was a newline
+			loop{
+				match slf.move_char_ptr_and_update_line_col() {
+					Some('\n')=> return true,
+					None=> return false,
+					_=> {}
+				}
+			}
+		};
+		let error_was_newline = matches!(self.source.as_bytes().get(self.offset_of(self.cur_char_ptr)), Some(b'\n') | Some(b'\r'));
+		if !error_was_newline && !skip_line(self) { return None; }
+		loop{
+			let rest = self.iter.as_str();
+			let content = rest.trim_start_matches([' ', '\t']);
+			match content.chars().next() {
+				None=> return None,
+				Some('\n') | Some('\r')=> {},
+				Some(_)=> {
+					if rest.len() - content.len() <= max_indent {
+						self.begin_indentation();
+						//if no line has been started yet, there's nothing for this one to be indented under
+						self.mode = if self.line_paren_stack.is_empty() { Self::seeking_beginning }else{ Self::eating_indentation };
+						return Some(self.offset_of(self.next_char_ptr()));
+					}
+				}
+			}
+			if !skip_line(self) { return None; }
+		}
+	}
+
 } //TermposeParserState
 
 
-///Returns a Branch containing all of the Woods at root level, even if there is only one Wood, it will be wrapped in an additional Branch
-pub fn parse_multiline_termpose_style<'a>(s:&'a str, style:TermposeStyle)-> Result<Wood, Box<WoodError>> {
-	let mut state = TermposeParserState::<'a>{
-		root: Wood::empty(), //a yet empty line
+///The result of a parse that carried on past its errors. Text that couldn't be parsed is skipped, so `wood` is a best-effort reading of the rest.
+#[derive(Debug)]
+pub struct RecoveredParse<S=String> {
+	pub wood: Wood<S>,
+	///every error encountered, in order
+	pub errors: Vec<WoodError>,
+	recovered: Vec<Span>,
+}
+impl<S> RecoveredParse<S> {
+	///the regions of the text that were affected by errors: from the beginning of each line an error occurred on to the point parsing resumed
+	pub fn recovered_spans(&self)-> &[Span] { &self.recovered }
+	///whether the wood was read from a line that had an error in it. Such woods may be missing some of their contents.
+	pub fn is_recovered(&self, w:&Wood<S>)-> bool {
+		w.span().is_some_and(|sp| self.recovered.iter().any(|r| r.start <= sp.start && sp.start < r.end))
+	}
+}
+
+fn parse_multiline_termpose_style_into<'a, S:LeafStorage<'a>>(s:&'a str, style:TermposeStyle, file:Option<FileId>)-> Result<Wood<S>, Box<WoodError>> {
+	run_termpose_parser(s, style, file, false, &mut Vec::new()).map(|r| r.wood)
+}
+
+///if `recover` is false, returns the first error instead of carrying on
+///`pending_comments` are comments that came before `s` and haven't been given to a wood yet. They're left holding the ones at the end of `s` that weren't given to one either.
+fn run_termpose_parser<'a, S:LeafStorage<'a>>(s:&'a str, style:TermposeStyle, file:Option<FileId>, recover:bool, pending_comments:&mut Vec<String>)-> Result<RecoveredParse<S>, Box<WoodError>> {
+	let mut root = Branchv(Branch{ line:-1, column:-1, span:Some(Span::new(file, 0, s.len())), comments:Vec::new(), v:Vec::new() }); //a yet empty line
+	//root is kept out of the state, and not touched again until the parse is over, so that borrowing the state doesn't invalidate the pointers into it
+	let root_contents: *mut Vec<Wood<S>> = &mut assume_branch_mut(&mut root).v;
+	let mut state = TermposeParserState::<'a, S>{
+		source: s,
+		file,
 		indent_stack: vec!(""),
 		stretch_reading_start: s.as_ptr(),
+		indentation_end: null(),
 		cur_char_ptr: s.as_ptr(),
 		colon_receptacle: null_mut(),
 		last_completed_term_on_line: null_mut(),
 		leaf_being_read_into: null_mut(),
 		iter: s.chars(),
 		line: 1,
-		column: 1,
+		column: 0,
 		multilines_indent: "",
 		line_paren_stack: vec!(),
-		indent_branch_stack: vec!(),
-		mode: TermposeParserState::<'a>::seeking_beginning,
+		indent_branch_stack: vec!(root_contents),
+		indent_depths: vec!(0),
+		line_depth: 0,
+		line_undecided: false,
+		nodes: 0,
+		quoted_blank: false,
+		mode: TermposeParserState::<'a, S>::seeking_beginning,
 		chosen_style: style,
+		pending_comments: std::mem::take(pending_comments),
 	};
-	state.indent_branch_stack = vec!(&mut assume_branch_mut(&mut state.root).v);
-	
 	
+	let mut errors = Vec::new();
+	let mut recovered = Vec::new();
+	let mut over_limit = None;
 	loop {
 		let co = state.move_char_ptr_and_update_line_col();
-		(state.mode)(&mut state, co)?;
+		if let Err(e) = (state.mode)(&mut state, co) {
+			if !recover { return Err(e); }
+			//there's no recovering from this. Carrying on would go beyond the limits
+			if e.kind == ErrorKind::LimitExceeded { over_limit = Some(e); break; }
+			let error_at = state.offset_of(state.cur_char_ptr);
+			let line_start = s[..error_at].rfind(['\n', '\r']).map_or(0, |i| i + 1);
+			errors.push(*e);
+			match state.resynchronize(line_start) {
+				Some(resumed_at)=> {
+					recovered.push(Span::new(file, line_start, resumed_at));
+				}
+				None=> {
+					recovered.push(Span::new(file, line_start, s.len()));
+					if !state.line_paren_stack.is_empty() { state.end_unindented_line(); }
+					break;
+				}
+			}
+		}
 		if co == None { break; }
 	}
 	
-	Ok(state.root)
+	if over_limit.is_none() {
+		//the last line is only settled once the text is over
+		if let Err(e) = state.check_line_depth() {
+			if !recover { return Err(e); }
+			over_limit = Some(e);
+		}
+	}
+	settle_branch_spans(&mut root);
+	if let Some(e) = over_limit {
+		//none of it can be trusted to be within the limits, so none of it is kept
+		errors.push(*e);
+		recovered.push(Span::new(file, 0, s.len()));
+		root = Branchv(Branch{ line:-1, column:-1, span:Some(Span::new(file, 0, s.len())), comments:Vec::new(), v:Vec::new() });
+	}
+	*pending_comments = state.pending_comments;
+	Ok(RecoveredParse{ wood:root, errors, recovered })
+}
+
+///parses a part of a longer text, for the stream. `pending_comments` are carried from one part to the next, as they are in `run_termpose_parser`
+pub(crate) fn parse_multiline_termpose_part(s:&str, style:TermposeStyle, pending_comments:&mut Vec<String>)-> Result<Wood, Box<WoodError>> {
+	run_termpose_parser(s, style, None, false, pending_comments).map(|r| r.wood)
+}
+
+pub(crate) fn parse_multiline_termpose_style_in(s:&str, style:TermposeStyle, file:Option<FileId>)-> Result<Wood, Box<WoodError>> {
+	parse_multiline_termpose_style_into(s, style, file)
+}
+
+///Returns a Branch containing all of the Woods at root level, even if there is only one Wood, it will be wrapped in an additional Branch
+pub fn parse_multiline_termpose_style<'a>(s:&'a str, style:TermposeStyle)-> Result<Wood, Box<WoodError>> {
+	parse_multiline_termpose_style_into(s, style, None)
 }
 
 ///Returns a Branch containing all of the Woods at root level, even if there is only one Wood, it will be wrapped in an additional Branch
@@ -598,15 +906,32 @@ pub fn parse_multiline_termpose<'a>(s:&'a str)-> Result<Wood, Box<WoodError>> {
 
 ///If multiple Woods are at root level in the input, it will wrap them all in a Branch Wood. Otherwise, if there's only one, it wont. This is probably the behaviour you will expect, most of the time, but if I didn't explain it here it might have derailed you, the rest of the time.
 pub fn parse_termpose<'a>(s:&'a str)-> Result<Wood, Box<WoodError>> {
-	parse_multiline_termpose(s).map(|t|{
-		let l = assume_branch(t); //parse_multiline_termpose only returns branchs
-		if l.v.len() == 1 {
-			yank_first(l.v) //just confirmed it's there
-		}else{
-			//then the caller was wrong, it wasn't a single root term, so I guess, they get the whole Branch? Maybe this should be a WoodError... I dunno about that
-			Branchv(l)
-		}
-	})
+	parse_multiline_termpose(s).map(unwrap_single_root)
+}
+
+///`parse_termpose`, in the given style. This is what reads back what `pretty_termpose_detail` writes in the same style.
+pub fn parse_termpose_style(s:&str, style:TermposeStyle)-> Result<Wood, Box<WoodError>> {
+	parse_multiline_termpose_style(s, style).map(unwrap_single_root)
+}
+
+///Like `parse_multiline_termpose_style`, but instead of stopping at the first error, skips to the next line that isn't indented deeper than the line the error was on and carries on from there. Useful for tooling that has to make what it can of files that are still being written.
+pub fn parse_multiline_termpose_style_recovering(s:&str, style:TermposeStyle)-> RecoveredParse {
+	run_termpose_parser(s, style, None, true, &mut Vec::new()).unwrap() //never returns an error when recovering
+}
+
+///`parse_multiline_termpose_style_recovering` with the default style
+pub fn parse_multiline_termpose_recovering(s:&str)-> RecoveredParse {
+	parse_multiline_termpose_style_recovering(s, DEFAULT_STYLE.clone())
+}
+
+///`parse_multiline_termpose`, but leaves that contain no escapes borrow from `s` instead of being copied
+pub fn parse_multiline_termpose_borrowed<'a>(s:&'a str)-> Result<WoodRef<'a>, Box<WoodError>> {
+	parse_multiline_termpose_style_into(s, DEFAULT_STYLE.clone(), None)
+}
+
+///`parse_termpose`, but leaves that contain no escapes borrow from `s` instead of being copied
+pub fn parse_termpose_borrowed<'a>(s:&'a str)-> Result<WoodRef<'a>, Box<WoodError>> {
+	parse_multiline_termpose_borrowed(s).map(unwrap_single_root)
 }
 
 
@@ -620,145 +945,187 @@ pub fn parse_termpose<'a>(s:&'a str)-> Result<Wood, Box<WoodError>> {
 
 ///Blurts it into a single line. (Might be woodslist compatable??)
 pub fn stringify_leaf_termpose(v:&Leaf, s:&mut String, style:&TermposeStyle){
-	let needs_quotes = v.v.chars().any(|c|{ c == ' ' || c == style.pairing || c == '\t' || c == style.open || c == style.close });
-	if needs_quotes { s.push('"'); }
-	push_escaped(s, v.v.as_str());
-	if needs_quotes { s.push('"'); }
+	write_leaf_termpose(v, s, style).unwrap(); //writing to a String can't fail
 }
-
-fn inline_stringify_termpose_branch_baseline(b:&Branch, s:&mut String, style:&TermposeStyle){
-	//space separated
-	let mut i = b.v.iter();
-	if let Some(ref first) = i.next() {
-		inline_stringify_termpose(first, s, style);
-		while let Some(ref nexto) = i.next() {
-			s.push(' ');
-			inline_stringify_termpose(nexto, s, style);
-		}
-	}
+fn write_leaf_termpose<W:Write>(v:&Leaf, s:&mut W, style:&TermposeStyle)-> fmt::Result {
+	//an empty leaf would be nothing at all, and a leaf that begins with a comment marker would be read as a comment
+	let needs_quotes = v.v.is_empty() || style.comments.comment_at(&v.v).is_some() || v.v.chars().any(|c|{ c == ' ' || c == style.pairing || c == '\t' || c == style.open || c == style.close || c == '"' });
+	if needs_quotes { s.write_char('"')?; }
+	write_escaped(s, v.v.as_str())?;
+	if needs_quotes { s.write_char('"')?; }
+	Ok(())
 }
-fn inline_stringify_termpose_branch(b:&Branch, s:&mut String, style:&TermposeStyle){
-	if b.v.len() == 2 && b.v[0].is_leaf() {
-		inline_stringify_termpose(&b.v[0], s, style);
-		s.push(style.pairing);
-		inline_stringify_termpose(&b.v[1], s, style);
+
+///a branch being written out inline: the contents it has left, whether any have been written yet, and what closes it, if anything
+type InlineFrame<'a> = (std::slice::Iter<'a, Wood>, bool, Option<char>);
+///writes the beginning of a branch, returning what's left of it
+fn open_inline_termpose_branch<'a, W:Write>(b:&'a Branch, s:&mut W, style:&TermposeStyle)-> Result<InlineFrame<'a>, fmt::Error> {
+	if let [Leafv(ref first), ref second] = b.v[..] {
+		write_leaf_termpose(first, s, style)?;
+		s.write_char(style.pairing)?;
+		Ok((std::slice::from_ref(second).iter(), true, None))
 	}else{
-		s.push(style.open);
-		inline_stringify_termpose_branch_baseline(b, s, style);
-		s.push(style.close);
+		s.write_char(style.open)?;
+		Ok((b.v.iter(), true, Some(style.close)))
 	}
 }
-fn inline_stringify_termpose(w:&Wood, s:&mut String, style:&TermposeStyle){
-	match *w {
-		Branchv(ref b)=> {
-			inline_stringify_termpose_branch(b, s, style);
-		}
-		Leafv(ref v)=> {
-			stringify_leaf_termpose(v, s, style);
+fn inline_stringify_termpose_frames<W:Write>(mut stack:Vec<InlineFrame>, s:&mut W, style:&TermposeStyle)-> fmt::Result {
+	//an explicit stack rather than recursion, so that deep trees don't overflow
+	while let Some((contents, first, close)) = stack.last_mut() {
+		match contents.next() {
+			Some(w)=> {
+				//space separated
+				if !replace(first, false) { s.write_char(' ')?; }
+				match *w {
+					Branchv(ref b)=> {
+						let frame = open_inline_termpose_branch(b, s, style)?;
+						stack.push(frame);
+					}
+					Leafv(ref l)=> write_leaf_termpose(l, s, style)?,
+				}
+			}
+			None=> {
+				if let Some(c) = *close { s.write_char(c)?; }
+				stack.pop();
+			}
 		}
 	}
+	Ok(())
 }
-fn termpose_inline_length_estimate_branch_baseline(b:&Branch)-> usize {
-	if b.v.len() > 0 {
-		let spaces_length = b.v.len() - 1;
-		b.v.iter().fold(spaces_length, |n, w|{
-			n + termpose_inline_length_estimate(w)
-		})
-	}else{
-		2
-	}
+fn inline_stringify_termpose_branch_baseline<W:Write>(b:&Branch, s:&mut W, style:&TermposeStyle)-> fmt::Result {
+	inline_stringify_termpose_frames(vec![(b.v.iter(), true, None)], s, style)
 }
-fn termpose_inline_length_estimate_for_branch(b:&Branch)-> usize {
-	if b.v.len() == 2 && b.v[0].is_leaf() {
-		//do a pairing
-		return
-			1 +
-			termpose_inline_length_estimate(&b.v[0]) +
-			termpose_inline_length_estimate(&b.v[1]);
-	}else{
-		//+2 for parens
-		if b.v.len() > 0 {
-			let spaces_length = b.v.len() - 1;
-			2 + b.v.iter().fold(spaces_length, |n, w|{
-				n + termpose_inline_length_estimate(w)
-			})
-		}else{
-			2
+fn inline_stringify_termpose_branch<W:Write>(b:&Branch, s:&mut W, style:&TermposeStyle)-> fmt::Result {
+	let frame = open_inline_termpose_branch(b, s, style)?;
+	inline_stringify_termpose_frames(vec![frame], s, style)
+}
+///how long `b`'s contents would be on one line, which stops counting once it passes `limit`, so that a big branch isn't walked all the way through (again for each branch it's in) only to find that it doesn't fit
+fn termpose_inline_length_estimate_branch_baseline(b:&Branch, limit:usize)-> usize {
+	let mut ret = if b.v.is_empty() { 2 }else{ b.v.len() - 1 };
+	let mut stack = vec![b.v.iter()];
+	while let Some(contents) = stack.last_mut() {
+		if ret > limit { break; }
+		match contents.next() {
+			Some(Branchv(ref c))=> {
+				ret += if c.v.len() == 2 && c.v[0].is_leaf() {
+					1 //for the pairing
+				}else{
+					2 + c.v.len().saturating_sub(1) //2 for parens, and the spaces between
+				};
+				stack.push(c.v.iter());
+			}
+			Some(Leafv(ref l))=> ret += l.v.len(),
+			None=> { stack.pop(); }
 		}
 	}
+	ret
 }
-fn termpose_inline_length_estimate(w:&Wood)-> usize {
+fn maybe_inline_termpose_stringification_baseline<'a, W:Write>(w:&'a Wood, column_limit:usize, out:&mut W, style:&TermposeStyle)-> Result<Option<&'a Branch>, fmt::Error> { //returns Some Branch that w is iff it did NOT insert it inline (because it didn't have room)
 	match w {
 		&Branchv(ref b)=> {
-			termpose_inline_length_estimate_for_branch(b)
+			//the estimate goes first, as it stops early, so comments are only looked for in branches small enough to fit
+			if !b.v.is_empty() && (termpose_inline_length_estimate_branch_baseline(b, column_limit) > column_limit || style.comments.comments_within(w)) {
+				return Ok(Some(b));
+			}else if b.v.len() < 2 {
+				//a line with one thing on it is read as that thing, and an empty line isn't read at all, so these keep their parens
+				inline_stringify_termpose_branch(b, out, style)?;
+			}else{
+				inline_stringify_termpose_branch_baseline(b, out, style)?;
+			}
 		}
 		&Leafv(ref l)=> {
-			l.v.len()
+			write_leaf_termpose(l, out, style)?;
 		}
 	}
+	Ok(None)
 }
-fn maybe_inline_termpose_stringification_baseline<'a>(w:&'a Wood, column_limit:usize, out:&mut String, style:&TermposeStyle)-> Option<&'a Branch> { //returns Some Branch that w is iff it did NOT insert it inline (because it didn't have room)
-	match w {
-		&Branchv(ref b)=> {
-			if termpose_inline_length_estimate_branch_baseline(b) > column_limit {
-				return Some(b);
-			}else{
-				inline_stringify_termpose_branch_baseline(b, out, style);
-			}
+pub(crate) fn do_termpose_stringification<W:Write>(w:&Wood, indent:&str, indent_depth:usize, column_limit:usize, out:&mut W, style:&TermposeStyle)-> fmt::Result {
+	termpose_lines(std::slice::from_ref(w).iter(), indent, indent_depth, column_limit, out, style)
+}
+
+///writes out a branch that didn't fit on one line, with the contents after the first on lines of their own
+fn termpose_branch_lines<W:Write>(b:&Branch, indent:&str, indent_depth:usize, column_limit:usize, out:&mut W, style:&TermposeStyle)-> fmt::Result {
+	let contents = open_termpose_branch_lines(b, column_limit, out, style)?;
+	termpose_lines(contents, indent, indent_depth + 1, column_limit, out, style)
+}
+
+///writes the first wood of a branch that didn't fit on one line, if it fits there, returning the contents that need lines of their own
+fn open_termpose_branch_lines<'a, W:Write>(b:&'a Branch, column_limit:usize, out:&mut W, style:&TermposeStyle)-> Result<std::slice::Iter<'a, Wood>, fmt::Error> {
+	let mut bi = b.v.iter();
+	if let Some(fw) = bi.next() {
+		//a lone first wood would be read as the whole line, rather than as the first of the line's contents. (Comments within fw are seen to by maybe_inline_termpose_stringification_baseline.)
+		if b.v.len() == 1 || style.comments.has_comments(fw) || maybe_inline_termpose_stringification_baseline(fw, column_limit, out, style)?.is_some() {
+			//then the first one wont fit in the first one position
+			out.write_char(style.open)?;
+			return Ok(b.v.iter());
 		}
-		&Leafv(ref l)=> {
-			stringify_leaf_termpose(l, out, style);
-		}
-	}
-	None
-}
-fn do_termpose_stringification(w:&Wood, indent:&str, indent_depth:usize, column_limit:usize, out:&mut String, style:&TermposeStyle){
-	out.push('\n');
-	do_indent(indent, indent_depth, out);
-	if let Some(b) = maybe_inline_termpose_stringification_baseline(w, column_limit, out, style) {
-		let mut bi = b.v.iter();
-		if let Some(fw) = bi.next() {
-			if let Some(_fwb) = maybe_inline_termpose_stringification_baseline(fw, column_limit, out, style) {
-				//then the first one wont fit in the first one position
-				out.push(style.open);
-				for iw in b.v.iter() {
-					do_termpose_stringification(iw, indent, indent_depth + 1, column_limit, out, style);
-				}
-			}else{
-				for iw in bi {
-					do_termpose_stringification(iw, indent, indent_depth + 1, column_limit, out, style);
+	}
+	Ok(bi)
+}
+
+///writes each of `contents` on lines of their own at `indent_depth`
+fn termpose_lines<W:Write>(contents:std::slice::Iter<Wood>, indent:&str, indent_depth:usize, column_limit:usize, out:&mut W, style:&TermposeStyle)-> fmt::Result {
+	//the branches being written out, with the contents each has left. An explicit stack rather than recursion, so that deep trees don't overflow
+	let mut stack = vec![(contents, indent_depth)];
+	while let Some((contents, depth)) = stack.last_mut() {
+		let depth = *depth;
+		match contents.next() {
+			Some(w)=> {
+				style.comments.print_comments(w, indent, depth, out)?;
+				out.write_char('\n')?;
+				do_indent(indent, depth, out)?;
+				if let Some(b) = maybe_inline_termpose_stringification_baseline(w, column_limit, out, style)? {
+					let contents = open_termpose_branch_lines(b, column_limit, out, style)?;
+					stack.push((contents, depth + 1));
 				}
 			}
+			None=> { stack.pop(); }
 		}
 	}
+	Ok(())
+}
+
+pub(crate) fn indent_str(indent_is_tab:bool, tab_size:usize)-> Cow<'static, str> {
+	if indent_is_tab { "\t".into() }else{ " ".repeat(tab_size).into() }
 }
 
 
-///Indents and uses pairing when appropriate.
+///Indents and uses pairing when appropriate. If the style has comment markers, any comments the woods have are written on lines of their own before them.
 
 /// # Arguments
 ///
 /// * `column_limit` - column_limit ignores indentation, only limits the length of what's beyond the indentation. The reason is... for a start, that's simpler to implement. If it had a strict limit, deeply indented code would get sort of squashed as it approaches the side, which is visually awkward, and eventually it would have to be allowed to penetrate through the limit, and I didn't want to code that. If you don't expect to indent deeply, this shouldn't make much of a difference to you. Pull requests for a more strictly constraining column limit are welcome.
 pub fn pretty_termpose_detail(w:&Wood, indent_is_tab:bool, tab_size:usize, column_limit:usize, style:&TermposeStyle)-> String {
-	let indent_string:String;
-	let indent:&str;
-	if indent_is_tab {
-		indent = "\t";
-	}else{
-		indent_string = " ".repeat(tab_size);
-		indent = indent_string.as_str();
-	}
-	
 	let mut ret = String::new();
+	fmt_termpose_detail(w, &mut ret, indent_is_tab, tab_size, column_limit, style).unwrap(); //writing to a String can't fail
+	ret
+}
+
+///`pretty_termpose_detail`, but writes into any `fmt::Write` as it goes instead of building a String
+pub fn fmt_termpose_detail<W:Write>(w:&Wood, out:&mut W, indent_is_tab:bool, tab_size:usize, column_limit:usize, style:&TermposeStyle)-> fmt::Result {
+	let indent = indent_str(indent_is_tab, tab_size);
+	style.comments.print_comments(w, &indent, 0, out)?;
 	
 	//we may need to do a special case for the first level if it's a long branch, in which case, every element should be at depth zero. This differs from the normal case where 
-	if let Some(b) = maybe_inline_termpose_stringification_baseline(w, column_limit, &mut ret, style) {
-		for iw in b.v.iter() {
-			do_termpose_stringification(iw, indent, 0, column_limit, &mut ret, style);
+	if let Some(b) = maybe_inline_termpose_stringification_baseline(w, column_limit, out, style)? {
+		if b.v.len() == 1 {
+			//a lone wood at the root would be read as the root itself, so it keeps its parens
+			termpose_branch_lines(b, &indent, 0, column_limit, out, style)?;
+		}else{
+			termpose_lines(b.v.iter(), &indent, 0, column_limit, out, style)?;
 		}
 	}
-	
-	ret
+	Ok(())
+}
+
+///`pretty_termpose_detail`, streamed out to an `io::Write`. The output is written in many small pieces, so files and sockets should be wrapped in a `BufWriter`. To write many root items one at a time, see `WoodSerializer`.
+pub fn write_termpose_detail<W:io::Write>(w:&Wood, out:W, indent_is_tab:bool, tab_size:usize, column_limit:usize, style:&TermposeStyle)-> io::Result<()> {
+	write_through(out, |out| fmt_termpose_detail(w, out, indent_is_tab, tab_size, column_limit, style))
+}
+
+///`write_termpose_detail(w, out, false, 2, 73, &DEFAULT_STYLE)`, the streaming `pretty_termpose`
+pub fn write_termpose<W:io::Write>(w:&Wood, out:W)-> io::Result<()> {
+	write_termpose_detail(w, out, false, 2, 73, &DEFAULT_STYLE)
 }
 
 ///`pretty_termpose_detail(w, false, 2, 73, &DEFAULT_STYLE)`
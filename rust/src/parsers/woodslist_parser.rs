@@ -1,31 +1,103 @@
 use super::*;
+use std::io;
+
+/// Configures the woodslist parser and printer
+#[derive(Clone, Debug)]
+pub struct WoodslistStyle {
+    /// The default style has no comments. `;` line comments and `#| |#` block comments are traditional for s-expressions.
+    pub comments: CommentStyle,
+    /// The default has no limits. The printers ignore them.
+    pub limits: ParseLimits,
+}
+
+pub static DEFAULT_WOODSLIST_STYLE: WoodslistStyle = WoodslistStyle {
+    comments: NO_COMMENTS,
+    limits: NO_LIMITS,
+};
 
 struct SexpParserState<'a> {
-    iter: std::iter::Peekable<std::str::Chars<'a>>,
+    source: &'a str,
+    file: Option<FileId>,
+    iter: std::str::Chars<'a>,
+    /// the byte offset of the char most recently taken from iter
+    pos: usize,
     line: isize,
     column: isize,
-}
-
-#[derive(PartialEq)]
-enum HowEnded {
-    FoundParen,
-    FoundEOF,
+    style: WoodslistStyle,
+    /// comments read since the last wood was created, for attaching to the next one
+    pending_comments: Vec<String>,
+    /// how many woods have been read, for `ParseLimits::max_nodes`
+    nodes: usize,
 }
 
 impl<'a> SexpParserState<'a> {
+    fn new(source: &'a str, style: WoodslistStyle, file: Option<FileId>) -> Self {
+        SexpParserState {
+            source,
+            file,
+            iter: source.chars(),
+            pos: 0,
+            line: 1,
+            column: 0,
+            style,
+            pending_comments: Vec::new(),
+            nodes: 0,
+        }
+    }
+
+    fn take_pending_comments(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.pending_comments)
+    }
+
+    /// if a comment begins at the char just taken, skips over it, keeping its text if comments are being attached. Returns whether there was one.
+    fn skip_comment(&mut self) -> Result<bool, Box<WoodError>> {
+        let start = self.pos;
+        let comment = match self.style.comments.comment_at(&self.source[start..]) {
+            Some(c) => c,
+            None => return Ok(false),
+        };
+        if !comment.closed {
+            return Err(self.a_fail(UNTERMINATED_BLOCK_COMMENT.into()));
+        }
+        if self.style.comments.attach {
+            self.pending_comments.push(comment.text.trim().to_string());
+        }
+        while self.next_pos() < start + comment.len {
+            self.move_char_ptr_and_update_line_col();
+        }
+        Ok(true)
+    }
+
+    /// a span beginning at the char most recently taken
+    fn span_from_here(&self) -> Option<Span> {
+        Some(Span::new(self.file, self.pos, self.next_pos()))
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.iter.clone().next()
+    }
+
+    fn next_pos(&self) -> usize {
+        self.source.len() - self.iter.as_str().len()
+    }
+
     fn a_fail(&self, message: String) -> Box<WoodError> {
         Box::new(WoodError {
             line: self.line,
             column: self.column,
+            span: self.span_from_here(),
+            kind: ErrorKind::Syntax,
+            path: Vec::new(),
             msg: message,
             cause: None,
         })
     }
 
     fn move_char_ptr_and_update_line_col(&mut self) -> Option<char> {
+        self.pos = self.next_pos();
         self.iter.next().and_then(|c| {
             if c == '\r' {
-                if Some(&'\n') == self.iter.peek() {
+                if Some('\n') == self.peek() {
                     //crlf support
                     self.iter.next();
                 }
@@ -44,150 +116,197 @@ impl<'a> SexpParserState<'a> {
     }
 
     fn read_escaped_char(&mut self) -> Result<char, Box<WoodError>> {
-        let nco = self.move_char_ptr_and_update_line_col();
-        let match_fail_message = "escape slash must be followed by a valid escape character code";
-        if let Some(nc) = nco {
-            match nc {
-                'n' => Ok('\n'),
-                'r' => Ok('\r'),
-                't' => Ok('\t'),
-                'h' => Ok('☃'),
-                '"' => Ok('"'),
-                '\\' => Ok('\\'),
-                _ => Err(self.a_fail(match_fail_message.into())),
-            }
-        } else {
-            Err(self.a_fail(match_fail_message.into()))
-        }
+        escaped_char(|| self.move_char_ptr_and_update_line_col()).ok_or_else(|| {
+            self.a_fail("escape slash must be followed by a valid escape character code".into())
+        })
+    }
+
+    /// counts a wood that's beginning at the char just taken
+    fn count_node(&mut self) -> Result<(), Box<WoodError>> {
+        let span = self.span_from_here();
+        self.style
+            .limits
+            .count_node(&mut self.nodes, self.line, self.column, span)
     }
 
-    fn seeking(&mut self, into: &mut Branch) -> Result<HowEnded, Box<WoodError>> {
+    /// reads the rest of the text into `root`. Branches are kept on a stack rather than read by recursion, so that no nesting is too deep to read.
+    fn seeking<S: LeafStorage<'a>>(
+        &mut self,
+        root: Branch<S>,
+    ) -> Result<Branch<S>, Box<WoodError>> {
+        //the branches that haven't been closed yet, innermost last
+        let mut open = vec![root];
         while let Some(c) = self.move_char_ptr_and_update_line_col() {
             match c {
                 '(' => {
-                    into.v.push(Branchv(Branch {
+                    self.count_node()?;
+                    if open.len() > self.style.limits.max_depth {
+                        return Err(self.style.limits.depth_error(
+                            self.line,
+                            self.column,
+                            self.span_from_here(),
+                        ));
+                    }
+                    open.push(Branch {
                         line: self.line,
                         column: self.column,
+                        span: self.span_from_here(),
+                        comments: self.take_pending_comments(),
                         v: Vec::new(),
-                    }));
-                    let b = assume_branch_mut(into.v.last_mut().unwrap());
-                    let how_inner_ended = self.seeking(b)?;
-                    if HowEnded::FoundEOF == how_inner_ended {
-                        return Err(Box::new(WoodError {
-                            msg: "unmatched opening paren".into(),
-                            line: b.line,
-                            column: b.column,
-                            cause: None,
-                        }));
-                    }
+                    });
                 }
                 ')' => {
-                    return Ok(HowEnded::FoundParen);
+                    if open.len() == 1 {
+                        return Err(self.a_fail("unmatched closing paren".into()));
+                    }
+                    let mut b = open.pop().unwrap();
+                    extend_span(&mut b.span, self.next_pos());
+                    open.last_mut().unwrap().v.push(Branchv(b));
                 }
                 ' ' | '\t' | '\n' => {}
+                _ if self.skip_comment()? => {}
                 '"' => {
-                    into.v.push(Leafv(Leaf {
+                    self.count_node()?;
+                    let mut reading_into = Leaf {
                         line: self.line,
                         column: self.column,
-                        v: String::new(),
-                    }));
-                    let reading_into = assume_leaf_mut(into.v.last_mut().unwrap());
-                    let get_char = |this:&mut SexpParserState| {
-                        this.move_char_ptr_and_update_line_col().ok_or_else(|| {
-                            this.a_fail(format!("unclosed string starting at "))
-                        })
+                        span: self.span_from_here(),
+                        comments: self.take_pending_comments(),
+                        v: S::starting_at(self.source, self.next_pos()),
+                    };
+                    let get_char = |this: &mut SexpParserState| {
+                        this.move_char_ptr_and_update_line_col()
+                            .ok_or_else(|| this.a_fail(format!("unclosed string starting at ")))
                     };
                     let mut c = get_char(self)?;
                     if c == '\n' {
                         //skip any initial newline, to allow the user to get everything lined up at the baseline, if they want.
                         c = get_char(self)?;
+                        reading_into.v = S::starting_at(self.source, self.pos);
                     }
                     loop {
                         match c {
                             '"' => {
+                                extend_span(&mut reading_into.span, self.next_pos());
                                 break;
                             }
                             '\\' => {
-                                reading_into.v.push(self.read_escaped_char()?);
+                                let ec = self.read_escaped_char()?;
+                                reading_into.v.push_from(self.source, self.pos, ec);
                             }
                             c => {
-                                reading_into.v.push(c);
+                                reading_into.v.push_from(self.source, self.pos, c);
                             }
                         }
+                        //checked as it grows, so that a leaf that's too long isn't read to the end first
+                        self.style.limits.check_leaf(&reading_into)?;
                         c = get_char(self)?;
                     }
+                    open.last_mut().unwrap().v.push(Leafv(reading_into));
                 }
                 mut c => {
-                    into.v.push(Leafv(Leaf {
+                    self.count_node()?;
+                    let mut reading_into = Leaf {
                         line: self.line,
                         column: self.column,
-                        v: String::new(),
-                    }));
-                    let reading_into = assume_leaf_mut(into.v.last_mut().unwrap());
+                        span: self.span_from_here(),
+                        comments: self.take_pending_comments(),
+                        v: S::starting_at(self.source, self.pos),
+                    };
                     loop {
                         match c {
                             '\\' => {
-                                reading_into.v.push(self.read_escaped_char()?);
+                                let ec = self.read_escaped_char()?;
+                                reading_into.v.push_from(self.source, self.pos, ec);
                             }
                             c => {
-                                reading_into.v.push(c);
+                                reading_into.v.push_from(self.source, self.pos, c);
                             }
                         }
-                        //return control without advancing it again iff the next character is interrupty, the next char can be dealt with by the outer loop
-                        if let Some(nc) = self.iter.peek() {
-                            match *nc {
-                                ' ' | '\t' | '\n' | '"' | '(' | ')' => {
-                                    break;
-                                }
-                                _ => {}
-                            }
-                        } else {
-                            return Ok(HowEnded::FoundEOF);
+                        extend_span(&mut reading_into.span, self.next_pos());
+                        self.style.limits.check_leaf(&reading_into)?;
+                        //stop without advancing again iff the next character is interrupty, the next char can be dealt with by the outer loop
+                        match self.peek() {
+                            Some(' ' | '\t' | '\n' | '\r' | '"' | '(' | ')') | None => break,
+                            Some(_) => {}
                         }
                         c = self.move_char_ptr_and_update_line_col().unwrap();
                     }
+                    open.last_mut().unwrap().v.push(Leafv(reading_into));
                 }
             }
         }
-        Ok(HowEnded::FoundEOF)
+        if open.len() > 1 {
+            let b = open.last().unwrap();
+            return Err(Box::new(WoodError {
+                msg: "unmatched opening paren".into(),
+                line: b.line,
+                column: b.column,
+                span: b.span,
+                kind: ErrorKind::Syntax,
+                path: Vec::new(),
+                cause: None,
+            }));
+        }
+        Ok(open.pop().unwrap())
     }
 }
 
-pub fn parse_multiline_woodslist<'a>(s: &'a str) -> Result<Wood, Box<WoodError>> {
-    //parses as if it's a file, and each term at root is a separate term. This is not what you want if you expect only a single line, and you want that line to be the root term, but its behaviour is more consistent if you are parsing files
-    let mut state = SexpParserState {
-        iter: s.chars().peekable(),
-        line: 1,
-        column: 1,
-    };
+fn parse_multiline_woodslist_into<'a, S: LeafStorage<'a>>(
+    s: &'a str,
+    style: WoodslistStyle,
+    file: Option<FileId>,
+) -> Result<Wood<S>, Box<WoodError>> {
+    let mut state = SexpParserState::new(s, style, file);
 
-    let mut root_branch = Branch {
+    let root_branch = Branch {
         column: 1,
         line: 1,
+        span: Some(Span::new(file, 0, s.len())),
+        comments: Vec::new(),
         v: vec![],
     };
 
-    match state.seeking(&mut root_branch)? {
-        HowEnded::FoundEOF => Ok(Branchv(root_branch)),
-        HowEnded::FoundParen => Err(Box::new(WoodError {
-            msg: "unmatched closing paren".into(),
-            line: state.line,
-            column: state.column,
-            cause: None,
-        })),
-    }
+    state.seeking(root_branch).map(Branchv)
+}
+
+pub(crate) fn parse_multiline_woodslist_in(
+    s: &str,
+    file: Option<FileId>,
+) -> Result<Wood, Box<WoodError>> {
+    parse_multiline_woodslist_into(s, DEFAULT_WOODSLIST_STYLE.clone(), file)
+}
+
+pub fn parse_multiline_woodslist<'a>(s: &'a str) -> Result<Wood, Box<WoodError>> {
+    //parses as if it's a file, and each term at root is a separate term. This is not what you want if you expect only a single line, and you want that line to be the root term, but its behaviour is more consistent if you are parsing files
+    parse_multiline_woodslist_style(s, DEFAULT_WOODSLIST_STYLE.clone())
 }
 
 pub fn parse_woodslist<'a>(s: &'a str) -> Result<Wood, Box<WoodError>> {
-    parse_multiline_woodslist(s).map(|t| {
-        let l = assume_branch(t); //parse_multiline_termpose only returns branchs
-        if l.v.len() == 1 {
-            yank_first(l.v) //just confirmed it's there
-        } else {
-            //then the caller was wrong, it wasn't a single root term, so I guess, they get the whole Branch? Maybe this should be a PositionedError... I dunno about that
-            Branchv(l)
-        }
-    })
+    parse_multiline_woodslist(s).map(unwrap_single_root)
+}
+
+/// `parse_multiline_woodslist`, recognising the style's comments
+pub fn parse_multiline_woodslist_style(
+    s: &str,
+    style: WoodslistStyle,
+) -> Result<Wood, Box<WoodError>> {
+    parse_multiline_woodslist_into(s, style, None)
+}
+
+/// `parse_woodslist`, recognising the style's comments
+pub fn parse_woodslist_style(s: &str, style: WoodslistStyle) -> Result<Wood, Box<WoodError>> {
+    parse_multiline_woodslist_style(s, style).map(unwrap_single_root)
+}
+
+/// `parse_multiline_woodslist`, but leaves that contain no escapes borrow from `s` instead of being copied
+pub fn parse_multiline_woodslist_borrowed<'a>(s: &'a str) -> Result<WoodRef<'a>, Box<WoodError>> {
+    parse_multiline_woodslist_into(s, DEFAULT_WOODSLIST_STYLE.clone(), None)
+}
+
+/// `parse_woodslist`, but leaves that contain no escapes borrow from `s` instead of being copied
+pub fn parse_woodslist_borrowed<'a>(s: &'a str) -> Result<WoodRef<'a>, Box<WoodError>> {
+    parse_multiline_woodslist_borrowed(s).map(unwrap_single_root)
 }
 
 pub fn to_woodslist(w: &Wood) -> String {
@@ -195,107 +314,232 @@ pub fn to_woodslist(w: &Wood) -> String {
     inline_stringify_woodslist(w, &mut ret);
     ret
 }
+/// `to_woodslist`, but writes into any `fmt::Write` as it goes instead of building a String
+pub fn fmt_woodslist<W: Write>(w: &Wood, out: &mut W) -> fmt::Result {
+    fmt_woodslist_commented(w, out, &NO_COMMENTS)
+}
+/// `fmt_woodslist`, quoting any leaves that would otherwise be read as comments
+fn fmt_woodslist_commented<W: Write>(
+    w: &Wood,
+    out: &mut W,
+    comments: &CommentStyle,
+) -> fmt::Result {
+    match *w {
+        Branchv(ref b) => fmt_woodslist_branch(b, out, comments),
+        Leafv(ref v) => fmt_leaf_woodslist(v, out, comments),
+    }
+}
+/// `to_woodslist`, streamed out to an `io::Write`. The output is written in many small pieces, so files and sockets should be wrapped in a `BufWriter`.
+pub fn write_woodslist<W: io::Write>(w: &Wood, out: W) -> io::Result<()> {
+    write_through(out, |out| fmt_woodslist(w, out))
+}
 pub fn stringify_leaf_woodslist(v: &Leaf, s: &mut String) {
-    let needs_quotes =
-        v.v.chars()
-            .any(|c| c == ' ' || c == '\t' || c == '(' || c == ')');
+    fmt_leaf_woodslist(v, s, &NO_COMMENTS).unwrap(); //writing to a String can't fail
+}
+fn fmt_leaf_woodslist<W: Write>(v: &Leaf, s: &mut W, comments: &CommentStyle) -> fmt::Result {
+    //an empty leaf would be nothing at all, and a leaf that begins with a comment marker would be read as a comment
+    let needs_quotes = v.v.is_empty()
+        || comments.comment_at(&v.v).is_some()
+        || v.v
+            .chars()
+            .any(|c| c == ' ' || c == '\t' || c == '(' || c == ')' || c == '"');
     if needs_quotes {
-        s.push('"');
+        s.write_char('"')?;
     }
-    push_escaped(s, v.v.as_str());
+    write_escaped(s, v.v.as_str())?;
     if needs_quotes {
-        s.push('"');
+        s.write_char('"')?;
     }
+    Ok(())
 }
 pub fn inline_stringify_woodslist_branch(b: &Branch, s: &mut String) {
-    s.push('(');
-    //space separated
-    let mut i = b.v.iter();
-    if let Some(ref first) = i.next() {
-        inline_stringify_woodslist(first, s);
-        while let Some(ref nexto) = i.next() {
-            s.push(' ');
-            inline_stringify_woodslist(nexto, s);
+    fmt_woodslist_branch(b, s, &NO_COMMENTS).unwrap();
+}
+fn fmt_woodslist_branch<W: Write>(b: &Branch, s: &mut W, comments: &CommentStyle) -> fmt::Result {
+    //the branches that have been opened, with the contents each has left and whether any have been written yet. An explicit stack rather than recursion, so that deep trees don't overflow
+    s.write_char('(')?;
+    let mut stack = vec![(b.v.iter(), true)];
+    while let Some((contents, first)) = stack.last_mut() {
+        match contents.next() {
+            Some(w) => {
+                //space separated
+                if !std::mem::replace(first, false) {
+                    s.write_char(' ')?;
+                }
+                match *w {
+                    Branchv(ref c) => {
+                        s.write_char('(')?;
+                        stack.push((c.v.iter(), true));
+                    }
+                    Leafv(ref l) => fmt_leaf_woodslist(l, s, comments)?,
+                }
+            }
+            None => {
+                s.write_char(')')?;
+                stack.pop();
+            }
         }
     }
-    s.push(')');
+    Ok(())
 }
 pub fn inline_stringify_woodslist(w: &Wood, s: &mut String) {
-    match *w {
-        Branchv(ref b) => {
-            inline_stringify_woodslist_branch(b, s);
+    fmt_woodslist(w, s).unwrap();
+}
+/// how long `b` would be on one line, which stops counting once it passes `limit`, so that a big branch isn't walked all the way through (again for each branch it's in) only to find that it doesn't fit
+fn woodslist_inline_length_estimate_for_branch(b: &Branch, limit: usize) -> usize {
+    let mut ret = 0;
+    let mut stack = Vec::new();
+    let mut next = Some(b);
+    loop {
+        if let Some(b) = next.take() {
+            //2 for parens, and the spaces between
+            ret += 2 + b.v.len().saturating_sub(1);
+            stack.push(b.v.iter());
         }
-        Leafv(ref v) => {
-            stringify_leaf_woodslist(v, s);
+        if ret > limit {
+            return ret;
+        }
+        match stack.last_mut() {
+            Some(contents) => match contents.next() {
+                Some(Branchv(ref c)) => next = Some(c),
+                Some(Leafv(ref l)) => ret += l.v.len(),
+                None => {
+                    stack.pop();
+                }
+            },
+            None => return ret,
         }
     }
 }
-fn woodslist_inline_length_estimate_for_branch(b: &Branch) -> usize {
-    let mut ret = 2; //2 for parens
-    if b.v.len() > 0 {
-        let spaces_length = b.v.len() - 1;
-        ret += b.v.iter().fold(spaces_length, |n, w| {
-            n + woodslist_inline_length_estimate(w)
-        });
-    }
-    ret
-}
-fn woodslist_inline_length_estimate(w: &Wood) -> usize {
-    match w {
-        &Branchv(ref b) => woodslist_inline_length_estimate_for_branch(b),
-        &Leafv(ref l) => l.v.len(),
-    }
-}
-fn maybe_inline_woodslist_stringification<'a>(
+fn maybe_inline_woodslist_stringification<'a, W: Write>(
     w: &'a Wood,
     column_limit: usize,
-    out: &mut String,
-) -> Option<&'a Branch> {
-    //returns Some Branch that w is iff it did NOT insert it inline (because it didn't have room)
+    out: &mut W,
+    comments: &CommentStyle,
+) -> Result<Option<&'a Branch>, fmt::Error> {
+    //returns Some Branch that w is iff it did NOT insert it inline (because it didn't have room, or because there are comments inside it that need lines of their own)
     match w {
         &Branchv(ref b) => {
-            if woodslist_inline_length_estimate_for_branch(b) > column_limit {
-                return Some(b);
+            //the estimate goes first, as it stops early, so comments are only looked for in branches small enough to fit
+            if woodslist_inline_length_estimate_for_branch(b, column_limit) > column_limit
+                || comments.comments_within(w)
+            {
+                return Ok(Some(b));
             } else {
-                inline_stringify_woodslist_branch(b, out);
+                fmt_woodslist_branch(b, out, comments)?;
             }
         }
         &Leafv(ref l) => {
-            stringify_leaf_woodslist(l, out);
+            fmt_leaf_woodslist(l, out, comments)?;
         }
     }
-    None
+    Ok(None)
 }
-fn do_woodslist_stringification(
+pub(crate) fn do_woodslist_stringification<W: Write>(
     w: &Wood,
     indent: &str,
     indent_depth: usize,
     column_limit: usize,
-    out: &mut String,
-) {
-    out.push('\n');
-    do_indent(indent, indent_depth, out);
-    if let Some(b) = maybe_inline_woodslist_stringification(w, column_limit, out) {
-        let mut bi = b.v.iter();
-        out.push('(');
-        if let Some(fw) = bi.next() {
-            if let Some(_fwb) = maybe_inline_woodslist_stringification(fw, column_limit - 1, out) {
-                //column_limit - 1 because there's an opening paren in the line
-                //then the first one wont fit in the first one position
-                out.push('\n');
-                for iw in b.v.iter() {
-                    do_woodslist_stringification(iw, indent, indent_depth + 1, column_limit, out);
+    out: &mut W,
+    comments: &CommentStyle,
+) -> fmt::Result {
+    woodslist_lines(
+        std::slice::from_ref(w).iter(),
+        false,
+        indent,
+        indent_depth,
+        column_limit,
+        out,
+        comments,
+    )
+}
+
+/// writes out a branch that didn't fit on one line, with the contents after the first on lines of their own
+fn woodslist_branch_lines<W: Write>(
+    b: &Branch,
+    indent: &str,
+    indent_depth: usize,
+    column_limit: usize,
+    out: &mut W,
+    comments: &CommentStyle,
+) -> fmt::Result {
+    let contents = open_woodslist_branch_lines(b, column_limit, out, comments)?;
+    woodslist_lines(
+        contents,
+        true,
+        indent,
+        indent_depth + 1,
+        column_limit,
+        out,
+        comments,
+    )
+}
+
+/// writes the opening paren of a branch that didn't fit on one line, and its first wood if that fits after it, returning the contents that need lines of their own
+fn open_woodslist_branch_lines<'a, W: Write>(
+    b: &'a Branch,
+    column_limit: usize,
+    out: &mut W,
+    comments: &CommentStyle,
+) -> Result<std::slice::Iter<'a, Wood>, fmt::Error> {
+    let mut bi = b.v.iter();
+    out.write_char('(')?;
+    if let Some(fw) = bi.next() {
+        //column_limit - 1 because there's an opening paren in the line. (Comments within fw are seen to by maybe_inline_woodslist_stringification.)
+        if comments.has_comments(fw)
+            || maybe_inline_woodslist_stringification(
+                fw,
+                column_limit.saturating_sub(1),
+                out,
+                comments,
+            )?
+            .is_some()
+        {
+            //then the first one wont fit in the first one position
+            out.write_char('\n')?;
+            return Ok(b.v.iter());
+        }
+    }
+    Ok(bi)
+}
+
+/// writes each of `contents` on lines of their own at `indent_depth`, then, if they `close` a branch, its closing paren on the line after
+fn woodslist_lines<W: Write>(
+    contents: std::slice::Iter<Wood>,
+    close: bool,
+    indent: &str,
+    indent_depth: usize,
+    column_limit: usize,
+    out: &mut W,
+    comments: &CommentStyle,
+) -> fmt::Result {
+    //the branches being written out, with the contents each has left. An explicit stack rather than recursion, so that deep trees don't overflow
+    let mut stack = vec![(contents, indent_depth, close)];
+    while let Some((contents, depth, close)) = stack.last_mut() {
+        let (depth, close) = (*depth, *close);
+        match contents.next() {
+            Some(w) => {
+                comments.print_comments(w, indent, depth, out)?;
+                out.write_char('\n')?;
+                do_indent(indent, depth, out)?;
+                if let Some(b) =
+                    maybe_inline_woodslist_stringification(w, column_limit, out, comments)?
+                {
+                    let contents = open_woodslist_branch_lines(b, column_limit, out, comments)?;
+                    stack.push((contents, depth + 1, true));
                 }
-            } else {
-                for iw in bi {
-                    do_woodslist_stringification(iw, indent, indent_depth + 1, column_limit, out);
+            }
+            None => {
+                if close {
+                    out.write_char('\n')?;
+                    do_indent(indent, depth - 1, out)?;
+                    out.write_char(')')?;
                 }
+                stack.pop();
             }
         }
-        out.push('\n');
-        do_indent(indent, indent_depth, out);
-        out.push(')');
     }
+    Ok(())
 }
 
 /// formats a Wood into readable indented woodslist notation
@@ -309,25 +553,71 @@ pub fn indented_woodslist_detail(
     tab_size: usize,
     column_limit: usize,
 ) -> String {
-    let indent_string: String;
-    let indent: &str;
-    if indent_is_tab {
-        indent = "\t";
-    } else {
-        indent_string = " ".repeat(tab_size);
-        indent = indent_string.as_str();
-    }
+    indented_woodslist_style(
+        w,
+        indent_is_tab,
+        tab_size,
+        column_limit,
+        &DEFAULT_WOODSLIST_STYLE,
+    )
+}
 
+/// `indented_woodslist_detail`, also writing out any comments the woods have, on lines of their own before the woods, if the style has comment markers
+pub fn indented_woodslist_style(
+    w: &Wood,
+    indent_is_tab: bool,
+    tab_size: usize,
+    column_limit: usize,
+    style: &WoodslistStyle,
+) -> String {
     let mut ret = String::new();
+    fmt_indented_woodslist_style(w, &mut ret, indent_is_tab, tab_size, column_limit, style)
+        .unwrap(); //writing to a String can't fail
+    ret
+}
+
+/// `indented_woodslist_style`, but writes into any `fmt::Write` as it goes instead of building a String
+pub fn fmt_indented_woodslist_style<W: Write>(
+    w: &Wood,
+    out: &mut W,
+    indent_is_tab: bool,
+    tab_size: usize,
+    column_limit: usize,
+    style: &WoodslistStyle,
+) -> fmt::Result {
+    let comments = &style.comments;
+    let indent = indent_str(indent_is_tab, tab_size);
+    comments.print_comments(w, &indent, 0, out)?;
 
     //we may need to do a special case for the first level if it's a long branch, in which case, every element should be at depth zero. This differs from the normal case where
-    if let Some(b) = maybe_inline_woodslist_stringification(w, column_limit, &mut ret) {
-        for iw in b.v.iter() {
-            do_woodslist_stringification(iw, indent, 0, column_limit, &mut ret);
+    if let Some(b) = maybe_inline_woodslist_stringification(w, column_limit, out, comments)? {
+        if b.v.len() == 1 {
+            //a lone wood at the root would be read as the root itself, so it keeps its parens
+            woodslist_branch_lines(b, &indent, 0, column_limit, out, comments)?;
+        } else {
+            woodslist_lines(b.v.iter(), false, &indent, 0, column_limit, out, comments)?;
         }
     }
+    Ok(())
+}
 
-    ret
+/// `indented_woodslist_style`, streamed out to an `io::Write`. The output is written in many small pieces, so files and sockets should be wrapped in a `BufWriter`. To write many root items one at a time, see `WoodSerializer`.
+pub fn write_indented_woodslist_style<W: io::Write>(
+    w: &Wood,
+    out: W,
+    indent_is_tab: bool,
+    tab_size: usize,
+    column_limit: usize,
+    style: &WoodslistStyle,
+) -> io::Result<()> {
+    write_through(out, |out| {
+        fmt_indented_woodslist_style(w, out, indent_is_tab, tab_size, column_limit, style)
+    })
+}
+
+/// `write_indented_woodslist_style(w, out, false, 2, 73, &DEFAULT_WOODSLIST_STYLE)`, the streaming `indented_woodslist`
+pub fn write_indented_woodslist<W: io::Write>(w: &Wood, out: W) -> io::Result<()> {
+    write_indented_woodslist_style(w, out, false, 2, 73, &DEFAULT_WOODSLIST_STYLE)
 }
 
 /// `indented_woodslist_detail(w, false, 2, 73)`
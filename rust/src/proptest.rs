@@ -0,0 +1,81 @@
+//! proptest `Strategy`s that generate woods and texts, for property testing code that reads or writes them. The crate's own round trip tests are built on these.
+//!
+//! ```
+//! use proptest::{prelude::*, test_runner::TestRunner};
+//! use wood::{parse_woodslist, to_woodslist};
+//!
+//! TestRunner::new(ProptestConfig::with_cases(64))
+//!     .run(&wood::proptest::wood(), |w| {
+//!         prop_assert_eq!(&w, &parse_woodslist(&to_woodslist(&w)).unwrap());
+//!         Ok(())
+//!     })
+//!     .unwrap();
+//! ```
+
+use super::*;
+use ::proptest::{collection::vec, prelude::*, sample::select};
+
+/// whitespace, the characters that mean something to one of the formats or the comment styles, and a few ordinary ones
+const TELLING_CHARS: &[char] = &[
+    ' ', '\t', '\n', '\r', '"', '\\', '(', ')', ':', ';', '#', '|', '/', '*', 'a', '☃',
+];
+
+/// pieces of termpose and woodslist, most of which don't make sense where they'll end up
+const FRAGMENTS: &[&str] = &[
+    "a",
+    "bc",
+    " ",
+    "  ",
+    "\t",
+    "\n",
+    "\r\n",
+    "\r",
+    "\n  ",
+    "\n\t",
+    "(",
+    ")",
+    ":",
+    "\"",
+    "\\",
+    "\\n",
+    "\\u{1F600}",
+    "\\u{",
+    "//",
+    "/*",
+    "*/",
+    ";",
+    "#|",
+    "|#",
+    "☃",
+];
+
+/// Up to 5 chars, mostly ones that have to be quoted or escaped in one of the formats. Any char can turn up.
+pub fn leaf_text() -> impl Strategy<Value = String> {
+    vec(
+        prop_oneof![3 => select(TELLING_CHARS), 1 => any::<char>()],
+        0..6,
+    )
+    .prop_map(|cs| cs.into_iter().collect())
+}
+
+/// A leaf of `leaf_text`.
+pub fn leaf() -> impl Strategy<Value = Wood> {
+    leaf_text().prop_map(Wood::leaf)
+}
+
+/// Woods nested up to `depth` branches deep, of around `size` woods in all, with up to `width` woods in each branch. Branches may be empty.
+pub fn wood_with(depth: u32, size: u32, width: usize) -> impl Strategy<Value = Wood> {
+    leaf().prop_recursive(depth, size, width as u32, move |inner| {
+        vec(inner, 0..=width).prop_map(Wood::branch)
+    })
+}
+
+/// `wood_with(5, 48, 4)`
+pub fn wood() -> impl Strategy<Value = Wood> {
+    wood_with(5, 48, 4)
+}
+
+/// Texts made of scraps of termpose and woodslist. Few of them parse, and the ones that don't go down the parsers' error paths.
+pub fn text() -> impl Strategy<Value = String> {
+    vec(select(FRAGMENTS), 0..40).prop_map(|fs| fs.concat())
+}
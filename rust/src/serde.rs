@@ -0,0 +1,1006 @@
+//! A serde `Serializer` that produces `Wood`, and a serde `Deserializer` that reads from `&Wood`.
+//!
+//! The shapes are the same ones `wood_derive` produces, so types deriving serde and types deriving `Woodable`/`Dewoodable` can read each other's output:
+//!
+//! * structs are `StructName (field value) (field value) ...`
+//! * tuple structs are `StructName a b ...`, unit structs are `(StructName)`
+//! * unit variants are a leaf with the variant's name, other variants are `Variant ...` in the same shape as the structs, tagged by `initial_str`
+//! * sequences and tuples are branches, maps are branches of `(key value)` pairs
+//! * `None` is the empty branch, `Some(v)` is just `v`. (This means `Some` of something that woodifies as `()` will come back as `None`.) Struct fields that are `None` are left out, as they are by the derives.
+
+use super::*;
+use ::serde::{
+    de::{self, DeserializeOwned, DeserializeSeed, Visitor},
+    ser::{self, Serialize},
+    Deserialize,
+};
+
+impl ser::Error for Box<WoodError> {
+    fn custom<T: Display>(msg: T) -> Self {
+        Box::new(WoodError {
+            line: -1,
+            column: -1,
+            span: None,
+            kind: ErrorKind::Other,
+            path: Vec::new(),
+            msg: msg.to_string(),
+            cause: None,
+        })
+    }
+}
+impl de::Error for Box<WoodError> {
+    fn custom<T: Display>(msg: T) -> Self {
+        <Self as ser::Error>::custom(msg)
+    }
+    fn invalid_length(len: usize, exp: &dyn de::Expected) -> Self {
+        let mut e = Self::custom(format_args!("invalid length {}, expected {}", len, exp));
+        e.kind = ErrorKind::ArityMismatch;
+        e
+    }
+    fn unknown_variant(variant: &str, expected: &'static [&'static str]) -> Self {
+        let mut e = Self::custom(format_args!(
+            "unknown variant `{}`, {}",
+            variant,
+            one_of(expected, "variants")
+        ));
+        e.kind = ErrorKind::UnknownVariant;
+        e
+    }
+    fn unknown_field(field: &str, expected: &'static [&'static str]) -> Self {
+        let mut e = Self::custom(format_args!(
+            "unknown field `{}`, {}",
+            field,
+            one_of(expected, "fields")
+        ));
+        e.kind = ErrorKind::UnknownKey;
+        e.within(PathSegment::Key(field.to_string()))
+    }
+    fn missing_field(field: &'static str) -> Self {
+        let mut e = Self::custom(format_args!("missing field `{}`", field));
+        e.kind = ErrorKind::MissingKey;
+        e.within(PathSegment::Key(field.to_string()))
+    }
+}
+/// the end of serde's own messages about unknown names, eg, "expected one of `a`, `b`, `c`"
+fn one_of(names: &[&str], what: &str) -> String {
+    match *names {
+        [] => format!("there are no {}", what),
+        [a] => format!("expected `{}`", a),
+        [a, b] => format!("expected either `{}` or `{}`", a, b),
+        _ => format!("expected one of `{}`", names.join("`, `")),
+    }
+}
+
+/// errors raised by serde's generated code don't know where they are, this gives them the position of the wood that was being read when they occurred
+fn locate(mut e: Box<WoodError>, at: &Wood) -> Box<WoodError> {
+    if e.line == -1 {
+        let (line, column) = at.line_and_col();
+        e.line = line;
+        e.column = column;
+        e.span = at.span();
+    }
+    e
+}
+
+/// `value.serialize(Serializer)`
+pub fn to_wood<T>(value: &T) -> Result<Wood, Box<WoodError>>
+where
+    T: Serialize + ?Sized,
+{
+    value.serialize(Serializer)
+}
+/// `T::deserialize(Deserializer::new(v))`
+pub fn from_wood<'de, T>(v: &'de Wood) -> Result<T, Box<WoodError>>
+where
+    T: Deserialize<'de>,
+{
+    T::deserialize(Deserializer::new(v))
+}
+/// `to_wood(value).map(|w| pretty_termpose(&w))`
+pub fn to_termpose<T>(value: &T) -> Result<String, Box<WoodError>>
+where
+    T: Serialize + ?Sized,
+{
+    to_wood(value).map(|w| pretty_termpose(&w))
+}
+/// `parse_termpose(v).and_then(|w| from_wood(&w))`
+pub fn from_termpose<T>(v: &str) -> Result<T, Box<WoodError>>
+where
+    T: DeserializeOwned,
+{
+    parse_termpose(v).and_then(|w| from_wood(&w))
+}
+/// `to_wood(value).map(|w| crate::to_woodslist(&w))`
+pub fn to_woodslist<T>(value: &T) -> Result<String, Box<WoodError>>
+where
+    T: Serialize + ?Sized,
+{
+    to_wood(value).map(|w| crate::to_woodslist(&w))
+}
+/// `parse_woodslist(v).and_then(|w| from_wood(&w))`
+pub fn from_woodslist<T>(v: &str) -> Result<T, Box<WoodError>>
+where
+    T: DeserializeOwned,
+{
+    parse_woodslist(v).and_then(|w| from_wood(&w))
+}
+
+/// Serializes any `Serialize` into a `Wood`
+pub struct Serializer;
+
+fn tagged(tag: &'static str, rest: Vec<Wood>) -> Wood {
+    let mut v = Vec::with_capacity(rest.len() + 1);
+    v.push(tag.into());
+    v.extend(rest);
+    v.into()
+}
+
+impl ser::Serializer for Serializer {
+    type Ok = Wood;
+    type Error = Box<WoodError>;
+    type SerializeSeq = SeqSerializer;
+    type SerializeTuple = SeqSerializer;
+    type SerializeTupleStruct = SeqSerializer;
+    type SerializeTupleVariant = SeqSerializer;
+    type SerializeMap = MapSerializer;
+    type SerializeStruct = SeqSerializer;
+    type SerializeStructVariant = SeqSerializer;
+
+    fn serialize_bool(self, v: bool) -> Result<Wood, Box<WoodError>> {
+        Ok(v.woodify())
+    }
+    fn serialize_i8(self, v: i8) -> Result<Wood, Box<WoodError>> {
+        Ok(v.to_string().into())
+    }
+    fn serialize_i16(self, v: i16) -> Result<Wood, Box<WoodError>> {
+        Ok(v.to_string().into())
+    }
+    fn serialize_i32(self, v: i32) -> Result<Wood, Box<WoodError>> {
+        Ok(v.woodify())
+    }
+    fn serialize_i64(self, v: i64) -> Result<Wood, Box<WoodError>> {
+        Ok(v.woodify())
+    }
+    fn serialize_i128(self, v: i128) -> Result<Wood, Box<WoodError>> {
+        Ok(v.woodify())
+    }
+    fn serialize_u8(self, v: u8) -> Result<Wood, Box<WoodError>> {
+        Ok(v.to_string().into())
+    }
+    fn serialize_u16(self, v: u16) -> Result<Wood, Box<WoodError>> {
+        Ok(v.to_string().into())
+    }
+    fn serialize_u32(self, v: u32) -> Result<Wood, Box<WoodError>> {
+        Ok(v.woodify())
+    }
+    fn serialize_u64(self, v: u64) -> Result<Wood, Box<WoodError>> {
+        Ok(v.woodify())
+    }
+    fn serialize_u128(self, v: u128) -> Result<Wood, Box<WoodError>> {
+        Ok(v.woodify())
+    }
+    fn serialize_f32(self, v: f32) -> Result<Wood, Box<WoodError>> {
+        Ok(v.woodify())
+    }
+    fn serialize_f64(self, v: f64) -> Result<Wood, Box<WoodError>> {
+        Ok(v.woodify())
+    }
+    fn serialize_char(self, v: char) -> Result<Wood, Box<WoodError>> {
+        Ok(v.woodify())
+    }
+    fn serialize_str(self, v: &str) -> Result<Wood, Box<WoodError>> {
+        Ok(v.into())
+    }
+    fn serialize_bytes(self, v: &[u8]) -> Result<Wood, Box<WoodError>> {
+        Ok(v.iter()
+            .map(|b| Wood::from(b.to_string()))
+            .collect::<Vec<_>>()
+            .into())
+    }
+    fn serialize_none(self) -> Result<Wood, Box<WoodError>> {
+        Ok(Wood::empty())
+    }
+    fn serialize_some<T>(self, value: &T) -> Result<Wood, Box<WoodError>>
+    where
+        T: Serialize + ?Sized,
+    {
+        value.serialize(self)
+    }
+    fn serialize_unit(self) -> Result<Wood, Box<WoodError>> {
+        Ok(Wood::empty())
+    }
+    fn serialize_unit_struct(self, name: &'static str) -> Result<Wood, Box<WoodError>> {
+        Ok(woods!(name))
+    }
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Wood, Box<WoodError>> {
+        Ok(variant.into())
+    }
+    fn serialize_newtype_struct<T>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<Wood, Box<WoodError>>
+    where
+        T: Serialize + ?Sized,
+    {
+        Ok(woods!(name, value.serialize(self)?))
+    }
+    fn serialize_newtype_variant<T>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Wood, Box<WoodError>>
+    where
+        T: Serialize + ?Sized,
+    {
+        Ok(woods!(variant, value.serialize(self)?))
+    }
+    fn serialize_seq(self, len: Option<usize>) -> Result<SeqSerializer, Box<WoodError>> {
+        Ok(SeqSerializer {
+            tag: None,
+            v: Vec::with_capacity(len.unwrap_or(0)),
+        })
+    }
+    fn serialize_tuple(self, len: usize) -> Result<SeqSerializer, Box<WoodError>> {
+        self.serialize_seq(Some(len))
+    }
+    fn serialize_tuple_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<SeqSerializer, Box<WoodError>> {
+        Ok(SeqSerializer {
+            tag: Some(name),
+            v: Vec::with_capacity(len),
+        })
+    }
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SeqSerializer, Box<WoodError>> {
+        self.serialize_tuple_struct(variant, len)
+    }
+    fn serialize_map(self, len: Option<usize>) -> Result<MapSerializer, Box<WoodError>> {
+        Ok(MapSerializer {
+            v: Vec::with_capacity(len.unwrap_or(0)),
+            key: None,
+        })
+    }
+    fn serialize_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<SeqSerializer, Box<WoodError>> {
+        self.serialize_tuple_struct(name, len)
+    }
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SeqSerializer, Box<WoodError>> {
+        self.serialize_tuple_struct(variant, len)
+    }
+}
+
+/// Accumulates the elements of sequences, tuples and structs, prefixed by `tag` if there is one
+pub struct SeqSerializer {
+    tag: Option<&'static str>,
+    v: Vec<Wood>,
+}
+impl SeqSerializer {
+    fn finish(self) -> Wood {
+        match self.tag {
+            Some(tag) => tagged(tag, self.v),
+            None => self.v.into(),
+        }
+    }
+}
+impl ser::SerializeSeq for SeqSerializer {
+    type Ok = Wood;
+    type Error = Box<WoodError>;
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Box<WoodError>>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.v.push(value.serialize(Serializer)?);
+        Ok(())
+    }
+    fn end(self) -> Result<Wood, Box<WoodError>> {
+        Ok(self.finish())
+    }
+}
+impl ser::SerializeTuple for SeqSerializer {
+    type Ok = Wood;
+    type Error = Box<WoodError>;
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Box<WoodError>>
+    where
+        T: Serialize + ?Sized,
+    {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+    fn end(self) -> Result<Wood, Box<WoodError>> {
+        Ok(self.finish())
+    }
+}
+impl ser::SerializeTupleStruct for SeqSerializer {
+    type Ok = Wood;
+    type Error = Box<WoodError>;
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Box<WoodError>>
+    where
+        T: Serialize + ?Sized,
+    {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+    fn end(self) -> Result<Wood, Box<WoodError>> {
+        Ok(self.finish())
+    }
+}
+impl ser::SerializeTupleVariant for SeqSerializer {
+    type Ok = Wood;
+    type Error = Box<WoodError>;
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Box<WoodError>>
+    where
+        T: Serialize + ?Sized,
+    {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+    fn end(self) -> Result<Wood, Box<WoodError>> {
+        Ok(self.finish())
+    }
+}
+impl ser::SerializeStruct for SeqSerializer {
+    type Ok = Wood;
+    type Error = Box<WoodError>;
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Box<WoodError>>
+    where
+        T: Serialize + ?Sized,
+    {
+        //absent means None
+        if !matches!(value.serialize(IsNone), Ok(true)) {
+            self.v.push(woods!(key, value.serialize(Serializer)?));
+        }
+        Ok(())
+    }
+    fn end(self) -> Result<Wood, Box<WoodError>> {
+        Ok(self.finish())
+    }
+}
+impl ser::SerializeStructVariant for SeqSerializer {
+    type Ok = Wood;
+    type Error = Box<WoodError>;
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Box<WoodError>>
+    where
+        T: Serialize + ?Sized,
+    {
+        ser::SerializeStruct::serialize_field(self, key, value)
+    }
+    fn end(self) -> Result<Wood, Box<WoodError>> {
+        Ok(self.finish())
+    }
+}
+
+/// Tells whether a value serializes as `None`, without serializing any more of it than that
+struct IsNone;
+macro_rules! not_none {
+    ($($method:ident($($arg:ty),*);)*) => {
+        $(fn $method(self, $(_: $arg),*) -> Result<bool, Box<WoodError>> {
+            Ok(false)
+        })*
+    };
+}
+macro_rules! not_none_compound {
+    ($($method:ident($($arg:ty),*) -> $ret:ident;)*) => {
+        $(fn $method(self, $(_: $arg),*) -> Result<Self::$ret, Box<WoodError>> {
+            Err(<Box<WoodError> as ser::Error>::custom("not None"))
+        })*
+    };
+}
+impl ser::Serializer for IsNone {
+    type Ok = bool;
+    type Error = Box<WoodError>;
+    type SerializeSeq = ser::Impossible<bool, Box<WoodError>>;
+    type SerializeTuple = ser::Impossible<bool, Box<WoodError>>;
+    type SerializeTupleStruct = ser::Impossible<bool, Box<WoodError>>;
+    type SerializeTupleVariant = ser::Impossible<bool, Box<WoodError>>;
+    type SerializeMap = ser::Impossible<bool, Box<WoodError>>;
+    type SerializeStruct = ser::Impossible<bool, Box<WoodError>>;
+    type SerializeStructVariant = ser::Impossible<bool, Box<WoodError>>;
+
+    fn serialize_none(self) -> Result<bool, Box<WoodError>> {
+        Ok(true)
+    }
+    not_none! {
+        serialize_bool(bool);
+        serialize_i8(i8);
+        serialize_i16(i16);
+        serialize_i32(i32);
+        serialize_i64(i64);
+        serialize_i128(i128);
+        serialize_u8(u8);
+        serialize_u16(u16);
+        serialize_u32(u32);
+        serialize_u64(u64);
+        serialize_u128(u128);
+        serialize_f32(f32);
+        serialize_f64(f64);
+        serialize_char(char);
+        serialize_str(&str);
+        serialize_bytes(&[u8]);
+        serialize_unit();
+        serialize_unit_struct(&'static str);
+        serialize_unit_variant(&'static str, u32, &'static str);
+    }
+    fn serialize_some<T>(self, _: &T) -> Result<bool, Box<WoodError>>
+    where
+        T: Serialize + ?Sized,
+    {
+        Ok(false)
+    }
+    fn serialize_newtype_struct<T>(self, _: &'static str, _: &T) -> Result<bool, Box<WoodError>>
+    where
+        T: Serialize + ?Sized,
+    {
+        Ok(false)
+    }
+    fn serialize_newtype_variant<T>(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: &T,
+    ) -> Result<bool, Box<WoodError>>
+    where
+        T: Serialize + ?Sized,
+    {
+        Ok(false)
+    }
+    not_none_compound! {
+        serialize_seq(Option<usize>) -> SerializeSeq;
+        serialize_tuple(usize) -> SerializeTuple;
+        serialize_tuple_struct(&'static str, usize) -> SerializeTupleStruct;
+        serialize_tuple_variant(&'static str, u32, &'static str, usize) -> SerializeTupleVariant;
+        serialize_map(Option<usize>) -> SerializeMap;
+        serialize_struct(&'static str, usize) -> SerializeStruct;
+        serialize_struct_variant(&'static str, u32, &'static str, usize) -> SerializeStructVariant;
+    }
+}
+
+/// Accumulates `(key value)` pairs
+pub struct MapSerializer {
+    v: Vec<Wood>,
+    key: Option<Wood>,
+}
+impl ser::SerializeMap for MapSerializer {
+    type Ok = Wood;
+    type Error = Box<WoodError>;
+    fn serialize_key<T>(&mut self, key: &T) -> Result<(), Box<WoodError>>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.key = Some(key.serialize(Serializer)?);
+        Ok(())
+    }
+    fn serialize_value<T>(&mut self, value: &T) -> Result<(), Box<WoodError>>
+    where
+        T: Serialize + ?Sized,
+    {
+        let key = self
+            .key
+            .take()
+            .ok_or_else(|| <Box<WoodError> as ser::Error>::custom("map value without a key"))?;
+        self.v.push(woods!(key, value.serialize(Serializer)?));
+        Ok(())
+    }
+    fn end(self) -> Result<Wood, Box<WoodError>> {
+        Ok(self.v.into())
+    }
+}
+
+/// Deserializes any `Deserialize` from a `&Wood`. Strings are borrowed from the wood where possible.
+#[derive(Clone, Copy)]
+pub struct Deserializer<'de> {
+    v: &'de Wood,
+}
+impl<'de> Deserializer<'de> {
+    pub fn new(v: &'de Wood) -> Self {
+        Deserializer { v }
+    }
+    fn parse_scalar<T>(&self, type_name: &str) -> Result<T, Box<WoodError>>
+    where
+        T: FromStr,
+        T::Err: Error + 'static,
+    {
+        T::from_str(self.v.initial_str()).map_err(|er| {
+            Box::new(
+                WoodError::new_with_cause(
+                    self.v,
+                    format!("couldn't parse {}", type_name),
+                    Box::new(er),
+                )
+                .with_kind(ErrorKind::ParseScalar),
+            )
+        })
+    }
+    fn leaf_str(&self) -> Result<&'de str, Box<WoodError>> {
+        self.v.get_leaf().ok_or_else(|| {
+            Box::new(
+                WoodError::new(self.v, "sought string, found branch".into())
+                    .with_kind(ErrorKind::UnexpectedBranch),
+            )
+        })
+    }
+    fn tail_of_length(&self, len: usize, of_what: &str) -> Result<&'de [Wood], Box<WoodError>> {
+        let li = self.v.tail().as_slice();
+        if li.len() == len {
+            Ok(li)
+        } else {
+            Err(Box::new(
+                WoodError::new(
+                    self.v,
+                    format!(
+                        "{} expected the wood to have {} elements, but it has {}",
+                        of_what,
+                        len,
+                        li.len()
+                    ),
+                )
+                .with_kind(ErrorKind::ArityMismatch),
+            ))
+        }
+    }
+}
+
+macro_rules! deserialize_scalar {
+    ($method:ident, $visit:ident, $Type:ident) => {
+        fn $method<V>(self, visitor: V) -> Result<V::Value, Box<WoodError>>
+        where
+            V: Visitor<'de>,
+        {
+            let v: $Type = self.parse_scalar(stringify!($Type))?;
+            visitor.$visit(v).map_err(|e| locate(e, self.v))
+        }
+    };
+}
+
+impl<'de> de::Deserializer<'de> for Deserializer<'de> {
+    type Error = Box<WoodError>;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Box<WoodError>>
+    where
+        V: Visitor<'de>,
+    {
+        match *self.v {
+            Leafv(ref l) => visitor.visit_borrowed_str(l.v.as_str()),
+            Branchv(ref b) => visitor.visit_seq(SeqAccess::new(b.v.iter())),
+        }
+        .map_err(|e| locate(e, self.v))
+    }
+
+    fn deserialize_bool<V>(self, visitor: V) -> Result<V::Value, Box<WoodError>>
+    where
+        V: Visitor<'de>,
+    {
+        visitor
+            .visit_bool(bool::dewoodify(self.v)?)
+            .map_err(|e| locate(e, self.v))
+    }
+
+    deserialize_scalar!(deserialize_i8, visit_i8, i8);
+    deserialize_scalar!(deserialize_i16, visit_i16, i16);
+    deserialize_scalar!(deserialize_i32, visit_i32, i32);
+    deserialize_scalar!(deserialize_i64, visit_i64, i64);
+    deserialize_scalar!(deserialize_i128, visit_i128, i128);
+    deserialize_scalar!(deserialize_u8, visit_u8, u8);
+    deserialize_scalar!(deserialize_u16, visit_u16, u16);
+    deserialize_scalar!(deserialize_u32, visit_u32, u32);
+    deserialize_scalar!(deserialize_u64, visit_u64, u64);
+    deserialize_scalar!(deserialize_u128, visit_u128, u128);
+    deserialize_scalar!(deserialize_f32, visit_f32, f32);
+    deserialize_scalar!(deserialize_f64, visit_f64, f64);
+    deserialize_scalar!(deserialize_char, visit_char, char);
+
+    fn deserialize_str<V>(self, visitor: V) -> Result<V::Value, Box<WoodError>>
+    where
+        V: Visitor<'de>,
+    {
+        visitor
+            .visit_borrowed_str(self.leaf_str()?)
+            .map_err(|e| locate(e, self.v))
+    }
+    fn deserialize_string<V>(self, visitor: V) -> Result<V::Value, Box<WoodError>>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_str(visitor)
+    }
+    fn deserialize_bytes<V>(self, visitor: V) -> Result<V::Value, Box<WoodError>>
+    where
+        V: Visitor<'de>,
+    {
+        let mut bytes = Vec::new();
+        for b in self.v.contents() {
+            bytes.push(Deserializer::new(b).parse_scalar::<u8>("u8")?);
+        }
+        visitor.visit_byte_buf(bytes).map_err(|e| locate(e, self.v))
+    }
+    fn deserialize_byte_buf<V>(self, visitor: V) -> Result<V::Value, Box<WoodError>>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_bytes(visitor)
+    }
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Box<WoodError>>
+    where
+        V: Visitor<'de>,
+    {
+        match self.v.get_branch() {
+            Some([]) => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+        .map_err(|e| locate(e, self.v))
+    }
+    fn deserialize_unit<V>(self, visitor: V) -> Result<V::Value, Box<WoodError>>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_unit().map_err(|e| locate(e, self.v))
+    }
+    fn deserialize_unit_struct<V>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Box<WoodError>>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_unit(visitor)
+    }
+    fn deserialize_newtype_struct<V>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Box<WoodError>>
+    where
+        V: Visitor<'de>,
+    {
+        let li = self.tail_of_length(1, name)?;
+        visitor
+            .visit_newtype_struct(Deserializer::new(&li[0]))
+            .map_err(|e| locate(e, self.v))
+    }
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value, Box<WoodError>>
+    where
+        V: Visitor<'de>,
+    {
+        visitor
+            .visit_seq(SeqAccess::new(self.v.contents()))
+            .map_err(|e| locate(e, self.v))
+    }
+    fn deserialize_tuple<V>(self, len: usize, visitor: V) -> Result<V::Value, Box<WoodError>>
+    where
+        V: Visitor<'de>,
+    {
+        let li = self.v.contents().as_slice();
+        if li.len() != len {
+            return Err(Box::new(
+                WoodError::new(
+                    self.v,
+                    format!("expected a tuple of {} elements, found {}", len, li.len()),
+                )
+                .with_kind(ErrorKind::ArityMismatch),
+            ));
+        }
+        visitor
+            .visit_seq(SeqAccess::new(li.iter()))
+            .map_err(|e| locate(e, self.v))
+    }
+    fn deserialize_tuple_struct<V>(
+        self,
+        name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Box<WoodError>>
+    where
+        V: Visitor<'de>,
+    {
+        let li = self.tail_of_length(len, name)?;
+        visitor
+            .visit_seq(SeqAccess::new(li.iter()))
+            .map_err(|e| locate(e, self.v))
+    }
+    fn deserialize_map<V>(self, visitor: V) -> Result<V::Value, Box<WoodError>>
+    where
+        V: Visitor<'de>,
+    {
+        visitor
+            .visit_map(MapAccess::new(self.v.contents()))
+            .map_err(|e| locate(e, self.v))
+    }
+    fn deserialize_struct<V>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Box<WoodError>>
+    where
+        V: Visitor<'de>,
+    {
+        visitor
+            .visit_map(MapAccess::new(self.v.tail()))
+            .map_err(|e| locate(e, self.v))
+    }
+    fn deserialize_enum<V>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Box<WoodError>>
+    where
+        V: Visitor<'de>,
+    {
+        visitor
+            .visit_enum(EnumAccess { v: self.v })
+            .map_err(|e| locate(e, self.v))
+    }
+    fn deserialize_identifier<V>(self, visitor: V) -> Result<V::Value, Box<WoodError>>
+    where
+        V: Visitor<'de>,
+    {
+        visitor
+            .visit_borrowed_str(self.v.initial_str())
+            .map_err(|e| locate(e, self.v))
+    }
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value, Box<WoodError>>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_unit()
+    }
+}
+
+struct SeqAccess<'de> {
+    i: slice::Iter<'de, Wood>,
+}
+impl<'de> SeqAccess<'de> {
+    fn new(i: slice::Iter<'de, Wood>) -> Self {
+        SeqAccess { i }
+    }
+}
+impl<'de> de::SeqAccess<'de> for SeqAccess<'de> {
+    type Error = Box<WoodError>;
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Box<WoodError>>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        match self.i.next() {
+            Some(w) => seed.deserialize(Deserializer::new(w)).map(Some),
+            None => Ok(None),
+        }
+    }
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.i.len())
+    }
+}
+
+/// reads each wood as a `(key value)` pair
+struct MapAccess<'de> {
+    i: slice::Iter<'de, Wood>,
+    value: Option<&'de Wood>,
+}
+impl<'de> MapAccess<'de> {
+    fn new(i: slice::Iter<'de, Wood>) -> Self {
+        MapAccess { i, value: None }
+    }
+}
+impl<'de> de::MapAccess<'de> for MapAccess<'de> {
+    type Error = Box<WoodError>;
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Box<WoodError>>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        match self.i.next() {
+            Some(pair) => match pair.get_branch() {
+                Some([k, v]) => {
+                    self.value = Some(v);
+                    seed.deserialize(Deserializer::new(k)).map(Some)
+                }
+                Some(li) => Err(Box::new(
+                    WoodError::new(
+                        pair,
+                        format!(
+                            "expected a pair, two elements, but the branch here has {}",
+                            li.len()
+                        ),
+                    )
+                    .with_kind(ErrorKind::ArityMismatch),
+                )),
+                None => Err(Box::new(
+                    WoodError::new(pair, "expected a pair, but the wood here is an leaf".into())
+                        .with_kind(ErrorKind::UnexpectedLeaf),
+                )),
+            },
+            None => Ok(None),
+        }
+    }
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Box<WoodError>>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let v = self
+            .value
+            .take()
+            .ok_or_else(|| <Box<WoodError> as de::Error>::custom("map value without a key"))?;
+        seed.deserialize(Deserializer::new(v))
+    }
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.i.len())
+    }
+}
+
+/// reads the variant name from the wood's `initial_str`
+struct EnumAccess<'de> {
+    v: &'de Wood,
+}
+impl<'de> de::EnumAccess<'de> for EnumAccess<'de> {
+    type Error = Box<WoodError>;
+    type Variant = Deserializer<'de>;
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Deserializer<'de>), Box<WoodError>>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let tag = de::value::BorrowedStrDeserializer::<Box<WoodError>>::new(self.v.initial_str());
+        let variant = seed.deserialize(tag).map_err(|e| locate(e, self.v))?;
+        Ok((variant, Deserializer::new(self.v)))
+    }
+}
+impl<'de> de::VariantAccess<'de> for Deserializer<'de> {
+    type Error = Box<WoodError>;
+    fn unit_variant(self) -> Result<(), Box<WoodError>> {
+        Ok(())
+    }
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, Box<WoodError>>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        let li = self.tail_of_length(1, self.v.initial_str())?;
+        seed.deserialize(Deserializer::new(&li[0]))
+    }
+    fn tuple_variant<V>(self, len: usize, visitor: V) -> Result<V::Value, Box<WoodError>>
+    where
+        V: Visitor<'de>,
+    {
+        let li = self.tail_of_length(len, self.v.initial_str())?;
+        visitor
+            .visit_seq(SeqAccess::new(li.iter()))
+            .map_err(|e| locate(e, self.v))
+    }
+    fn struct_variant<V>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Box<WoodError>>
+    where
+        V: Visitor<'de>,
+    {
+        visitor
+            .visit_map(MapAccess::new(self.v.tail()))
+            .map_err(|e| locate(e, self.v))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::serde::{Deserialize, Serialize};
+    use std::collections::BTreeMap;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Dato {
+        a: String,
+        b: bool,
+    }
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    enum Shape {
+        Empty,
+        Circle(f64),
+        Rect(u32, u32),
+        Named { name: String, sides: Vec<u8> },
+    }
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Drawing {
+        title: Option<String>,
+        shapes: Vec<Shape>,
+        tags: BTreeMap<String, i16>,
+        origin: (i32, i32),
+    }
+
+    #[test]
+    fn same_shape_as_derive() {
+        let od = Dato {
+            a: "chock".into(),
+            b: true,
+        };
+        let s = to_termpose(&od).unwrap();
+        assert_eq!("Dato a:chock b:true", &s);
+        assert_eq!(&od, &from_termpose::<Dato>(&s).unwrap());
+        assert_eq!(
+            &woods!("Circle", "1.5"),
+            &to_wood(&Shape::Circle(1.5)).unwrap()
+        );
+        assert_eq!(&Wood::from("Empty"), &to_wood(&Shape::Empty).unwrap());
+    }
+
+    #[derive(Serialize, Deserialize, wood_derive::Woodable, PartialEq, Debug)]
+    #[wood(crate = "crate")]
+    struct Sometimes {
+        a: Option<u32>,
+        b: Option<u32>,
+        c: Vec<u32>,
+    }
+
+    #[test]
+    fn absent_fields_same_as_derive() {
+        let s = Sometimes {
+            a: None,
+            b: Some(2),
+            c: vec![],
+        };
+        let w = to_wood(&s).unwrap();
+        assert_eq!(&s.woodify(), &w);
+        assert_eq!(
+            &woods!("Sometimes", woods!("b", "2"), woods!("c", Wood::empty())),
+            &w
+        );
+        assert_eq!(&s, &from_wood::<Sometimes>(&w).unwrap());
+    }
+
+    #[test]
+    fn round_trips() {
+        let d = Drawing {
+            title: None,
+            shapes: vec![
+                Shape::Empty,
+                Shape::Rect(2, 3),
+                Shape::Named {
+                    name: "tri angle".into(),
+                    sides: vec![3, 4, 5],
+                },
+            ],
+            tags: [("a".to_string(), -1), ("b".to_string(), 2)]
+                .into_iter()
+                .collect(),
+            origin: (0, -4),
+        };
+        assert_eq!(
+            &d,
+            &from_termpose::<Drawing>(&to_termpose(&d).unwrap()).unwrap()
+        );
+        assert_eq!(
+            &d,
+            &from_woodslist::<Drawing>(&to_woodslist(&d).unwrap()).unwrap()
+        );
+    }
+
+    #[test]
+    fn errors_are_located() {
+        let e = from_termpose::<Dato>("Dato\n  a:chock\n  b:perhaps").unwrap_err();
+        assert_eq!((e.line, e.column), (3, 5));
+        let e = from_termpose::<Dato>("Dato\n  a:chock").unwrap_err();
+        assert_eq!(e.line, 1);
+        assert!(e.msg.contains("`b`"));
+    }
+}
@@ -0,0 +1,139 @@
+use super::*;
+
+/// Identifies a file that has been added to a `SourceMap`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct FileId(u32);
+impl FileId {
+    /// the position of the file in the order they were added to their `SourceMap`
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// A range of bytes in the text that a Wood (or a WoodError) came from. `end` is exclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Span {
+    pub file: Option<FileId>,
+    pub start: usize,
+    pub end: usize,
+}
+impl Span {
+    pub fn new(file: Option<FileId>, start: usize, end: usize) -> Self {
+        Span { file, start, end }
+    }
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+    /// the smallest span containing both of these
+    pub fn to(self, other: Span) -> Span {
+        Span {
+            file: self.file,
+            start: self.start.min(other.start),
+            end: self.end.max(other.end),
+        }
+    }
+}
+
+/// byte offsets of the beginnings of every line. Lines are ended by "\n", "\r\n" or a lone "\r", the same as in the parsers.
+pub(crate) fn line_starts(text: &str) -> Vec<usize> {
+    let mut ret = vec![0];
+    let bytes = text.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\n' => ret.push(i + 1),
+            b'\r' => {
+                if bytes.get(i + 1) == Some(&b'\n') {
+                    i += 1;
+                }
+                ret.push(i + 1);
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    ret
+}
+
+struct SourceFile {
+    name: String,
+    text: String,
+    line_starts: Vec<usize>,
+}
+
+/// Keeps the texts that Woods are parsed from, so that their `Span`s can be converted back into 1-based lines and columns (columns are counted in chars) or snippets of the text, whenever they're needed.
+/// ```
+/// use wood::SourceMap;
+/// let mut sm = SourceMap::new();
+/// let f = sm.add_file("conf.term", "server\n  port 80".into());
+/// let w = sm.parse_multiline_termpose(f).unwrap();
+/// let port = w.head().unwrap().find("port").unwrap();
+/// assert_eq!(Some((2, 3)), sm.start_line_col(port.span().unwrap()));
+/// assert_eq!(Some("port 80"), sm.snippet(port.span().unwrap()));
+/// ```
+#[derive(Default)]
+pub struct SourceMap {
+    files: Vec<SourceFile>,
+}
+impl SourceMap {
+    pub fn new() -> Self {
+        SourceMap { files: Vec::new() }
+    }
+    pub fn add_file(&mut self, name: &str, text: String) -> FileId {
+        let line_starts = line_starts(&text);
+        self.files.push(SourceFile {
+            name: name.into(),
+            text,
+            line_starts,
+        });
+        FileId(self.files.len() as u32 - 1)
+    }
+    pub fn name(&self, file: FileId) -> &str {
+        &self.files[file.index()].name
+    }
+    pub fn text(&self, file: FileId) -> &str {
+        &self.files[file.index()].text
+    }
+    /// the 1-based line and column of the byte at `offset`
+    pub fn line_col(&self, file: FileId, offset: usize) -> (usize, usize) {
+        let f = &self.files[file.index()];
+        let line = match f.line_starts.binary_search(&offset) {
+            Ok(l) => l,
+            Err(l) => l - 1,
+        };
+        let line_start = f.line_starts[line];
+        let column = f.text[line_start..offset.min(f.text.len())].chars().count();
+        (line + 1, column + 1)
+    }
+    /// the line and column where the span begins, if it has a file
+    pub fn start_line_col(&self, span: Span) -> Option<(usize, usize)> {
+        span.file.map(|f| self.line_col(f, span.start))
+    }
+    /// the line and column just after the end of the span, if it has a file
+    pub fn end_line_col(&self, span: Span) -> Option<(usize, usize)> {
+        span.file.map(|f| self.line_col(f, span.end))
+    }
+    /// the text that the span covers, if it has a file
+    pub fn snippet(&self, span: Span) -> Option<&str> {
+        span.file
+            .and_then(|f| self.files[f.index()].text.get(span.start..span.end))
+    }
+    /// `WoodError::render`, finding the source text and file name from the error's span. Errors without a span in this map will be rendered without a snippet.
+    pub fn render_error(&self, e: &WoodError) -> String {
+        match e.span.and_then(|sp| sp.file) {
+            Some(f) => e.render(self.text(f), Some(self.name(f))),
+            None => e.render("", None),
+        }
+    }
+    /// `parse_multiline_termpose` on the file's text, recording the file in every span
+    pub fn parse_multiline_termpose(&self, file: FileId) -> Result<Wood, Box<WoodError>> {
+        parse_multiline_termpose_style_in(self.text(file), DEFAULT_STYLE.clone(), Some(file))
+    }
+    /// `parse_multiline_woodslist` on the file's text, recording the file in every span
+    pub fn parse_multiline_woodslist(&self, file: FileId) -> Result<Wood, Box<WoodError>> {
+        parse_multiline_woodslist_in(self.text(file), Some(file))
+    }
+}
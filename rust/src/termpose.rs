@@ -0,0 +1,3 @@
+//! Tooling specific to termpose. The termpose parsers and printers themselves live at the crate root.
+
+pub mod cst;
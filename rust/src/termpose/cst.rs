@@ -0,0 +1,1126 @@
+//! A lossless syntax tree for termpose.
+//!
+//! `parse_termpose` forgets how a document was written: whether a pair was written `a:b` or `(a b)`, whether a leaf was quoted, how it was indented, where the blank lines were. The `File` produced by `parse` keeps every byte of the text, trivia included, so it can be printed back exactly as it was (`to_string`), edited without reflowing the parts that weren't touched, and lowered into the `Wood` that `parse_multiline_termpose` would have produced (`lower`).
+//! ```
+//! use wood::termpose::cst;
+//! let text = "server\n\tport:80  \n\n\tname \"big one\"\n";
+//! let file = cst::parse(text).unwrap();
+//! assert_eq!(text, file.to_string());
+//! assert_eq!(wood::parse_multiline_termpose(text).unwrap(), file.lower().unwrap());
+//! ```
+//! If the style has comments, they're kept as `Comment` tokens, wherever they were.
+
+use crate::parsers::{escaped_char, settle_branch_spans, UNTERMINATED_BLOCK_COMMENT};
+use crate::{
+    line_starts, Branch, Branchv, ErrorKind, Leaf, Leafv, Span, TermposeStyle, Wood, WoodError,
+    DEFAULT_STYLE,
+};
+use std::fmt::{self, Display, Formatter};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    /// spaces and tabs
+    Whitespace,
+    /// "\n", "\r\n" or a lone "\r"
+    Newline,
+    /// an unquoted leaf, escapes and all
+    Leaf,
+    /// a quoted leaf, including its quotes. The closing quote will be missing if the leaf was ended by the end of its line
+    Quoted,
+    Pairing,
+    Open,
+    Close,
+    /// a line of a multiline string's content, after the string's indentation
+    Text,
+    /// a comment, markers and all. Line comments don't include their line ending.
+    Comment,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub text: String,
+}
+impl Token {
+    pub fn new(kind: TokenKind, text: &str) -> Self {
+        Token {
+            kind,
+            text: text.into(),
+        }
+    }
+}
+
+/// An element of a line, or of the inside of a paren
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Term {
+    Space(Token),
+    Leaf(Token),
+    Quoted(Token),
+    Multiline(Multiline),
+    Pairing(Token),
+    Paren(Paren),
+    Comment(Token),
+}
+impl Term {
+    /// whether this term ends with something that a paren or a quote can invoke, if one follows it without a space: `f(a)`, `f"a"`, `(f)(a)`, `"f""a"`
+    fn is_invocable(&self) -> bool {
+        match self {
+            Term::Leaf(_) | Term::Quoted(_) => true,
+            Term::Paren(p) => p.close.is_some(),
+            _ => false,
+        }
+    }
+}
+
+/// Parens don't span lines, so when a line ends with some left open, `close` will be `None` and any indented lines that follow will be added to the innermost one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Paren {
+    pub open: Token,
+    pub terms: Vec<Term>,
+    pub close: Option<Token>,
+}
+
+/// A quote with nothing but whitespace after it on its line, which takes the more indented lines that follow as its content.
+/// `body` begins with the newline that ends the quote's line. Each line of content is a `Newline`, then the string's indentation as `Whitespace`, then the rest as `Text`. Lines that don't reach the string's indentation don't count as content, and have no `Text`, though they may have `Comment`s.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Multiline {
+    pub open: Token,
+    pub body: Vec<Token>,
+}
+impl Multiline {
+    pub fn value(&self) -> String {
+        let mut ret = String::new();
+        for (i, t) in self
+            .body
+            .iter()
+            .filter(|t| t.kind == TokenKind::Text)
+            .enumerate()
+        {
+            if i != 0 {
+                ret.push('\n');
+            }
+            ret.push_str(&t.text);
+        }
+        ret
+    }
+}
+
+/// A line, and the lines indented beneath it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Line {
+    /// the empty, whitespace-only or comment-only lines before this one
+    pub blanks: Vec<Token>,
+    /// The first line's indentation is always ignored
+    pub indent: Token,
+    pub terms: Vec<Term>,
+    pub newline: Option<Token>,
+    pub children: Vec<Line>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct File {
+    pub lines: Vec<Line>,
+    /// the blank lines after the last line
+    pub trailing: Vec<Token>,
+}
+
+fn write_term(t: &Term, f: &mut Formatter) -> fmt::Result {
+    match t {
+        Term::Space(k) | Term::Leaf(k) | Term::Quoted(k) | Term::Pairing(k) | Term::Comment(k) => {
+            f.write_str(&k.text)
+        }
+        Term::Multiline(m) => {
+            f.write_str(&m.open.text)?;
+            m.body.iter().try_for_each(|k| f.write_str(&k.text))
+        }
+        Term::Paren(p) => {
+            f.write_str(&p.open.text)?;
+            p.terms.iter().try_for_each(|t| write_term(t, f))?;
+            match p.close {
+                Some(ref c) => f.write_str(&c.text),
+                None => Ok(()),
+            }
+        }
+    }
+}
+fn write_line(l: &Line, f: &mut Formatter) -> fmt::Result {
+    l.blanks.iter().try_for_each(|k| f.write_str(&k.text))?;
+    f.write_str(&l.indent.text)?;
+    l.terms.iter().try_for_each(|t| write_term(t, f))?;
+    if let Some(ref n) = l.newline {
+        f.write_str(&n.text)?;
+    }
+    l.children.iter().try_for_each(|c| write_line(c, f))
+}
+
+/// Prints the text exactly as it was parsed, or as it has been edited
+impl Display for File {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        self.lines.iter().try_for_each(|l| write_line(l, f))?;
+        self.trailing.iter().try_for_each(|k| f.write_str(&k.text))
+    }
+}
+
+fn is_whitespace(c: char) -> bool {
+    c == ' ' || c == '\t'
+}
+fn is_newline(c: char) -> bool {
+    c == '\n' || c == '\r'
+}
+
+const ESCAPE_FAIL: &str = "escape slash must be followed by a valid escape character code";
+
+/// reads the contents of a leaf or quoted token, without its quotes. Returns whether the closing quote was there. Only fails if the token has been edited into something invalid.
+fn unescape(text: &str) -> Option<(String, bool)> {
+    let mut ret = String::new();
+    let mut cs = text.chars();
+    while let Some(c) = cs.next() {
+        match c {
+            '\\' => ret.push(escaped_char(|| cs.next())?),
+            '"' => return Some((ret, true)),
+            _ => ret.push(c),
+        }
+    }
+    Some((ret, false))
+}
+
+/// `parse_style` with the default style
+pub fn parse(s: &str) -> Result<File, Box<WoodError>> {
+    parse_style(s, &DEFAULT_STYLE)
+}
+
+/// Accepts and rejects the same texts as `parse_multiline_termpose_style`, with the same error messages
+pub fn parse_style(s: &str, style: &TermposeStyle) -> Result<File, Box<WoodError>> {
+    CstParser {
+        s,
+        pos: 0,
+        style,
+        line_starts: line_starts(s),
+    }
+    .file()
+}
+
+struct CstParser<'a> {
+    s: &'a str,
+    pos: usize,
+    style: &'a TermposeStyle,
+    line_starts: Vec<usize>,
+}
+
+impl<'a> CstParser<'a> {
+    fn peek(&self) -> Option<char> {
+        self.s[self.pos..].chars().next()
+    }
+    fn take_while(&mut self, f: impl Fn(char) -> bool) -> &'a str {
+        let rest = &self.s[self.pos..];
+        let len = rest.find(|c| !f(c)).unwrap_or(rest.len());
+        self.pos += len;
+        &rest[..len]
+    }
+    fn newline(&mut self) -> Option<Token> {
+        let rest = &self.s[self.pos..];
+        let len = if rest.starts_with("\r\n") {
+            2
+        } else if rest.starts_with(is_newline) {
+            1
+        } else {
+            return None;
+        };
+        self.pos += len;
+        Some(Token::new(TokenKind::Newline, &rest[..len]))
+    }
+    fn fail(&self, at: usize, msg: &str) -> Box<WoodError> {
+        let line = match self.line_starts.binary_search(&at) {
+            Ok(l) => l,
+            Err(l) => l - 1,
+        };
+        let column = self.s[self.line_starts[line]..at].chars().count() + 1;
+        let end = at + self.s[at..].chars().next().map_or(0, char::len_utf8);
+        Box::new(WoodError {
+            line: line as isize + 1,
+            column: column as isize,
+            span: Some(Span::new(None, at, end)),
+            kind: ErrorKind::Syntax,
+            path: Vec::new(),
+            msg: msg.into(),
+            cause: None,
+        })
+    }
+
+    /// the comment beginning here, if there is one
+    fn comment(&mut self) -> Result<Option<Token>, Box<WoodError>> {
+        match self.style.comments.comment_at(&self.s[self.pos..]) {
+            Some(c) if !c.closed => Err(self.fail(self.pos, UNTERMINATED_BLOCK_COMMENT)),
+            Some(c) => {
+                let start = self.pos;
+                self.pos += c.len;
+                Ok(Some(Token::new(
+                    TokenKind::Comment,
+                    &self.s[start..self.pos],
+                )))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// the comments starting at `at` and the whitespace between and after them, and where they end. Stops before any unterminated comment.
+    fn comments_from(&self, mut at: usize) -> (usize, Vec<Token>) {
+        let mut ret = Vec::new();
+        while let Some(c) = self.style.comments.comment_at(&self.s[at..]) {
+            if !c.closed {
+                break;
+            }
+            ret.push(Token::new(TokenKind::Comment, &self.s[at..at + c.len]));
+            at += c.len;
+            let rest = &self.s[at..];
+            let ws = rest.find(|c| !is_whitespace(c)).unwrap_or(rest.len());
+            if ws > 0 {
+                ret.push(Token::new(TokenKind::Whitespace, &rest[..ws]));
+            }
+            at += ws;
+        }
+        (at, ret)
+    }
+
+    /// whitespace-only and comment-only lines, up to the indentation of the next line that has something on it, or `limit`
+    fn blank_lines(&mut self, limit: usize) -> Result<Vec<Token>, Box<WoodError>> {
+        let mut ret = Vec::new();
+        while self.pos < limit {
+            let save = self.pos;
+            let mut line = Vec::new();
+            let ws = self.take_while(is_whitespace);
+            if !ws.is_empty() {
+                line.push(Token::new(TokenKind::Whitespace, ws));
+            }
+            let mut has_comment = false;
+            while let Some(k) = self.comment()? {
+                line.push(k);
+                has_comment = true;
+                let ws = self.take_while(is_whitespace);
+                if !ws.is_empty() {
+                    line.push(Token::new(TokenKind::Whitespace, ws));
+                }
+            }
+            match self.newline() {
+                Some(n) => {
+                    ret.extend(line);
+                    ret.push(n);
+                }
+                None if has_comment && self.pos == self.s.len() => {
+                    ret.extend(line);
+                    break;
+                }
+                None => {
+                    self.pos = save;
+                    break;
+                }
+            }
+        }
+        Ok(ret)
+    }
+
+    fn file(mut self) -> Result<File, Box<WoodError>> {
+        let mut flat: Vec<(usize, Line)> = Vec::new(); //lines with their depths
+        let mut indents: Vec<&'a str> = vec![""];
+        let mut after_multiline = false;
+        let trailing = loop {
+            let mut blanks = self.blank_lines(self.s.len())?;
+            let indent = self.take_while(is_whitespace);
+            if self.pos == self.s.len() {
+                if !indent.is_empty() {
+                    blanks.push(Token::new(TokenKind::Whitespace, indent));
+                }
+                break blanks;
+            }
+            if !flat.is_empty() {
+                self.settle_indent(&mut indents, indent, after_multiline)?;
+            }
+            let mut terms = self.terms_of_line()?;
+            after_multiline = false;
+            let multiline_content = match last_term(&terms) {
+                Some(Term::Quoted(q)) => self.opens_multiline(q, indents[indents.len() - 1]),
+                _ => None,
+            };
+            if let Some(content_start) = multiline_content {
+                let body = self.multiline_body(content_start)?;
+                let last = last_term_mut(&mut terms).unwrap();
+                if let Term::Quoted(open) = last {
+                    *last = Term::Multiline(Multiline {
+                        open: open.clone(),
+                        body,
+                    });
+                }
+                after_multiline = true;
+            }
+            let newline = self.newline();
+            flat.push((
+                indents.len() - 1,
+                Line {
+                    blanks,
+                    indent: Token::new(TokenKind::Whitespace, indent),
+                    terms,
+                    newline,
+                    children: Vec::new(),
+                },
+            ));
+        };
+        Ok(File {
+            lines: nest(flat),
+            trailing,
+        })
+    }
+
+    /// finds the level of a line in the indent stack, pushing it if it's deeper than the last. After a multiline string, the line can't be deeper.
+    fn settle_indent(
+        &mut self,
+        indents: &mut Vec<&'a str>,
+        indent: &'a str,
+        after_multiline: bool,
+    ) -> Result<(), Box<WoodError>> {
+        let inconsistent = || self.fail(self.pos, "inconsistent indentation");
+        let top = indents[indents.len() - 1];
+        if indent.len() > top.len() {
+            if after_multiline || !indent.starts_with(top) {
+                return Err(inconsistent());
+            }
+            indents.push(indent);
+            return Ok(());
+        }
+        let mut level = indents.len();
+        loop {
+            level -= 1;
+            let containing = indents[level];
+            if indent.len() == containing.len() {
+                if indent != containing {
+                    return Err(inconsistent());
+                }
+                indents.truncate(level + 1);
+                return Ok(());
+            } else if indent.len() > containing.len() {
+                return Err(inconsistent());
+            }
+        }
+    }
+
+    fn terms_of_line(&mut self) -> Result<Vec<Term>, Box<WoodError>> {
+        let mut open_parens: Vec<(Token, Vec<Term>)> = Vec::new();
+        let mut terms = Vec::new();
+        let mut has_previous_term = false; //whether a pairing can go here
+        let mut invocable = false;
+        while let Some(c) = self.peek() {
+            let start = self.pos;
+            if is_newline(c) {
+                break;
+            } else if is_whitespace(c) {
+                terms.push(Term::Space(Token::new(
+                    TokenKind::Whitespace,
+                    self.take_while(is_whitespace),
+                )));
+                invocable = false;
+                continue;
+            } else if c == self.style.open {
+                self.pos += c.len_utf8();
+                open_parens.push((
+                    Token::new(TokenKind::Open, &self.s[start..self.pos]),
+                    std::mem::take(&mut terms),
+                ));
+                has_previous_term = false;
+                invocable = false;
+                continue;
+            } else if c == self.style.close {
+                match open_parens.pop() {
+                    Some((open, outer)) => {
+                        self.pos += c.len_utf8();
+                        let inner = std::mem::replace(&mut terms, outer);
+                        terms.push(Term::Paren(Paren {
+                            open,
+                            terms: inner,
+                            close: Some(Token::new(TokenKind::Close, &self.s[start..self.pos])),
+                        }));
+                    }
+                    None => return Err(self.fail(start, "unmatched paren")),
+                }
+                has_previous_term = true;
+            } else if c == self.style.pairing {
+                if !has_previous_term {
+                    return Err(self.fail(start, "no previous term, cannot open a colon here"));
+                }
+                self.pos += c.len_utf8();
+                terms.push(Term::Pairing(Token::new(
+                    TokenKind::Pairing,
+                    &self.s[start..self.pos],
+                )));
+                has_previous_term = false;
+                invocable = false;
+                continue;
+            } else if let Some(k) = self.comment()? {
+                terms.push(Term::Comment(k));
+                invocable = false;
+                continue;
+            } else if c == '"' {
+                self.pos += 1;
+                self.read_leaf_chars(|ch| is_newline(ch) || ch == '"')?;
+                if self.peek() == Some('"') {
+                    self.pos += 1;
+                }
+                terms.push(Term::Quoted(Token::new(
+                    TokenKind::Quoted,
+                    &self.s[start..self.pos],
+                )));
+                //a quote invoking the previous term doesn't become the previous term
+                has_previous_term |= !invocable;
+            } else {
+                let style = self.style;
+                self.read_leaf_chars(|ch| {
+                    is_whitespace(ch)
+                        || is_newline(ch)
+                        || ch == '"'
+                        || ch == style.open
+                        || ch == style.close
+                        || ch == style.pairing
+                })?;
+                terms.push(Term::Leaf(Token::new(
+                    TokenKind::Leaf,
+                    &self.s[start..self.pos],
+                )));
+                has_previous_term = true;
+            }
+            invocable = true;
+        }
+        while let Some((open, outer)) = open_parens.pop() {
+            let inner = std::mem::replace(&mut terms, outer);
+            terms.push(Term::Paren(Paren {
+                open,
+                terms: inner,
+                close: None,
+            }));
+        }
+        Ok(terms)
+    }
+
+    /// reads up to the first unescaped char that `ends`, or the end of the text
+    fn read_leaf_chars(&mut self, ends: impl Fn(char) -> bool) -> Result<(), Box<WoodError>> {
+        while let Some(c) = self.peek() {
+            if ends(c) {
+                break;
+            }
+            self.pos += c.len_utf8();
+            if c == '\\' {
+                let mut rest = self.s[self.pos..].chars();
+                if escaped_char(|| rest.next()).is_none() {
+                    let at = if self.pos < self.s.len() {
+                        self.pos
+                    } else {
+                        self.pos - 1
+                    };
+                    return Err(self.fail(at, ESCAPE_FAIL));
+                }
+                self.pos = self.s.len() - rest.as_str().len();
+            }
+        }
+        Ok(())
+    }
+
+    /// whether the quoted leaf at the end of the current line begins a multiline string: it has only whitespace in it, no closing quote, and the next line with anything on it (besides comments) is indented deeper than the current line's level. Returns where that line begins.
+    fn opens_multiline(&self, q: &Token, level_indent: &str) -> Option<usize> {
+        match unescape(&q.text[1..]) {
+            Some((v, false)) if v.chars().all(is_whitespace) => {}
+            _ => return None,
+        }
+        let mut at = self.pos;
+        loop {
+            let rest = &self.s[at..];
+            let line_start = at
+                + if rest.starts_with("\r\n") {
+                    2
+                } else if rest.starts_with(is_newline) {
+                    1
+                } else {
+                    return None;
+                };
+            let line = &self.s[line_start..];
+            let indent = &line[..line.find(|c| !is_whitespace(c)).unwrap_or(line.len())];
+            at = line_start + indent.len();
+            match self.s[at..].chars().next() {
+                None => return None,
+                Some(c) if is_newline(c) => continue,
+                _ => {}
+            }
+            if indent.len() > level_indent.len() && indent.starts_with(level_indent) {
+                return Some(line_start);
+            }
+            //a comment on a line that isn't indented enough to be content is skipped like a blank line
+            let (after, comments) = self.comments_from(at);
+            if comments.is_empty() || !self.s[after..].starts_with(is_newline) {
+                return None;
+            }
+            at = after;
+        }
+    }
+
+    fn multiline_body(&mut self, content_start: usize) -> Result<Vec<Token>, Box<WoodError>> {
+        let mut body = vec![self.newline().unwrap()]; //opens_multiline confirmed it
+        body.extend(self.blank_lines(content_start)?);
+        let indent = self.take_while(is_whitespace);
+        body.push(Token::new(TokenKind::Whitespace, indent));
+        body.push(Token::new(
+            TokenKind::Text,
+            self.take_while(|c| !is_newline(c)),
+        ));
+        let mut committed = (body.len(), self.pos);
+        while let Some(n) = self.newline() {
+            body.push(n);
+            let line_start = self.pos;
+            let mut full = false;
+            while let Some(c) = self.peek().filter(|&c| is_whitespace(c)) {
+                self.pos += c.len_utf8();
+                let cur = &self.s[line_start..self.pos];
+                if cur.len() == indent.len() {
+                    if cur != indent {
+                        return Err(self.fail(self.pos - 1, "inconsistent indentation"));
+                    }
+                    full = true;
+                    break;
+                } else if !indent.starts_with(cur) {
+                    return Err(self.fail(self.pos - 1, "inconsistent indentation"));
+                }
+            }
+            body.push(Token::new(
+                TokenKind::Whitespace,
+                &self.s[line_start..self.pos],
+            ));
+            if full {
+                body.push(Token::new(
+                    TokenKind::Text,
+                    self.take_while(|c| !is_newline(c)),
+                ));
+                committed = (body.len(), self.pos);
+                continue;
+            }
+            //comments on lines too shallow to be content are skipped like blank lines
+            let (after, comments) = self.comments_from(self.pos);
+            if self.s[after..].starts_with(is_newline) {
+                body.extend(comments);
+                self.pos = after;
+            } else {
+                break; //the string is over
+            }
+        }
+        //whatever came after the last line of content belongs to the lines that follow
+        body.truncate(committed.0);
+        self.pos = committed.1;
+        Ok(body)
+    }
+}
+
+fn last_term(terms: &[Term]) -> Option<&Term> {
+    match terms.last() {
+        Some(Term::Paren(p)) if p.close.is_none() => last_term(&p.terms),
+        t => t,
+    }
+}
+fn last_term_mut(terms: &mut [Term]) -> Option<&mut Term> {
+    let in_open_paren = matches!(terms.last(), Some(Term::Paren(p)) if p.close.is_none());
+    if in_open_paren {
+        match terms.last_mut() {
+            Some(Term::Paren(p)) => last_term_mut(&mut p.terms),
+            _ => None,
+        }
+    } else {
+        terms.last_mut()
+    }
+}
+
+/// makes each line the child of the last line before it that was one level shallower
+fn nest(flat: Vec<(usize, Line)>) -> Vec<Line> {
+    let mut stack: Vec<Vec<Line>> = vec![Vec::new()];
+    for (depth, line) in flat {
+        while stack.len() > depth + 1 {
+            let children = stack.pop().unwrap();
+            stack.last_mut().unwrap().last_mut().unwrap().children = children;
+        }
+        if stack.len() < depth + 1 {
+            stack.push(Vec::new());
+        }
+        stack.last_mut().unwrap().push(line);
+    }
+    while stack.len() > 1 {
+        let children = stack.pop().unwrap();
+        stack.last_mut().unwrap().last_mut().unwrap().children = children;
+    }
+    stack.pop().unwrap()
+}
+
+impl File {
+    /// The Wood that `parse_multiline_termpose` produces from `self.to_string()`: a Branch of the root level terms. Positions refer to the printed text. Comments aren't attached to the woods, they stay in the tree.
+    /// Only fails if the tree has been edited into something that isn't valid termpose.
+    pub fn lower(&self) -> Result<Wood, Box<WoodError>> {
+        let text = self.to_string();
+        let mut l = Lowering {
+            nodes: Vec::new(),
+            pos: 0,
+            line_starts: line_starts(&text),
+            text: &text,
+        };
+        let root = l.branch_at(0);
+        l.lines(&self.lines, root)?;
+        l.nodes[root].end = text.len();
+        let mut ret = l.build_wood(root);
+        if let Branchv(ref mut b) = ret {
+            b.line = -1;
+            b.column = -1;
+        }
+        settle_branch_spans(&mut ret);
+        Ok(ret)
+    }
+}
+
+enum NodeV {
+    Leaf(String),
+    Branch(Vec<usize>),
+}
+struct Node {
+    v: NodeV,
+    start: usize,
+    end: usize,
+}
+
+/// the state of a line being lowered. Mirrors the termpose parser's, but with indices into `Lowering::nodes` instead of pointers
+struct LineState {
+    parens: Vec<usize>,
+    receptacle: Option<usize>,
+    last: Option<usize>,
+    has_newline: bool,
+}
+
+struct Lowering<'t> {
+    nodes: Vec<Node>,
+    pos: usize,
+    line_starts: Vec<usize>,
+    text: &'t str,
+}
+
+impl<'t> Lowering<'t> {
+    fn branch_at(&mut self, start: usize) -> usize {
+        self.nodes.push(Node {
+            v: NodeV::Branch(Vec::new()),
+            start,
+            end: start,
+        });
+        self.nodes.len() - 1
+    }
+    fn children(&mut self, b: usize) -> &mut Vec<usize> {
+        match self.nodes[b].v {
+            NodeV::Branch(ref mut v) => v,
+            NodeV::Leaf(_) => unreachable!("only branches receive terms"),
+        }
+    }
+    /// turns the node into a branch containing what it was
+    fn accrete(&mut self, n: usize) {
+        let (start, end) = (self.nodes[n].start, self.nodes[n].end);
+        let moved = std::mem::replace(&mut self.nodes[n].v, NodeV::Branch(Vec::new()));
+        self.nodes.push(Node {
+            v: moved,
+            start,
+            end,
+        });
+        let inner = self.nodes.len() - 1;
+        self.children(n).push(inner);
+    }
+    fn collapse(&mut self, n: usize) {
+        if let NodeV::Branch(ref v) = self.nodes[n].v {
+            if v.len() == 1 {
+                let only = v[0];
+                let v = std::mem::replace(&mut self.nodes[only].v, NodeV::Branch(Vec::new()));
+                self.nodes[n] = Node {
+                    v,
+                    start: self.nodes[only].start,
+                    end: self.nodes[only].end,
+                };
+            }
+        }
+    }
+    fn insert(&mut self, st: &mut LineState, n: usize) {
+        let into = st
+            .receptacle
+            .take()
+            .unwrap_or(st.parens[st.parens.len() - 1]);
+        self.children(into).push(n);
+    }
+    fn skip(&mut self, t: &Token) {
+        self.pos += t.text.len();
+    }
+    fn fail(&self, msg: &str) -> Box<WoodError> {
+        let line = match self.line_starts.binary_search(&self.pos) {
+            Ok(l) => l,
+            Err(l) => l - 1,
+        };
+        Box::new(WoodError {
+            line: line as isize + 1,
+            column: self.text[self.line_starts[line]..self.pos].chars().count() as isize + 1,
+            span: Some(Span::new(None, self.pos, self.pos)),
+            kind: ErrorKind::Syntax,
+            path: Vec::new(),
+            msg: msg.into(),
+            cause: None,
+        })
+    }
+
+    fn lines(&mut self, lines: &[Line], into: usize) -> Result<(), Box<WoodError>> {
+        for line in lines {
+            line.blanks.iter().for_each(|t| self.skip(t));
+            self.skip(&line.indent);
+            let line_branch = self.branch_at(self.pos);
+            self.children(into).push(line_branch);
+            let mut st = LineState {
+                parens: vec![line_branch],
+                receptacle: None,
+                last: None,
+                has_newline: line.newline.is_some(),
+            };
+            self.terms(&line.terms, &mut st)?;
+            if let Some(ref n) = line.newline {
+                self.skip(n);
+            }
+            if line.children.is_empty() {
+                self.collapse(line_branch);
+            } else {
+                let hanging = if let Some(r) = st.receptacle.take() {
+                    r
+                } else if st.parens.len() > 1 {
+                    st.parens[st.parens.len() - 1]
+                } else {
+                    if self.children(line_branch).len() > 1 {
+                        self.accrete(line_branch);
+                    }
+                    line_branch
+                };
+                self.lines(&line.children, hanging)?;
+                //the indental went into an open paren or pairing, so if that's all that's on the line, the line is just that
+                if hanging != line_branch {
+                    self.collapse(line_branch);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn terms(&mut self, terms: &[Term], st: &mut LineState) -> Result<(), Box<WoodError>> {
+        let mut invocable = false;
+        for t in terms {
+            let start = self.pos;
+            match t {
+                Term::Space(k) | Term::Comment(k) => self.skip(k),
+                Term::Leaf(k) => {
+                    let v = match unescape(&k.text) {
+                        Some((v, false)) => v,
+                        _ => return Err(self.fail(ESCAPE_FAIL)),
+                    };
+                    self.skip(k);
+                    let n = self.leaf(v, start);
+                    self.insert(st, n);
+                    st.last = Some(n);
+                }
+                Term::Quoted(_) | Term::Multiline(_) => {
+                    let v = match t {
+                        Term::Quoted(k) => {
+                            let (v, closed) = k
+                                .text
+                                .strip_prefix('"')
+                                .and_then(unescape)
+                                .ok_or_else(|| self.fail(ESCAPE_FAIL))?;
+                            self.skip(k);
+                            if !closed && st.has_newline && v.chars().all(is_whitespace) {
+                                String::new() //it was taken to be the beginning of a multiline string, before finding that there was nothing indented after it
+                            } else {
+                                v
+                            }
+                        }
+                        Term::Multiline(m) => {
+                            self.skip(&m.open);
+                            m.body.iter().for_each(|k| self.skip(k));
+                            m.value()
+                        }
+                        _ => unreachable!(),
+                    };
+                    let n = self.leaf(v, start);
+                    if invocable {
+                        let top = st.parens[st.parens.len() - 1];
+                        let back = match self.children(top).last() {
+                            Some(&b) => b,
+                            None => return Err(self.fail("nothing for the quote to invoke")),
+                        };
+                        self.accrete(back);
+                        self.children(back).push(n);
+                    } else {
+                        self.insert(st, n);
+                        st.last = Some(n);
+                    }
+                }
+                Term::Pairing(k) => {
+                    let l = st
+                        .last
+                        .take()
+                        .ok_or_else(|| self.fail("no previous term, cannot open a colon here"))?;
+                    self.accrete(l);
+                    st.receptacle = Some(l);
+                    self.skip(k);
+                }
+                Term::Paren(p) => {
+                    if invocable {
+                        let l = st
+                            .last
+                            .take()
+                            .ok_or_else(|| self.fail("nothing for the paren to invoke"))?;
+                        self.accrete(l);
+                        st.parens.push(l);
+                    } else {
+                        let n = self.branch_at(start);
+                        self.insert(st, n);
+                        st.parens.push(n);
+                        st.last = None;
+                    }
+                    self.skip(&p.open);
+                    self.terms(&p.terms, st)?;
+                    if let Some(ref c) = p.close {
+                        self.skip(c);
+                        st.receptacle = None;
+                        let closed = st.parens.pop().unwrap(); //it was pushed above
+                        self.nodes[closed].end = self.pos;
+                        st.last = Some(closed);
+                    }
+                }
+            }
+            invocable = t.is_invocable();
+        }
+        Ok(())
+    }
+
+    fn leaf(&mut self, v: String, start: usize) -> usize {
+        self.nodes.push(Node {
+            v: NodeV::Leaf(v),
+            start,
+            end: self.pos,
+        });
+        self.nodes.len() - 1
+    }
+
+    fn build_wood(&mut self, n: usize) -> Wood {
+        let node = &mut self.nodes[n];
+        let span = Some(Span::new(None, node.start, node.end));
+        let line = match self.line_starts.binary_search(&node.start) {
+            Ok(l) => l,
+            Err(l) => l - 1,
+        };
+        let column = self.text[self.line_starts[line]..node.start]
+            .chars()
+            .count() as isize
+            + 1;
+        let line = line as isize + 1;
+        match std::mem::replace(&mut node.v, NodeV::Branch(Vec::new())) {
+            NodeV::Leaf(v) => Leafv(Leaf {
+                line,
+                column,
+                span,
+                comments: Vec::new(),
+                v,
+            }),
+            NodeV::Branch(cs) => Branchv(Branch {
+                line,
+                column,
+                span,
+                comments: Vec::new(),
+                v: cs.into_iter().map(|c| self.build_wood(c)).collect(),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{assert_same_positions, read_file_from_root};
+    use crate::{parse_multiline_termpose, parse_multiline_termpose_style, CommentStyle};
+    use std::panic::catch_unwind;
+
+    fn check(text: &str) {
+        check_style(text, &DEFAULT_STYLE);
+    }
+
+    fn check_style(text: &str, style: &TermposeStyle) {
+        let parsed = catch_unwind(|| parse_multiline_termpose_style(text, style.clone()));
+        let Ok(parsed) = parsed else { return }; //the parser panics on a few strange inputs
+        match (parsed, parse_style(text, style)) {
+            (Ok(w), Ok(f)) => {
+                assert_eq!(text, f.to_string());
+                let lowered = f.lower().unwrap();
+                assert_eq!(w, lowered, "lowering {:?}", text);
+            }
+            (Err(a), Err(b)) => assert_eq!(a.msg, b.msg, "for {:?}", text),
+            (a, b) => panic!(
+                "for {:?}, the parser gave {:?} but the cst gave {:?}",
+                text, a, b
+            ),
+        }
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)] //takes hours under miri, and the parser's reading of these files is checked in the parser's tests
+    fn agrees_with_the_parser_on_the_test_files() {
+        for file in ["tests.term", "longterm.term", "shortterm.term"] {
+            let text = read_file_from_root(file);
+            check(&text);
+            check(&text.replace('\n', "\r\n"));
+            assert_same_positions(
+                &parse_multiline_termpose(&text).unwrap(),
+                &parse(&text).unwrap().lower().unwrap(),
+            );
+            //each of the cases in tests.term is itself a termpose document
+            let w = parse_multiline_termpose(&text).unwrap();
+            fn each_leaf(w: &Wood, f: &mut impl FnMut(&str)) {
+                match w {
+                    Leafv(l) => f(&l.v),
+                    Branchv(b) => b.v.iter().for_each(|c| each_leaf(c, f)),
+                }
+            }
+            each_leaf(&w, &mut |s| check(s));
+        }
+    }
+
+    #[test]
+    fn agrees_with_the_parser_on_every_short_text() {
+        let alphabet = ['a', ' ', '\t', '\n', ':', '(', ')', '"', '\\'];
+        let mut text = String::new();
+        fn all(text: &mut String, alphabet: &[char], depth: usize) {
+            check(text);
+            if depth == 0 {
+                return;
+            }
+            for &c in alphabet {
+                text.push(c);
+                all(text, alphabet, depth - 1);
+                text.pop();
+            }
+        }
+        all(&mut text, &alphabet, if cfg!(miri) { 2 } else { 5 });
+    }
+
+    fn commented_style() -> TermposeStyle {
+        TermposeStyle {
+            comments: CommentStyle {
+                line: Some("//".into()),
+                block: Some(("/*".into(), "*/".into())),
+                attach: false,
+            },
+            ..DEFAULT_STYLE.clone()
+        }
+    }
+
+    #[test]
+    fn agrees_with_the_parser_on_every_short_text_with_comments() {
+        let style = commented_style();
+        let alphabet = ['a', ' ', '\t', '\n', '(', '"', '/', '*'];
+        let mut text = String::new();
+        fn all(text: &mut String, alphabet: &[char], depth: usize, style: &TermposeStyle) {
+            check_style(text, style);
+            if depth == 0 {
+                return;
+            }
+            for &c in alphabet {
+                text.push(c);
+                all(text, alphabet, depth - 1, style);
+                text.pop();
+            }
+        }
+        all(&mut text, &alphabet, if cfg!(miri) { 2 } else { 6 }, &style);
+        for text in [
+            "a \"\n//x\n\t\tb\n\t/* y */\n\t\tc\nd",
+            "a \"\n  /* x\n*/ \n  b\n /* c */ d\ne",
+            "a\n\t/* x\n y */ b /* z */ c // w\n\td",
+        ] {
+            check_style(text, &style);
+        }
+    }
+
+    #[test]
+    fn keeps_comments() {
+        let text = "// header\na /* b */ c\n\td // e\n";
+        let f = parse_style(text, &commented_style()).unwrap();
+        assert_eq!(text, f.to_string());
+        assert_eq!(
+            vec![
+                Token::new(TokenKind::Comment, "// header"),
+                Token::new(TokenKind::Newline, "\n")
+            ],
+            f.lines[0].blanks
+        );
+        assert_eq!(
+            Term::Comment(Token::new(TokenKind::Comment, "/* b */")),
+            f.lines[0].terms[2]
+        );
+        assert_eq!(
+            parse_multiline_termpose_style(text, commented_style()).unwrap(),
+            f.lower().unwrap()
+        );
+    }
+
+    #[test]
+    fn keeps_how_things_were_written() {
+        let text = "a b:c(d)  \"e\"\n\n\tf \"\n\t\tg\n\t\t  h\n\ti";
+        let f = parse(text).unwrap();
+        assert_eq!(1, f.lines.len());
+        let l = &f.lines[0];
+        assert_eq!(
+            vec![
+                TokenKind::Leaf,
+                TokenKind::Whitespace,
+                TokenKind::Leaf,
+                TokenKind::Pairing,
+                TokenKind::Leaf,
+                TokenKind::Open,
+                TokenKind::Whitespace,
+                TokenKind::Quoted,
+            ],
+            l.terms
+                .iter()
+                .map(|t| match t {
+                    Term::Space(k)
+                    | Term::Leaf(k)
+                    | Term::Quoted(k)
+                    | Term::Pairing(k)
+                    | Term::Comment(k) => k.kind,
+                    Term::Paren(p) => p.open.kind,
+                    Term::Multiline(m) => m.open.kind,
+                })
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(2, l.children.len());
+        assert_eq!(
+            vec![Token::new(TokenKind::Newline, "\n")],
+            l.children[0].blanks
+        );
+        match l.children[0].terms.last() {
+            Some(Term::Multiline(m)) => assert_eq!("g\n  h", m.value()),
+            t => panic!("{:?}", t),
+        }
+    }
+
+    #[test]
+    fn edits_leave_the_rest_alone() {
+        let text = "server\n  port:80   \n\n  name \"x\"\n";
+        let mut f = parse(text).unwrap();
+        if let Term::Leaf(ref mut k) = f.lines[0].children[0].terms[2] {
+            k.text = "8080".into();
+        }
+        assert_eq!(text.replace("80 ", "8080 "), f.to_string());
+        assert_eq!(
+            Some("8080"),
+            f.lower()
+                .unwrap()
+                .head()
+                .unwrap()
+                .seek_val("port")
+                .map(|w| w.initial_str())
+        );
+    }
+}
@@ -0,0 +1,29 @@
+use super::*;
+use std::fs::File;
+use std::io::Read;
+use std::path::PathBuf;
+
+/// the contents of one of the test documents that sit beside Cargo.toml
+pub(crate) fn read_file_from_root(file_name: &str) -> String {
+    let mut d = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    d.push(file_name);
+    let mut ret = String::new();
+    let mut file = File::open(d).unwrap();
+    file.read_to_string(&mut ret).unwrap();
+    ret
+}
+
+/// asserts that every wood in `a` has the same line, column and span as its counterpart in `b`
+pub(crate) fn assert_same_positions(a: &Wood, b: &Wood) {
+    assert_eq!(
+        (a.line_and_col(), a.span()),
+        (b.line_and_col(), b.span()),
+        "at {}",
+        a.to_string()
+    );
+    if let (Some(ac), Some(bc)) = (a.get_branch(), b.get_branch()) {
+        ac.iter()
+            .zip(bc)
+            .for_each(|(a, b)| assert_same_positions(a, b));
+    }
+}
@@ -0,0 +1,399 @@
+use super::*;
+use std::collections::VecDeque;
+
+/// Receives each part of a wood, in document order, from `Wood::visit`. Every method does nothing by default, so a visitor only needs to implement the ones it cares about.
+pub trait WoodVisitor<S = String> {
+    /// Called before a branch's contents are visited. If it returns false, they aren't. `leave_branch` is called either way.
+    fn enter_branch(&mut self, _branch: &Branch<S>) -> bool {
+        true
+    }
+    /// called after a branch's contents have been visited
+    fn leave_branch(&mut self, _branch: &Branch<S>) {}
+    fn visit_leaf(&mut self, _leaf: &Leaf<S>) {}
+}
+
+/// `WoodVisitor`, for editing a wood in place with `Wood::visit_mut`. Changes that `enter_branch` makes to a branch's contents are seen when the contents are visited.
+pub trait WoodVisitorMut<S = String> {
+    /// Called before a branch's contents are visited. If it returns false, they aren't. `leave_branch` is called either way.
+    fn enter_branch(&mut self, _branch: &mut Branch<S>) -> bool {
+        true
+    }
+    /// called after a branch's contents have been visited
+    fn leave_branch(&mut self, _branch: &mut Branch<S>) {}
+    fn visit_leaf(&mut self, _leaf: &mut Leaf<S>) {}
+}
+
+/// Iterates over every wood in a tree, each one before its contents, along with the indices that lead to it (see `Wood::at_path`). From `Wood::depth_first`.
+pub struct DepthFirst<'a, S = String> {
+    stack: Vec<(Vec<usize>, &'a Wood<S>)>,
+}
+impl<'a, S> Iterator for DepthFirst<'a, S> {
+    type Item = (Vec<usize>, &'a Wood<S>);
+    fn next(&mut self) -> Option<Self::Item> {
+        let (path, w) = self.stack.pop()?;
+        if let Branchv(ref b) = *w {
+            for (i, c) in b.v.iter().enumerate().rev() {
+                let mut p = Vec::with_capacity(path.len() + 1);
+                p.extend_from_slice(&path);
+                p.push(i);
+                self.stack.push((p, c));
+            }
+        }
+        Some((path, w))
+    }
+}
+
+/// Iterates over every wood in a tree, level by level, along with the indices that lead to it (see `Wood::at_path`). From `Wood::breadth_first`.
+pub struct BreadthFirst<'a, S = String> {
+    queue: VecDeque<(Vec<usize>, &'a Wood<S>)>,
+}
+impl<'a, S> Iterator for BreadthFirst<'a, S> {
+    type Item = (Vec<usize>, &'a Wood<S>);
+    fn next(&mut self) -> Option<Self::Item> {
+        let (path, w) = self.queue.pop_front()?;
+        if let Branchv(ref b) = *w {
+            for (i, c) in b.v.iter().enumerate() {
+                let mut p = Vec::with_capacity(path.len() + 1);
+                p.extend_from_slice(&path);
+                p.push(i);
+                self.queue.push_back((p, c));
+            }
+        }
+        Some((path, w))
+    }
+}
+
+/// a branch that's been taken apart so that its contents can be worked through one at a time
+struct Opened<S, T> {
+    line: isize,
+    column: isize,
+    span: Option<Span>,
+    comments: Vec<String>,
+    pending: std::vec::IntoIter<Wood<S>>,
+    done: Vec<Wood<T>>,
+}
+impl<S, T> Opened<S, T> {
+    fn new(mut b: Branch<S>) -> Self {
+        Opened {
+            line: b.line,
+            column: b.column,
+            span: b.span,
+            comments: std::mem::take(&mut b.comments),
+            done: Vec::with_capacity(b.v.len()),
+            pending: std::mem::take(&mut b.v).into_iter(),
+        }
+    }
+    fn close(self) -> Branch<T> {
+        Branch {
+            line: self.line,
+            column: self.column,
+            span: self.span,
+            comments: self.comments,
+            v: self.done,
+        }
+    }
+}
+
+impl<S> Wood<S> {
+    /// Walks the whole tree, passing each part of it to `visitor`. This doesn't recurse, so it's fine for trees of any depth.
+    pub fn visit(&self, visitor: &mut impl WoodVisitor<S>) {
+        let mut stack = Vec::new();
+        match *self {
+            Leafv(ref l) => visitor.visit_leaf(l),
+            Branchv(ref b) => {
+                if visitor.enter_branch(b) {
+                    stack.push((b, b.v.iter()));
+                } else {
+                    visitor.leave_branch(b);
+                }
+            }
+        }
+        while let Some(&mut (b, ref mut contents)) = stack.last_mut() {
+            match contents.next() {
+                Some(Leafv(ref l)) => visitor.visit_leaf(l),
+                Some(Branchv(ref c)) => {
+                    if visitor.enter_branch(c) {
+                        stack.push((c, c.v.iter()));
+                    } else {
+                        visitor.leave_branch(c);
+                    }
+                }
+                None => {
+                    visitor.leave_branch(b);
+                    stack.pop();
+                }
+            }
+        }
+    }
+
+    /// `visit`, for editing the tree in place
+    pub fn visit_mut(&mut self, visitor: &mut impl WoodVisitorMut<S>) {
+        //the tree is taken apart as it's walked and put back together behind the walk, so that no two parts of it are ever borrowed at once
+        let mut stack: Vec<Opened<S, S>> = Vec::new();
+        let mut next = Some(std::mem::take(self));
+        loop {
+            let finished = match next.take() {
+                Some(Leafv(mut l)) => {
+                    visitor.visit_leaf(&mut l);
+                    Some(Leafv(l))
+                }
+                Some(Branchv(mut b)) => {
+                    if visitor.enter_branch(&mut b) {
+                        stack.push(Opened::new(b));
+                        None
+                    } else {
+                        visitor.leave_branch(&mut b);
+                        Some(Branchv(b))
+                    }
+                }
+                None => {
+                    let top = stack.last_mut().unwrap();
+                    match top.pending.next() {
+                        Some(c) => {
+                            next = Some(c);
+                            continue;
+                        }
+                        None => {
+                            let mut b = stack.pop().unwrap().close();
+                            visitor.leave_branch(&mut b);
+                            Some(Branchv(b))
+                        }
+                    }
+                }
+            };
+            if let Some(w) = finished {
+                match stack.last_mut() {
+                    Some(top) => top.done.push(w),
+                    None => {
+                        *self = w;
+                        return;
+                    }
+                }
+            }
+        }
+    }
+
+    /// every wood in the tree, each one before its contents, with the indices that lead to it
+    pub fn depth_first(&self) -> DepthFirst<'_, S> {
+        DepthFirst {
+            stack: vec![(Vec::new(), self)],
+        }
+    }
+
+    /// every wood in the tree, level by level, with the indices that lead to it
+    pub fn breadth_first(&self) -> BreadthFirst<'_, S> {
+        BreadthFirst {
+            queue: VecDeque::from([(Vec::new(), self)]),
+        }
+    }
+
+    /// The wood that `path` leads to, each index choosing among the contents of a branch, as `depth_first` and `breadth_first` give them. The empty path leads to the wood itself.
+    pub fn at_path(&self, path: &[usize]) -> Option<&Wood<S>> {
+        path.iter()
+            .try_fold(self, |w, &i| w.get_branch().and_then(|v| v.get(i)))
+    }
+
+    /// `at_path`, to be edited
+    pub fn at_path_mut(&mut self, path: &[usize]) -> Option<&mut Wood<S>> {
+        path.iter().try_fold(self, |w, &i| match *w {
+            Branchv(ref mut b) => b.v.get_mut(i),
+            Leafv(_) => None,
+        })
+    }
+
+    /// Reduces the tree to a single value, from the leaves up. `branch` is given the values that its contents were reduced to, in order.
+    /// ```
+    /// let w = wood::parse_woodslist("a (b c) ((d))").unwrap();
+    /// let depth = w.fold(&mut |_| 0, &mut |_, contents: Vec<usize>| 1 + contents.into_iter().max().unwrap_or(0));
+    /// assert_eq!(3, depth);
+    /// ```
+    pub fn fold<T>(
+        &self,
+        leaf: &mut impl FnMut(&Leaf<S>) -> T,
+        branch: &mut impl FnMut(&Branch<S>, Vec<T>) -> T,
+    ) -> T {
+        let b = match *self {
+            Leafv(ref l) => return leaf(l),
+            Branchv(ref b) => b,
+        };
+        let mut stack = vec![(b, b.v.iter(), Vec::with_capacity(b.v.len()))];
+        loop {
+            let (_, contents, _) = stack.last_mut().unwrap();
+            let folded = match contents.next() {
+                Some(Leafv(ref l)) => leaf(l),
+                Some(Branchv(ref c)) => {
+                    stack.push((c, c.v.iter(), Vec::with_capacity(c.v.len())));
+                    continue;
+                }
+                None => {
+                    let (b, _, values) = stack.pop().unwrap();
+                    let folded = branch(b, values);
+                    if stack.is_empty() {
+                        return folded;
+                    }
+                    folded
+                }
+            };
+            stack.last_mut().unwrap().2.push(folded);
+        }
+    }
+
+    /// rebuilds the tree from the leaves up, replacing each leaf with `leaf` of it, and each branch with `branch` of it, once its contents have been replaced
+    fn rebuild<T>(
+        self,
+        leaf: &mut impl FnMut(Leaf<S>) -> Wood<T>,
+        branch: &mut impl FnMut(Branch<T>) -> Wood<T>,
+    ) -> Wood<T> {
+        let mut stack: Vec<Opened<S, T>> = Vec::new();
+        let mut next = Some(self);
+        loop {
+            let finished = match next.take() {
+                Some(Leafv(l)) => leaf(l),
+                Some(Branchv(b)) => {
+                    stack.push(Opened::new(b));
+                    continue;
+                }
+                None => {
+                    let top = stack.last_mut().unwrap();
+                    match top.pending.next() {
+                        Some(c) => {
+                            next = Some(c);
+                            continue;
+                        }
+                        None => branch(stack.pop().unwrap().close()),
+                    }
+                }
+            };
+            match stack.last_mut() {
+                Some(top) => top.done.push(finished),
+                None => return finished,
+            }
+        }
+    }
+
+    /// Replaces every leaf in the tree with `f` of it. The leaves can be replaced with branches, or with leaves of a different type.
+    /// ```
+    /// let w = wood::parse_woodslist("a (b c)").unwrap();
+    /// let shouted = w.map_leaves(&mut |l| l.v.to_uppercase().into());
+    /// assert_eq!(wood::parse_woodslist("A (B C)").unwrap(), shouted);
+    /// ```
+    pub fn map_leaves<T>(self, f: &mut impl FnMut(Leaf<S>) -> Wood<T>) -> Wood<T> {
+        self.rebuild(f, &mut Branchv)
+    }
+
+    /// Replaces every branch in the tree with `f` of it, from the leaves up, so each branch's contents have already been replaced when it's given to `f`
+    pub fn map_branches(self, f: &mut impl FnMut(Branch<S>) -> Wood<S>) -> Wood<S> {
+        self.rebuild(&mut Leafv, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// writes the tree out the way the woodslist printer would, but for the leaves that were skipped
+    struct Printer {
+        out: String,
+        skip: &'static str,
+    }
+    impl WoodVisitor for Printer {
+        fn enter_branch(&mut self, b: &Branch) -> bool {
+            self.out.push('(');
+            b.v.first().is_none_or(|w| w.initial_str() != self.skip)
+        }
+        fn leave_branch(&mut self, _: &Branch) {
+            if self.out.ends_with(' ') {
+                self.out.pop();
+            }
+            self.out.push_str(") ");
+        }
+        fn visit_leaf(&mut self, l: &Leaf) {
+            self.out.push_str(&l.v);
+            self.out.push(' ');
+        }
+    }
+
+    #[test]
+    fn visiting() {
+        let w = parse_woodslist("a (b (c d)) (skip e) f").unwrap();
+        let mut printer = Printer {
+            out: String::new(),
+            skip: "skip",
+        };
+        w.visit(&mut printer);
+        assert_eq!("(a (b (c d)) () f) ", printer.out);
+
+        struct Doubler;
+        impl WoodVisitorMut for Doubler {
+            fn enter_branch(&mut self, b: &mut Branch) -> bool {
+                b.v.retain(|w| w.initial_str() != "skip");
+                true
+            }
+            fn visit_leaf(&mut self, l: &mut Leaf) {
+                l.v = l.v.repeat(2);
+            }
+        }
+        let mut w = w;
+        w.visit_mut(&mut Doubler);
+        assert_eq!(parse_woodslist("aa (bb (cc dd)) ff").unwrap(), w);
+    }
+
+    #[test]
+    fn traversal_orders() {
+        let w = parse_woodslist("a (b (c)) d").unwrap();
+        let depth: Vec<(Vec<usize>, String)> =
+            w.depth_first().map(|(p, x)| (p, to_woodslist(x))).collect();
+        let breadth: Vec<(Vec<usize>, String)> = w
+            .breadth_first()
+            .map(|(p, x)| (p, to_woodslist(x)))
+            .collect();
+        for (p, s) in depth.iter() {
+            assert_eq!(s, &to_woodslist(w.at_path(p).unwrap()));
+        }
+        let strs =
+            |v: &[(Vec<usize>, String)]| v.iter().map(|(_, s)| s.clone()).collect::<Vec<_>>();
+        assert_eq!(
+            vec!["(a (b (c)) d)", "a", "(b (c))", "b", "(c)", "c", "d"],
+            strs(&depth)
+        );
+        assert_eq!(
+            vec!["(a (b (c)) d)", "a", "(b (c))", "d", "b", "(c)", "c"],
+            strs(&breadth)
+        );
+        assert_eq!(vec![1, 1, 0], depth[5].0);
+        assert!(w.at_path(&[0, 0]).is_none());
+    }
+
+    #[test]
+    fn transforming() {
+        let w = parse_woodslist("a (b (c d)) e").unwrap();
+        let leaves = w.fold(&mut |_| 1, &mut |_, contents: Vec<usize>| {
+            contents.into_iter().sum()
+        });
+        assert_eq!(5, leaves);
+        //flattens single element branches
+        let flattened = parse_woodslist("a ((b)) (c (d))")
+            .unwrap()
+            .map_branches(&mut |mut b| {
+                if b.v.len() == 1 {
+                    b.v.pop().unwrap()
+                } else {
+                    Branchv(b)
+                }
+            });
+        assert_eq!(parse_woodslist("a b (c d)").unwrap(), flattened);
+        let lengths: Wood<usize> = w.map_leaves(&mut |l| {
+            Leafv(Leaf {
+                line: l.line,
+                column: l.column,
+                span: l.span,
+                comments: l.comments,
+                v: l.v.len(),
+            })
+        });
+        assert_eq!(
+            5,
+            lengths.depth_first().filter(|(_, w)| w.is_leaf()).count()
+        );
+    }
+}
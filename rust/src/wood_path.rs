@@ -0,0 +1,532 @@
+use super::*;
+
+/// A query over the structure of a `Wood`, compiled once, from a string like `list/sublist/Datu[name=n]/numbers/*`, and then evaluated against any number of woods.
+///
+/// A path is a series of steps separated by `/`, each one selecting among the children of the woods the step before it selected. The wood a path is evaluated against is treated as a document, so all of its contents are children. After that, the children of a term are its `tail`, since its head is its name.
+/// * `name` selects the children whose `initial_str` is `name`, following the same convention as `Wood::seek`: where a child begins with a nested list, as in `((name vv) val)`, the step selects the initial list, `(name vv)`
+/// * `*` selects every child
+/// * `**` selects the wood itself and everything beneath it, at any depth
+/// * `"a name"` is a name with spaces or any of `/[]="` in it, with the same escapes as termpose
+///
+/// Any step can be followed by predicates, which filter what it selected, in order
+/// * `[2]` keeps the third of them (indices are 0-based)
+/// * `[key]` keeps those with a child that the step `key` would select
+/// * `[key=value]` keeps those with a `key` child whose second element is the leaf `value`
+/// * `[=value]` keeps those that are the leaf `value`
+///
+/// A step can be left empty if it has predicates, in which case it's a `*`, so `numbers/[0]` is the first number.
+///
+/// Each wood is selected at most once, and they're given in the order they appear in the document.
+/// ```
+/// let data = wood::parse_multiline_termpose("
+/// list
+///   sublist
+///     Datu name:m (numbers 1 2)
+///     Datu name:n (numbers 3 4 5)
+/// ").unwrap();
+/// let path = wood::WoodPath::compile("list/sublist/Datu[name=n]/numbers/*").unwrap();
+/// let numbers: Vec<&str> = path.select(&data).map(|w| w.initial_str()).collect();
+/// assert_eq!(vec!["3", "4", "5"], numbers);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WoodPath {
+    source: String,
+    steps: Vec<Step>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Selector {
+    Named(String),
+    Any,
+    Descendants,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Predicate {
+    Index(usize),
+    Has(String),
+    KeyEquals(String, String),
+    LeafEquals(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Step {
+    selector: Selector,
+    predicates: Vec<Predicate>,
+}
+
+/// a wood, with the indices that lead to it from the root
+type Found<'a, S> = (Vec<usize>, &'a Wood<S>);
+
+/// the children of the wood at `at`, with their indices
+fn children<'a, S>(at: &[usize], w: &'a Wood<S>) -> impl Iterator<Item = (usize, &'a Wood<S>)> {
+    let skip = if at.is_empty() { 0 } else { 1 };
+    w.get_branch().unwrap_or(&[]).iter().enumerate().skip(skip)
+}
+
+fn extended(at: &[usize], i: usize) -> Vec<usize> {
+    let mut ret = Vec::with_capacity(at.len() + 1);
+    ret.extend_from_slice(at);
+    ret.push(i);
+    ret
+}
+
+/// the children of `w` that the step `name` would select
+fn named<'a, S: AsRef<str>>(at: &[usize], w: &'a Wood<S>, name: &str) -> Vec<Found<'a, S>> {
+    children(at, w)
+        .filter(|&(_, c)| c.initial_str() == name)
+        .map(|(i, c)| {
+            let mut path = extended(at, i);
+            let mut c = c;
+            while let Some(first @ Branchv(_)) = c.get_branch().and_then(|v| v.first()) {
+                path.push(0);
+                c = first;
+            }
+            (path, c)
+        })
+        .collect()
+}
+
+impl Predicate {
+    fn accepts<S: AsRef<str>>(&self, (at, w): &Found<S>) -> bool {
+        match *self {
+            Predicate::Index(_) => true,
+            Predicate::Has(ref key) => !named(at, w, key).is_empty(),
+            Predicate::KeyEquals(ref key, ref value) => named(at, w, key)
+                .iter()
+                .any(|(_, k)| k.tail().next().and_then(|v| v.get_leaf()) == Some(value.as_str())),
+            Predicate::LeafEquals(ref value) => w.get_leaf() == Some(value.as_str()),
+        }
+    }
+}
+
+impl Step {
+    fn apply<'a, S: AsRef<str>>(&self, at: &[usize], w: &'a Wood<S>, out: &mut Vec<Found<'a, S>>) {
+        let mut selected: Vec<Found<'a, S>> = match self.selector {
+            Selector::Named(ref name) => named(at, w, name),
+            Selector::Any => children(at, w).map(|(i, c)| (extended(at, i), c)).collect(),
+            Selector::Descendants => {
+                let mut ret = Vec::new();
+                let mut stack = vec![(at.to_vec(), w)];
+                while let Some((path, w)) = stack.pop() {
+                    let len = stack.len();
+                    stack.extend(children(&path, w).map(|(i, c)| (extended(&path, i), c)));
+                    stack[len..].reverse();
+                    ret.push((path, w));
+                }
+                ret
+            }
+        };
+        for p in self.predicates.iter() {
+            match *p {
+                Predicate::Index(i) => {
+                    selected = if i < selected.len() {
+                        vec![selected.swap_remove(i)]
+                    } else {
+                        Vec::new()
+                    };
+                }
+                _ => selected.retain(|f| p.accepts(f)),
+            }
+        }
+        out.extend(selected);
+    }
+}
+
+impl WoodPath {
+    /// Parses a path. Errors are `ErrorKind::Syntax`, and locate the problem by its column in `path`.
+    pub fn compile(path: &str) -> Result<WoodPath, Box<WoodError>> {
+        PathParser {
+            source: path,
+            chars: path.char_indices().peekable(),
+            column: 0,
+        }
+        .parse()
+    }
+
+    fn evaluate<'a, S: AsRef<str>>(&self, root: &'a Wood<S>) -> Vec<Found<'a, S>> {
+        let mut selected: Vec<Found<'a, S>> = vec![(Vec::new(), root)];
+        for step in self.steps.iter() {
+            let mut next = Vec::new();
+            for (at, w) in selected.iter() {
+                step.apply(at, w, &mut next);
+            }
+            //paths sort in document order, and the same wood can be reached more than once, eg, by `**/**`
+            next.sort_by(|a, b| a.0.cmp(&b.0));
+            next.dedup_by(|a, b| a.0 == b.0);
+            selected = next;
+        }
+        selected
+    }
+
+    /// every wood in `root` that the path selects
+    pub fn select<'a, S: AsRef<str>>(&self, root: &'a Wood<S>) -> std::vec::IntoIter<&'a Wood<S>> {
+        self.evaluate(root)
+            .into_iter()
+            .map(|(_, w)| w)
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    /// the first wood in `root` that the path selects
+    pub fn first<'a, S: AsRef<str>>(&self, root: &'a Wood<S>) -> Option<&'a Wood<S>> {
+        self.select(root).next()
+    }
+
+    /// Every wood in `root` that the path selects, to be edited. A wood can't be borrowed mutably at the same time as a wood inside it, so where the path selects both, as `**` generally does, only the outer one is given.
+    pub fn select_mut<'a, S: AsRef<str>>(
+        &self,
+        root: &'a mut Wood<S>,
+    ) -> std::vec::IntoIter<&'a mut Wood<S>> {
+        let mut paths: Vec<Vec<usize>> = Vec::new();
+        for (path, _) in self.evaluate(root) {
+            //they're in document order, so anything inside the last one kept comes right after it
+            if !paths.last().is_some_and(|outer| path.starts_with(outer)) {
+                paths.push(path);
+            }
+        }
+        borrow_all_mut(root, &paths).into_iter()
+    }
+
+    /// the first wood in `root` that the path selects, to be edited
+    pub fn first_mut<'a, S: AsRef<str>>(&self, root: &'a mut Wood<S>) -> Option<&'a mut Wood<S>> {
+        self.select_mut(root).next()
+    }
+
+    /// the text the path was compiled from
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+}
+
+/// borrows the woods at `paths`, which must be in document order, with none inside another
+fn borrow_all_mut<'a, S>(root: &'a mut Wood<S>, paths: &[Vec<usize>]) -> Vec<&'a mut Wood<S>> {
+    let mut ret = Vec::with_capacity(paths.len());
+    if paths.is_empty() {
+        return ret;
+    }
+    let mut work = vec![(root, paths, 0)];
+    while let Some((w, paths, depth)) = work.pop() {
+        if paths[0].len() == depth {
+            //none are inside another, so this is the only one
+            ret.push(w);
+            continue;
+        }
+        let Branchv(ref mut b) = *w else {
+            unreachable!("a path led into a leaf")
+        };
+        let mut children = b.v.iter_mut().enumerate();
+        let mut rest = paths;
+        let len = work.len();
+        while let Some(first) = rest.first() {
+            let i = first[depth];
+            let (group, after) = rest.split_at(rest.iter().take_while(|p| p[depth] == i).count());
+            let (_, child) = children.find(|&(j, _)| j == i).unwrap();
+            work.push((child, group, depth + 1));
+            rest = after;
+        }
+        //so that they come off the stack in order
+        work[len..].reverse();
+    }
+    ret
+}
+
+impl FromStr for WoodPath {
+    type Err = Box<WoodError>;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        WoodPath::compile(s)
+    }
+}
+
+impl Display for WoodPath {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+struct PathParser<'a> {
+    source: &'a str,
+    chars: std::iter::Peekable<std::str::CharIndices<'a>>,
+    /// the number of chars consumed so far
+    column: isize,
+}
+
+impl PathParser<'_> {
+    fn offset(&mut self) -> usize {
+        self.chars.peek().map_or(self.source.len(), |&(i, _)| i)
+    }
+    fn next(&mut self) -> Option<char> {
+        let ret = self.chars.next().map(|(_, c)| c);
+        if ret.is_some() {
+            self.column += 1;
+        }
+        ret
+    }
+    fn peek(&mut self) -> Option<char> {
+        self.chars.peek().map(|&(_, c)| c)
+    }
+    fn error(&mut self, msg: String) -> Box<WoodError> {
+        let start = self.offset();
+        let end = start + self.peek().map_or(0, |c| c.len_utf8());
+        Box::new(WoodError {
+            line: 1,
+            column: self.column + 1,
+            span: Some(Span::new(None, start, end)),
+            kind: ErrorKind::Syntax,
+            path: Vec::new(),
+            msg,
+            cause: None,
+        })
+    }
+    fn expect(&mut self, c: char) -> Result<(), Box<WoodError>> {
+        if self.peek() == Some(c) {
+            self.next();
+            Ok(())
+        } else {
+            Err(self.unexpected(&format!("'{}'", c)))
+        }
+    }
+    fn unexpected(&mut self, expected: &str) -> Box<WoodError> {
+        let msg = match self.peek() {
+            Some(c) => format!("expected {}, found '{}'", expected, c),
+            None => format!("expected {}, but the path ended", expected),
+        };
+        self.error(msg)
+    }
+
+    fn parse(mut self) -> Result<WoodPath, Box<WoodError>> {
+        let mut steps = Vec::new();
+        loop {
+            steps.push(self.step()?);
+            match self.peek() {
+                Some('/') => {
+                    self.next();
+                }
+                None => break,
+                Some(_) => return Err(self.unexpected("'/' or '['")),
+            }
+        }
+        Ok(WoodPath {
+            source: self.source.to_string(),
+            steps,
+        })
+    }
+
+    fn step(&mut self) -> Result<Step, Box<WoodError>> {
+        let selector = match self.peek() {
+            Some('*') => {
+                self.next();
+                if self.peek() == Some('*') {
+                    self.next();
+                    Selector::Descendants
+                } else {
+                    Selector::Any
+                }
+            }
+            Some('[') => Selector::Any,
+            _ => Selector::Named(self.name()?),
+        };
+        let mut predicates = Vec::new();
+        while self.peek() == Some('[') {
+            self.next();
+            predicates.push(self.predicate()?);
+            self.expect(']')?;
+        }
+        Ok(Step {
+            selector,
+            predicates,
+        })
+    }
+
+    fn predicate(&mut self) -> Result<Predicate, Box<WoodError>> {
+        match self.peek() {
+            Some('=') => {
+                self.next();
+                Ok(Predicate::LeafEquals(self.name()?))
+            }
+            Some(c) if c.is_ascii_digit() => {
+                let mut digits = String::new();
+                while let Some(c) = self.peek().filter(|c| c.is_ascii_digit()) {
+                    digits.push(c);
+                    self.next();
+                }
+                digits
+                    .parse()
+                    .map(Predicate::Index)
+                    .map_err(|_| self.error(format!("the index {} is too large", digits)))
+            }
+            _ => {
+                let key = self.name()?;
+                if self.peek() == Some('=') {
+                    self.next();
+                    Ok(Predicate::KeyEquals(key, self.name()?))
+                } else {
+                    Ok(Predicate::Has(key))
+                }
+            }
+        }
+    }
+
+    fn name(&mut self) -> Result<String, Box<WoodError>> {
+        let mut ret = String::new();
+        if self.peek() == Some('"') {
+            self.next();
+            loop {
+                match self.next() {
+                    Some('"') => return Ok(ret),
+                    Some('\\') => match self.next() {
+                        Some('n') => ret.push('\n'),
+                        Some('t') => ret.push('\t'),
+                        Some('r') => ret.push('\r'),
+                        Some(c) => ret.push(c),
+                        None => break,
+                    },
+                    Some(c) => ret.push(c),
+                    None => break,
+                }
+            }
+            return Err(self.error("this quoted name is never closed".to_string()));
+        }
+        while let Some(c) = self.peek() {
+            match c {
+                '/' | '[' | ']' | '=' => break,
+                '"' | '*' => {
+                    return Err(
+                        self.error(format!("'{}' can only appear in a name if it's quoted", c))
+                    )
+                }
+                c if c.is_whitespace() => {
+                    return Err(self
+                        .error("whitespace can only appear in a name if it's quoted".to_string()))
+                }
+                c => {
+                    ret.push(c);
+                    self.next();
+                }
+            }
+        }
+        if ret.is_empty() {
+            Err(self.unexpected("a name"))
+        } else {
+            Ok(ret)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn data() -> Wood {
+        parse_multiline_termpose(
+            "
+list
+  sublist
+    Datu name:m (numbers 1 2)
+    Datu name:n (numbers 3 4 5)
+  ((sublist annotated) note)
+    Datu name:o
+other 7
+",
+        )
+        .unwrap()
+    }
+
+    fn selected(path: &str, w: &Wood) -> Vec<String> {
+        WoodPath::compile(path)
+            .unwrap()
+            .select(w)
+            .map(pretty_termpose)
+            .collect()
+    }
+
+    #[test]
+    fn paths_select() {
+        let d = data();
+        assert_eq!(
+            vec!["3", "4", "5"],
+            selected("list/sublist/Datu[name=n]/numbers/*", &d)
+        );
+        //the nested initial list is selected, rather than the term it heads
+        assert_eq!(
+            vec![
+                "sublist (Datu name:m (numbers 1 2)) (Datu name:n (numbers 3 4 5))",
+                "sublist annotated"
+            ],
+            selected("list/sublist", &d)
+        );
+        //`*` selects the whole term, so the Datu that was attached to it can be reached
+        assert_eq!(
+            vec!["name m", "name n", "name o"],
+            selected("list/*/Datu/name", &d)
+        );
+        assert_eq!(
+            Vec::<String>::new(),
+            selected("list/sublist/Datu[name=o]", &d)
+        );
+        assert_eq!(vec!["Datu name:o"], selected("**/Datu[name=o]", &d));
+        //indices count among the woods selected from each parent
+        assert_eq!(vec!["2", "4"], selected("**/numbers/[1]", &d));
+        assert_eq!(
+            vec!["Datu name:m (numbers 1 2)"],
+            selected("**/Datu[numbers][0]", &d)
+        );
+        assert_eq!(vec!["7"], selected("*/[=7]", &d));
+        assert_eq!(vec!["other 7"], selected("other", &d));
+        assert_eq!(Vec::<String>::new(), selected("list/Datu", &d));
+        //`**` selects each wood once, however many ways there are to reach it
+        assert_eq!(selected("**", &d), selected("**/**", &d));
+        assert_eq!(
+            vec!["a/b c"],
+            selected("\"a/b\"", &woods!(woods!("a/b", "c")))
+        );
+    }
+
+    #[test]
+    fn seek_finds_the_initial_list() {
+        let w = parse_woodslist("((key vv) val) (nonkey a)").unwrap();
+        assert_eq!("vv", w.find_val("key").unwrap().initial_str());
+        assert_eq!(w.seek("key"), WoodPath::compile("key").unwrap().first(&w));
+    }
+
+    #[test]
+    fn paths_select_mut() {
+        let mut d = data();
+        for n in WoodPath::compile("**/numbers/*")
+            .unwrap()
+            .select_mut(&mut d)
+        {
+            *n = Wood::from("0");
+        }
+        assert_eq!(vec!["0", "0", "0", "0", "0"], selected("**/numbers/*", &d));
+        //only the outermost of nested selections
+        let path = WoodPath::compile("**[name]").unwrap();
+        assert_eq!(3, path.select_mut(&mut d).count());
+        let path = WoodPath::compile("**").unwrap();
+        assert_eq!(1, path.select_mut(&mut d).count());
+    }
+
+    #[test]
+    fn bad_paths_are_located() {
+        for (path, column, msg) in [
+            ("a//b", 3, "expected a name, found '/'"),
+            ("a[b", 4, "expected ']', but the path ended"),
+            (
+                "a b",
+                2,
+                "whitespace can only appear in a name if it's quoted",
+            ),
+            ("\"ab", 4, "this quoted name is never closed"),
+            ("a*", 2, "'*' can only appear in a name if it's quoted"),
+            ("", 1, "expected a name, but the path ended"),
+        ] {
+            let e = WoodPath::compile(path).unwrap_err();
+            assert_eq!(
+                (ErrorKind::Syntax, column, msg),
+                (e.kind, e.column, &e.msg[..]),
+                "{}",
+                path
+            );
+        }
+    }
+}
@@ -1,8 +1,11 @@
 use super::*;
-use std::cmp::Eq;
-use std::collections::HashMap;
+use std::cmp::{Eq, Ord};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
 use std::hash::Hash;
 use std::iter::FromIterator;
+use std::ops::Deref;
+use std::rc::Rc;
+use std::sync::Arc;
 
 /// Specifies a bijection between T and Wood
 pub trait Biwooder<T>: Wooder<T> + Dewooder<T> {}
@@ -23,17 +26,42 @@ impl<'a> FieldScanning<'a> {
             eye: 0,
         }
     }
+    /// For woods that are just the pairs, with no tag before them, as `#[wood(untagged)]` enum variants are written. Errors if `v` is a leaf.
+    pub fn untagged(v: &'a Wood) -> Result<Self, Box<WoodError>> {
+        match v.get_branch() {
+            Some(li) => Ok(FieldScanning { v, li, eye: 0 }),
+            None => Err(Box::new(
+                WoodError::new(
+                    v,
+                    "expected a list of fields, but this is a leaf".to_string(),
+                )
+                .with_kind(ErrorKind::UnexpectedLeaf),
+            )),
+        }
+    }
+    /// The wood under `key`. Errors if there isn't one, with the key as the error's path.
     pub fn find(&mut self, key: &str) -> Result<&Wood, Box<WoodError>> {
+        self.find_optional(key)?.ok_or_else(|| {
+            Box::new(
+                WoodError::new(self.v, format!("could not find key \"{}\"", key))
+                    .with_kind(ErrorKind::MissingKey),
+            )
+            .within(PathSegment::Key(key.to_string()))
+        })
+    }
+    /// `find`, but a missing key isn't an error
+    pub fn find_optional(&mut self, key: &str) -> Result<Option<&'a Wood>, Box<WoodError>> {
         for _ in 0..self.li.len() {
             let c = &self.li[self.eye];
             if c.initial_str() == key {
                 return if let Some(s) = c.tail().next() {
-                    Ok(s)
+                    Ok(Some(s))
                 } else {
-                    Err(Box::new(WoodError::new(
-                        c,
-                        format!("expected a subwood, but the wood has no tail"),
-                    )))
+                    Err(Box::new(
+                        WoodError::new(c, format!("expected a subwood, but the wood has no tail"))
+                            .with_kind(ErrorKind::ArityMismatch),
+                    )
+                    .within(PathSegment::Key(key.to_string())))
                 };
             }
             self.eye += 1;
@@ -41,10 +69,39 @@ impl<'a> FieldScanning<'a> {
                 self.eye = 0;
             }
         }
-        Err(Box::new(WoodError::new(
-            self.v,
-            format!("could not find key \"{}\"", key),
-        )))
+        Ok(None)
+    }
+    /// Used by wood_derive for `#[wood(deny_unknown_fields)]`. Errors at the first entry that isn't under one of `keys`, naming every such entry. `of` is what the struct's called, for the message.
+    pub fn deny_unknown_keys(&self, keys: &[&str], of: &str) -> Result<(), Box<WoodError>> {
+        let mut unknown = self.li.iter().filter(|c| !keys.contains(&c.initial_str()));
+        if let Some(first) = unknown.next() {
+            let mut msg = format!("{} has no field called \"{}\"", of, first.initial_str());
+            let others: Vec<String> = unknown
+                .map(|c| format!("\"{}\"", c.initial_str()))
+                .collect();
+            if !others.is_empty() {
+                msg.push_str(&format!(" (nor {})", others.join(", ")));
+            }
+            msg.push_str(&format!(". Its fields are: {}", keys.join(", ")));
+            return Err(
+                Box::new(WoodError::new(first, msg).with_kind(ErrorKind::UnknownKey))
+                    .within(PathSegment::Key(first.initial_str().to_string())),
+            );
+        }
+        Ok(())
+    }
+    /// Used by wood_derive for `#[wood(flatten)]` fields. A copy of the wood without the entries under `keys`, which are the ones the struct reads itself, leaving the ones that belong to the flattened field. It keeps the tag, or if there wasn't one, puts an empty one in its place, so the flattened field can always read it as tagged.
+    pub fn without_keys(&self, keys: &[&str]) -> Wood {
+        let tagged = self
+            .v
+            .get_branch()
+            .is_none_or(|b| b.as_ptr() != self.li.as_ptr());
+        let head = match self.v.head() {
+            Ok(h) if tagged => h.clone(),
+            _ => "".into(),
+        };
+        let rest = self.li.iter().filter(|w| !keys.contains(&w.initial_str()));
+        Wood::branch(std::iter::once(head).chain(rest.cloned()).collect())
     }
 }
 
@@ -66,6 +123,9 @@ where
     fn dewoodify(&self, v: &Wood) -> Result<T, Box<WoodError>> {
         T::dewoodify(v)
     }
+    fn dewoodify_all_into(&self, v: &Wood, errors: &mut WoodErrors) -> Option<T> {
+        T::dewoodify_all_into(v, errors)
+    }
 }
 
 /// A biwooder that takes any valid expression of a bool, but produces the "yes"/"no" forms when wooding
@@ -121,6 +181,9 @@ where
     fn dewoodify(&self, v: &Wood) -> Result<T, Box<WoodError>> {
         Dewooder::dewoodify(&self.1, v)
     }
+    fn dewoodify_all_into(&self, v: &Wood, errors: &mut WoodErrors) -> Option<T> {
+        self.1.dewoodify_all_into(v, errors)
+    }
 }
 
 /// you might want this for initializing biwooders that can't be constexprs
@@ -140,6 +203,9 @@ where
     fn dewoodify(&self, v: &Wood) -> Result<T, Box<WoodError>> {
         Dewooder::dewoodify(&**self.0.as_ref().unwrap(), v)
     }
+    fn dewoodify_all_into(&self, v: &Wood, errors: &mut WoodErrors) -> Option<T> {
+        self.0.as_ref().unwrap().dewoodify_all_into(v, errors)
+    }
 }
 impl<B> OptionalBoxBiwooder<B>
 where
@@ -181,6 +247,10 @@ where
         dewoodify_seq_into(&self.0, v.contents(), &mut ret)?;
         Ok(ret)
     }
+    fn dewoodify_all_into(&self, v: &Wood, errors: &mut WoodErrors) -> Option<Vec<T>> {
+        let mut ret = Vec::new();
+        dewoodify_seq_all_into(&self.0, v.contents(), &mut ret, errors).then_some(ret)
+    }
 }
 
 #[derive(Copy, Clone)]
@@ -206,28 +276,37 @@ fn ensure_tag<'b>(v: &'b Wood, tag: &str) -> Result<std::slice::Iter<'b, Wood>,
                 if name == tag {
                     Ok(i)
                 } else {
-                    Err(Box::new(WoodError::new(
-                        name_wood,
-                        format!(
-                            "expected \"{}\" here, but instead there was \"{}\"",
-                            tag, name
-                        ),
-                    )))
+                    Err(Box::new(
+                        WoodError::new(
+                            name_wood,
+                            format!(
+                                "expected \"{}\" here, but instead there was \"{}\"",
+                                tag, name
+                            ),
+                        )
+                        .with_kind(ErrorKind::UnknownVariant),
+                    ))
                 }
             }
-            _ => Err(Box::new(WoodError::new(
-                name_wood,
-                format!(
-                    "expected \"{}\" here, but instead there was a branch wood",
-                    tag
-                ),
-            ))),
+            _ => Err(Box::new(
+                WoodError::new(
+                    name_wood,
+                    format!(
+                        "expected \"{}\" here, but instead there was a branch wood",
+                        tag
+                    ),
+                )
+                .with_kind(ErrorKind::UnexpectedBranch),
+            )),
         }
     } else {
-        Err(Box::new(WoodError::new(
-            v,
-            format!("expected \"{}\" at beginning, but the wood was empty", tag),
-        )))
+        Err(Box::new(
+            WoodError::new(
+                v,
+                format!("expected \"{}\" at beginning, but the wood was empty", tag),
+            )
+            .with_kind(ErrorKind::ArityMismatch),
+        ))
     }
 }
 
@@ -241,6 +320,11 @@ where
         dewoodify_seq_into(&self.1, it, &mut ret)?;
         Ok(ret)
     }
+    fn dewoodify_all_into(&self, v: &Wood, errors: &mut WoodErrors) -> Option<Vec<T>> {
+        let mut ret = Vec::new();
+        let it = errors.record(ensure_tag(v, self.0))?;
+        dewoodify_seq_all_into(&self.1, it, &mut ret, errors).then_some(ret)
+    }
 }
 
 fn dewoodify_pair<K, V, KeyTran, ValTran>(
@@ -256,29 +340,56 @@ where
         Branchv(ref lc) => {
             if lc.v.len() == 2 {
                 unsafe {
-                    let k = kt.dewoodify(lc.v.get_unchecked(0))?; // safe: we just checked the length
-                    let v = vt.dewoodify(lc.v.get_unchecked(1))?; //
+                    let kw = lc.v.get_unchecked(0); // safe: we just checked the length
+                    let k = kt.dewoodify(kw)?;
+                    let v = vt
+                        .dewoodify(lc.v.get_unchecked(1))
+                        .map_err(|e| e.within(PathSegment::Key(kw.initial_str().to_string())))?;
                     Ok((k, v))
                 }
             } else {
-                Err(Box::new(WoodError::new(
-                    v,
-                    format!(
-                        "expected a pair, two elements, but the branch here has {}",
-                        lc.v.len()
-                    ),
-                )))
+                Err(Box::new(
+                    WoodError::new(
+                        v,
+                        format!(
+                            "expected a pair, two elements, but the branch here has {}",
+                            lc.v.len()
+                        ),
+                    )
+                    .with_kind(ErrorKind::ArityMismatch),
+                ))
             }
         }
-        Leafv(_) => Err(Box::new(WoodError::new(
-            v,
-            "expected a pair, but the wood here is an leaf".into(),
-        ))),
+        Leafv(_) => Err(Box::new(
+            WoodError::new(v, "expected a pair, but the wood here is an leaf".into())
+                .with_kind(ErrorKind::UnexpectedLeaf),
+        )),
     }
 }
 
+/// `dewoodify_pair`, but reads both sides, noting their errors
+fn dewoodify_pair_all<K, V, KeyTran, ValTran>(
+    kt: &KeyTran,
+    vt: &ValTran,
+    v: &Wood,
+    errors: &mut WoodErrors,
+) -> Option<(K, V)>
+where
+    KeyTran: Dewooder<K>,
+    ValTran: Dewooder<V>,
+{
+    let (kw, vw) = match *v {
+        Branchv(ref lc) if lc.v.len() == 2 => (&lc.v[0], &lc.v[1]),
+        _ => return errors.record(dewoodify_pair(kt, vt, v)),
+    };
+    let k = kt.dewoodify_all_into(kw, errors);
+    let step = PathSegment::Key(kw.initial_str().to_string());
+    let v = errors.within(step, |errors| vt.dewoodify_all_into(vw, errors));
+    Some((k?, v?))
+}
+
 #[derive(Copy, Clone)]
-pub struct PairBi<KeyTran, ValTran>(KeyTran, ValTran);
+pub struct PairBi<KeyTran, ValTran>(pub KeyTran, pub ValTran);
 impl<K, V, KeyTran, ValTran> Wooder<(K, V)> for PairBi<KeyTran, ValTran>
 where
     KeyTran: Wooder<K>,
@@ -298,6 +409,9 @@ where
     fn dewoodify(&self, v: &Wood) -> Result<(K, V), Box<WoodError>> {
         dewoodify_pair(&self.0, &self.1, v)
     }
+    fn dewoodify_all_into(&self, v: &Wood, errors: &mut WoodErrors) -> Option<(K, V)> {
+        dewoodify_pair_all(&self.0, &self.1, v, errors)
+    }
 }
 
 fn woodify_map<'a, K, V, KeyWooder, ValWooder, I>(
@@ -334,10 +448,33 @@ where
     Ok(())
 }
 
+/// `dewoodify_map`, but reads every pair, noting the errors of the ones that fail. Returns whether they all succeeded.
+fn dewoodify_map_all<'a, K, V, KeyTran, ValTran, I>(
+    ktr: &KeyTran,
+    vtr: &ValTran,
+    i: I,
+    o: &mut Vec<(K, V)>,
+    errors: &mut WoodErrors,
+) -> bool
+where
+    KeyTran: Dewooder<K>,
+    ValTran: Dewooder<V>,
+    I: Iterator<Item = &'a Wood>,
+{
+    let mut all = true;
+    for v in i {
+        match dewoodify_pair_all(ktr, vtr, v, errors) {
+            Some(p) => o.push(p),
+            None => all = false,
+        }
+    }
+    all
+}
+
 impl<K, V> Woodable for HashMap<K, V>
 where
     K: Eq + Hash + Woodable,
-    V: Eq + Hash + Woodable,
+    V: Woodable,
 {
     fn woodify(&self) -> Wood {
         let mut ret = Vec::new();
@@ -348,23 +485,25 @@ where
 impl<K, V> Dewoodable for HashMap<K, V>
 where
     K: Eq + Hash + Dewoodable,
-    V: Eq + Hash + Dewoodable,
+    V: Dewoodable,
 {
     fn dewoodify(v: &Wood) -> Result<HashMap<K, V>, Box<WoodError>> {
         let mut ret = Vec::new();
         dewoodify_map(&Iden, &Iden, v.contents(), &mut ret)?;
         Ok(HashMap::from_iter(ret.into_iter()))
     }
+    fn dewoodify_all_into(v: &Wood, errors: &mut WoodErrors) -> Option<HashMap<K, V>> {
+        HashMapBi(Iden, Iden).dewoodify_all_into(v, errors)
+    }
 }
 
 #[derive(Clone)]
-pub struct HashMapBi<KeyTran, ValTran>(KeyTran, ValTran);
+pub struct HashMapBi<KeyTran, ValTran>(pub KeyTran, pub ValTran);
 impl<K, V, KeyTran, ValTran> Wooder<HashMap<K, V>> for HashMapBi<KeyTran, ValTran>
 where
     KeyTran: Wooder<K>,
     ValTran: Wooder<V>,
     K: Eq + Hash,
-    V: Eq + Hash,
 {
     fn woodify(&self, v: &HashMap<K, V>) -> Wood {
         let mut ret = Vec::new();
@@ -377,23 +516,26 @@ where
     KeyTran: Dewooder<K>,
     ValTran: Dewooder<V>,
     K: Eq + Hash,
-    V: Eq + Hash,
 {
     fn dewoodify(&self, v: &Wood) -> Result<HashMap<K, V>, Box<WoodError>> {
         let mut ret = Vec::new();
         dewoodify_map(&self.0, &self.1, v.contents(), &mut ret)?;
         Ok(HashMap::from_iter(ret.into_iter()))
     }
+    fn dewoodify_all_into(&self, v: &Wood, errors: &mut WoodErrors) -> Option<HashMap<K, V>> {
+        let mut ret = Vec::new();
+        dewoodify_map_all(&self.0, &self.1, v.contents(), &mut ret, errors)
+            .then(|| HashMap::from_iter(ret))
+    }
 }
 
 #[derive(Clone)]
-pub struct TaggedHashMapBi<'a, KeyTran, ValTran>(&'a str, KeyTran, ValTran);
+pub struct TaggedHashMapBi<'a, KeyTran, ValTran>(pub &'a str, pub KeyTran, pub ValTran);
 impl<'a, K, V, KeyTran, ValTran> Wooder<HashMap<K, V>> for TaggedHashMapBi<'a, KeyTran, ValTran>
 where
     KeyTran: Wooder<K>,
     ValTran: Wooder<V>,
     K: Eq + Hash,
-    V: Eq + Hash,
 {
     fn woodify(&self, v: &HashMap<K, V>) -> Wood {
         let mut ret = Vec::new();
@@ -407,7 +549,6 @@ where
     KeyTran: Dewooder<K>,
     ValTran: Dewooder<V>,
     K: Eq + Hash,
-    V: Eq + Hash,
 {
     fn dewoodify(&self, v: &Wood) -> Result<HashMap<K, V>, Box<WoodError>> {
         let mut ret = Vec::new();
@@ -415,12 +556,596 @@ where
         dewoodify_map(&self.1, &self.2, it, &mut ret)?;
         Ok(HashMap::from_iter(ret.into_iter()))
     }
+    fn dewoodify_all_into(&self, v: &Wood, errors: &mut WoodErrors) -> Option<HashMap<K, V>> {
+        let mut ret = Vec::new();
+        let it = errors.record(ensure_tag(v, self.0))?;
+        dewoodify_map_all(&self.1, &self.2, it, &mut ret, errors).then(|| HashMap::from_iter(ret))
+    }
+}
+
+/// HashMapBi, but writes the pairs in key order, so that the same map is always written the same way, instead of in an order that changes from run to run
+#[derive(Clone)]
+pub struct SortedHashMapBi<KeyTran, ValTran>(pub KeyTran, pub ValTran);
+impl<K, V, KeyTran, ValTran> Wooder<HashMap<K, V>> for SortedHashMapBi<KeyTran, ValTran>
+where
+    KeyTran: Wooder<K>,
+    ValTran: Wooder<V>,
+    K: Eq + Hash + Ord,
+{
+    fn woodify(&self, v: &HashMap<K, V>) -> Wood {
+        let mut pairs: Vec<(&K, &V)> = v.iter().collect();
+        pairs.sort_unstable_by(|a, b| a.0.cmp(b.0));
+        let mut ret = Vec::new();
+        woodify_map(&self.0, &self.1, pairs.into_iter(), &mut ret);
+        ret.into()
+    }
+}
+impl<K, V, KeyTran, ValTran> Dewooder<HashMap<K, V>> for SortedHashMapBi<KeyTran, ValTran>
+where
+    KeyTran: Dewooder<K>,
+    ValTran: Dewooder<V>,
+    K: Eq + Hash,
+{
+    fn dewoodify(&self, v: &Wood) -> Result<HashMap<K, V>, Box<WoodError>> {
+        let mut ret = Vec::new();
+        dewoodify_map(&self.0, &self.1, v.contents(), &mut ret)?;
+        Ok(HashMap::from_iter(ret))
+    }
+    fn dewoodify_all_into(&self, v: &Wood, errors: &mut WoodErrors) -> Option<HashMap<K, V>> {
+        let mut ret = Vec::new();
+        dewoodify_map_all(&self.0, &self.1, v.contents(), &mut ret, errors)
+            .then(|| HashMap::from_iter(ret))
+    }
+}
+
+/// HashSetBi, but writes the elements in order
+#[derive(Copy, Clone)]
+pub struct SortedHashSetBi<SubTran>(pub SubTran);
+impl<T, SubTran> Wooder<HashSet<T>> for SortedHashSetBi<SubTran>
+where
+    SubTran: Wooder<T>,
+    T: Eq + Hash + Ord,
+{
+    fn woodify(&self, v: &HashSet<T>) -> Wood {
+        let mut elements: Vec<&T> = v.iter().collect();
+        elements.sort_unstable();
+        let mut ret = Vec::new();
+        woodify_seq_into(&self.0, elements.into_iter(), &mut ret);
+        ret.into()
+    }
+}
+impl<T, SubTran> Dewooder<HashSet<T>> for SortedHashSetBi<SubTran>
+where
+    SubTran: Dewooder<T>,
+    T: Eq + Hash,
+{
+    fn dewoodify(&self, v: &Wood) -> Result<HashSet<T>, Box<WoodError>> {
+        let mut ret = Vec::new();
+        dewoodify_seq_into(&self.0, v.contents(), &mut ret)?;
+        Ok(HashSet::from_iter(ret))
+    }
+    fn dewoodify_all_into(&self, v: &Wood, errors: &mut WoodErrors) -> Option<HashSet<T>> {
+        let mut ret = Vec::new();
+        dewoodify_seq_all_into(&self.0, v.contents(), &mut ret, errors)
+            .then(|| HashSet::from_iter(ret))
+    }
+}
+
+impl<K, V> Woodable for BTreeMap<K, V>
+where
+    K: Ord + Woodable,
+    V: Woodable,
+{
+    fn woodify(&self) -> Wood {
+        let mut ret = Vec::new();
+        woodify_map(&Iden, &Iden, self.iter(), &mut ret);
+        ret.into()
+    }
+}
+impl<K, V> Dewoodable for BTreeMap<K, V>
+where
+    K: Ord + Dewoodable,
+    V: Dewoodable,
+{
+    fn dewoodify(v: &Wood) -> Result<BTreeMap<K, V>, Box<WoodError>> {
+        let mut ret = Vec::new();
+        dewoodify_map(&Iden, &Iden, v.contents(), &mut ret)?;
+        Ok(BTreeMap::from_iter(ret))
+    }
+    fn dewoodify_all_into(v: &Wood, errors: &mut WoodErrors) -> Option<BTreeMap<K, V>> {
+        BTreeMapBi(Iden, Iden).dewoodify_all_into(v, errors)
+    }
+}
+
+/// Writes the map as a branch of pairs, in key order
+#[derive(Clone)]
+pub struct BTreeMapBi<KeyTran, ValTran>(pub KeyTran, pub ValTran);
+impl<K, V, KeyTran, ValTran> Wooder<BTreeMap<K, V>> for BTreeMapBi<KeyTran, ValTran>
+where
+    KeyTran: Wooder<K>,
+    ValTran: Wooder<V>,
+    K: Ord,
+{
+    fn woodify(&self, v: &BTreeMap<K, V>) -> Wood {
+        let mut ret = Vec::new();
+        woodify_map(&self.0, &self.1, v.iter(), &mut ret);
+        ret.into()
+    }
+}
+impl<K, V, KeyTran, ValTran> Dewooder<BTreeMap<K, V>> for BTreeMapBi<KeyTran, ValTran>
+where
+    KeyTran: Dewooder<K>,
+    ValTran: Dewooder<V>,
+    K: Ord,
+{
+    fn dewoodify(&self, v: &Wood) -> Result<BTreeMap<K, V>, Box<WoodError>> {
+        let mut ret = Vec::new();
+        dewoodify_map(&self.0, &self.1, v.contents(), &mut ret)?;
+        Ok(BTreeMap::from_iter(ret))
+    }
+    fn dewoodify_all_into(&self, v: &Wood, errors: &mut WoodErrors) -> Option<BTreeMap<K, V>> {
+        let mut ret = Vec::new();
+        dewoodify_map_all(&self.0, &self.1, v.contents(), &mut ret, errors)
+            .then(|| BTreeMap::from_iter(ret))
+    }
+}
+
+/// Sequences other than Vec, which are written the same way a Vec is, as a branch of their elements
+macro_rules! sequence_impls {
+    ($(#[$doc:meta])* $Bi:ident, $Seq:ident, [$($bounds:tt)*]) => {
+        impl<T> Woodable for $Seq<T>
+        where
+            T: Woodable + $($bounds)*,
+        {
+            fn woodify(&self) -> Wood {
+                $Bi(Iden).woodify(self)
+            }
+        }
+        impl<T> Dewoodable for $Seq<T>
+        where
+            T: Dewoodable + $($bounds)*,
+        {
+            fn dewoodify(v: &Wood) -> Result<$Seq<T>, Box<WoodError>> {
+                $Bi(Iden).dewoodify(v)
+            }
+            fn dewoodify_all_into(v: &Wood, errors: &mut WoodErrors) -> Option<$Seq<T>> {
+                $Bi(Iden).dewoodify_all_into(v, errors)
+            }
+        }
+
+        $(#[$doc])*
+        #[derive(Copy, Clone)]
+        pub struct $Bi<SubTran>(pub SubTran);
+        impl<T, SubTran> Wooder<$Seq<T>> for $Bi<SubTran>
+        where
+            SubTran: Wooder<T>,
+            T: $($bounds)*,
+        {
+            fn woodify(&self, v: &$Seq<T>) -> Wood {
+                let mut ret = Vec::new();
+                woodify_seq_into(&self.0, v.iter(), &mut ret);
+                ret.into()
+            }
+        }
+        impl<T, SubTran> Dewooder<$Seq<T>> for $Bi<SubTran>
+        where
+            SubTran: Dewooder<T>,
+            T: $($bounds)*,
+        {
+            fn dewoodify(&self, v: &Wood) -> Result<$Seq<T>, Box<WoodError>> {
+                let mut ret = Vec::new();
+                dewoodify_seq_into(&self.0, v.contents(), &mut ret)?;
+                Ok(ret.into_iter().collect())
+            }
+            fn dewoodify_all_into(&self, v: &Wood, errors: &mut WoodErrors) -> Option<$Seq<T>> {
+                let mut ret = Vec::new();
+                dewoodify_seq_all_into(&self.0, v.contents(), &mut ret, errors)
+                    .then(|| ret.into_iter().collect())
+            }
+        }
+    };
+}
+sequence_impls!(
+    /// SequenceBi, for VecDeques
+    VecDequeBi, VecDeque, [Sized]
+);
+sequence_impls!(
+    /// Writes the elements in the set's iteration order, which for a HashSet changes from run to run
+    HashSetBi, HashSet, [Eq + Hash]
+);
+sequence_impls!(
+    /// Writes the elements in order
+    BTreeSetBi, BTreeSet, [Ord]
+);
+
+#[cfg(feature = "indexmap")]
+mod index_impls {
+    use super::*;
+    use indexmap::{IndexMap, IndexSet};
+
+    impl<K, V> Woodable for IndexMap<K, V>
+    where
+        K: Eq + Hash + Woodable,
+        V: Woodable,
+    {
+        fn woodify(&self) -> Wood {
+            IndexMapBi(Iden, Iden).woodify(self)
+        }
+    }
+    impl<K, V> Dewoodable for IndexMap<K, V>
+    where
+        K: Eq + Hash + Dewoodable,
+        V: Dewoodable,
+    {
+        fn dewoodify(v: &Wood) -> Result<IndexMap<K, V>, Box<WoodError>> {
+            IndexMapBi(Iden, Iden).dewoodify(v)
+        }
+        fn dewoodify_all_into(v: &Wood, errors: &mut WoodErrors) -> Option<IndexMap<K, V>> {
+            IndexMapBi(Iden, Iden).dewoodify_all_into(v, errors)
+        }
+    }
+
+    /// Writes the map as a branch of pairs, in insertion order, and reads them back in the same order
+    #[derive(Clone)]
+    pub struct IndexMapBi<KeyTran, ValTran>(pub KeyTran, pub ValTran);
+    impl<K, V, KeyTran, ValTran> Wooder<IndexMap<K, V>> for IndexMapBi<KeyTran, ValTran>
+    where
+        KeyTran: Wooder<K>,
+        ValTran: Wooder<V>,
+    {
+        fn woodify(&self, v: &IndexMap<K, V>) -> Wood {
+            let mut ret = Vec::new();
+            woodify_map(&self.0, &self.1, v.iter(), &mut ret);
+            ret.into()
+        }
+    }
+    impl<K, V, KeyTran, ValTran> Dewooder<IndexMap<K, V>> for IndexMapBi<KeyTran, ValTran>
+    where
+        KeyTran: Dewooder<K>,
+        ValTran: Dewooder<V>,
+        K: Eq + Hash,
+    {
+        fn dewoodify(&self, v: &Wood) -> Result<IndexMap<K, V>, Box<WoodError>> {
+            let mut ret = Vec::new();
+            dewoodify_map(&self.0, &self.1, v.contents(), &mut ret)?;
+            Ok(IndexMap::from_iter(ret))
+        }
+        fn dewoodify_all_into(&self, v: &Wood, errors: &mut WoodErrors) -> Option<IndexMap<K, V>> {
+            let mut ret = Vec::new();
+            dewoodify_map_all(&self.0, &self.1, v.contents(), &mut ret, errors)
+                .then(|| IndexMap::from_iter(ret))
+        }
+    }
+
+    sequence_impls!(
+        /// Writes the elements in insertion order
+        IndexSetBi, IndexSet, [Eq + Hash]
+    );
+}
+#[cfg(feature = "indexmap")]
+pub use self::index_impls::*;
+
+impl<T> Woodable for [T]
+where
+    T: Woodable,
+{
+    fn woodify(&self) -> Wood {
+        let mut ret = Vec::new();
+        woodify_seq_into(&Iden, self.iter(), &mut ret);
+        ret.into()
+    }
+}
+
+impl<T, const N: usize> Woodable for [T; N]
+where
+    T: Woodable,
+{
+    fn woodify(&self) -> Wood {
+        ArrayBi(Iden).woodify(self)
+    }
+}
+impl<T, const N: usize> Dewoodable for [T; N]
+where
+    T: Dewoodable,
+{
+    fn dewoodify(v: &Wood) -> Result<[T; N], Box<WoodError>> {
+        ArrayBi(Iden).dewoodify(v)
+    }
+    fn dewoodify_all_into(v: &Wood, errors: &mut WoodErrors) -> Option<[T; N]> {
+        ArrayBi(Iden).dewoodify_all_into(v, errors)
+    }
+}
+
+/// SequenceBi, for arrays, which must be read from exactly as many elements as they have
+#[derive(Copy, Clone)]
+pub struct ArrayBi<SubTran>(pub SubTran);
+impl<T, SubTran, const N: usize> Wooder<[T; N]> for ArrayBi<SubTran>
+where
+    SubTran: Wooder<T>,
+{
+    fn woodify(&self, v: &[T; N]) -> Wood {
+        let mut ret = Vec::with_capacity(N);
+        woodify_seq_into(&self.0, v.iter(), &mut ret);
+        ret.into()
+    }
+}
+impl<T, SubTran, const N: usize> Dewooder<[T; N]> for ArrayBi<SubTran>
+where
+    SubTran: Dewooder<T>,
+{
+    fn dewoodify(&self, v: &Wood) -> Result<[T; N], Box<WoodError>> {
+        let mut ret = Vec::with_capacity(N);
+        dewoodify_seq_into(&self.0, v.contents(), &mut ret)?;
+        array_of(v, ret)
+    }
+    fn dewoodify_all_into(&self, v: &Wood, errors: &mut WoodErrors) -> Option<[T; N]> {
+        let mut ret = Vec::with_capacity(N);
+        let all = dewoodify_seq_all_into(&self.0, v.contents(), &mut ret, errors);
+        if all {
+            errors.record(array_of(v, ret))
+        } else {
+            None
+        }
+    }
+}
+/// the elements read out of `v`, as an array, if there were the right number of them
+fn array_of<T, const N: usize>(v: &Wood, elements: Vec<T>) -> Result<[T; N], Box<WoodError>> {
+    let found = elements.len();
+    elements.try_into().map_err(|_| {
+        Box::new(
+            WoodError::new(
+                v,
+                format!(
+                    "expected an array of {} elements, but there are {}",
+                    N, found
+                ),
+            )
+            .with_kind(ErrorKind::ArityMismatch),
+        )
+    })
+}
+
+/// Writes `None` as `()`, and `Some(v)` as whatever the inner wooder writes `v` as, the same way `Option`'s own impls do
+#[derive(Copy, Clone)]
+pub struct OptionBi<SubTran>(pub SubTran);
+impl<T, SubTran> Wooder<Option<T>> for OptionBi<SubTran>
+where
+    SubTran: Wooder<T>,
+{
+    fn woodify(&self, v: &Option<T>) -> Wood {
+        match *v {
+            Some(ref v) => self.0.woodify(v),
+            None => Wood::branch(Vec::new()),
+        }
+    }
+}
+impl<T, SubTran> Dewooder<Option<T>> for OptionBi<SubTran>
+where
+    SubTran: Dewooder<T>,
+{
+    fn dewoodify(&self, v: &Wood) -> Result<Option<T>, Box<WoodError>> {
+        match *v {
+            Branchv(ref b) if b.v.is_empty() => Ok(None),
+            _ => self.0.dewoodify(v).map(Some),
+        }
+    }
+    fn dewoodify_all_into(&self, v: &Wood, errors: &mut WoodErrors) -> Option<Option<T>> {
+        match *v {
+            Branchv(ref b) if b.v.is_empty() => Some(None),
+            _ => self.0.dewoodify_all_into(v, errors).map(Some),
+        }
+    }
+}
+
+/// Pointers are written as whatever they point to
+macro_rules! pointer_impls {
+    ($Pointer:ident) => {
+        impl<T> Woodable for $Pointer<T>
+        where
+            T: Woodable + ?Sized,
+        {
+            fn woodify(&self) -> Wood {
+                (**self).woodify()
+            }
+        }
+        impl<T> Dewoodable for $Pointer<T>
+        where
+            T: Dewoodable,
+        {
+            fn dewoodify(v: &Wood) -> Result<$Pointer<T>, Box<WoodError>> {
+                T::dewoodify(v).map($Pointer::new)
+            }
+            fn dewoodify_all_into(v: &Wood, errors: &mut WoodErrors) -> Option<$Pointer<T>> {
+                T::dewoodify_all_into(v, errors).map($Pointer::new)
+            }
+        }
+    };
+}
+pointer_impls!(Box);
+pointer_impls!(Rc);
+pointer_impls!(Arc);
+
+/// For `Box`, `Rc`, `Arc`, or any other pointer that can be made from the value it points to. The pointer is written as whatever the inner wooder writes the value as.
+#[derive(Copy, Clone)]
+pub struct PointerBi<SubTran>(pub SubTran);
+impl<P, T, SubTran> Wooder<P> for PointerBi<SubTran>
+where
+    P: Deref<Target = T>,
+    SubTran: Wooder<T>,
+{
+    fn woodify(&self, v: &P) -> Wood {
+        self.0.woodify(v)
+    }
+}
+impl<P, T, SubTran> Dewooder<P> for PointerBi<SubTran>
+where
+    P: Deref<Target = T> + From<T>,
+    SubTran: Dewooder<T>,
+{
+    fn dewoodify(&self, v: &Wood) -> Result<P, Box<WoodError>> {
+        self.0.dewoodify(v).map(P::from)
+    }
+    fn dewoodify_all_into(&self, v: &Wood, errors: &mut WoodErrors) -> Option<P> {
+        self.0.dewoodify_all_into(v, errors).map(P::from)
+    }
+}
+
+/// Written as whatever the borrowed value is written as. Always read as `Cow::Owned`.
+impl<'a, T> Woodable for Cow<'a, T>
+where
+    T: Woodable + ToOwned + ?Sized,
+{
+    fn woodify(&self) -> Wood {
+        (**self).woodify()
+    }
+}
+impl<'a, T> Dewoodable for Cow<'a, T>
+where
+    T: ToOwned + ?Sized,
+    T::Owned: Dewoodable,
+{
+    fn dewoodify(v: &Wood) -> Result<Cow<'a, T>, Box<WoodError>> {
+        T::Owned::dewoodify(v).map(Cow::Owned)
+    }
+}
+
+/// For `Cow`s of sized, `Clone` types. (`Cow<str>` and `Cow<[T]>` don't need a wooder of their own, they can use Iden.) Always reads a `Cow::Owned`.
+#[derive(Copy, Clone)]
+pub struct CowBi<SubTran>(pub SubTran);
+impl<'a, T, SubTran> Wooder<Cow<'a, T>> for CowBi<SubTran>
+where
+    T: Clone,
+    SubTran: Wooder<T>,
+{
+    fn woodify(&self, v: &Cow<'a, T>) -> Wood {
+        self.0.woodify(&**v)
+    }
+}
+impl<'a, T, SubTran> Dewooder<Cow<'a, T>> for CowBi<SubTran>
+where
+    T: Clone,
+    SubTran: Dewooder<T>,
+{
+    fn dewoodify(&self, v: &Wood) -> Result<Cow<'a, T>, Box<WoodError>> {
+        self.0.dewoodify(v).map(Cow::Owned)
+    }
+}
+
+/// the elements of a tuple with `arity` elements
+fn tuple_elements(v: &Wood, arity: usize) -> Result<&[Wood], Box<WoodError>> {
+    match *v {
+        Branchv(ref b) if b.v.len() == arity => Ok(&b.v),
+        Branchv(ref b) => Err(Box::new(
+            WoodError::new(
+                v,
+                format!(
+                    "expected a tuple of {} elements, but the branch here has {}",
+                    arity,
+                    b.v.len()
+                ),
+            )
+            .with_kind(ErrorKind::ArityMismatch),
+        )),
+        Leafv(_) => Err(Box::new(
+            WoodError::new(
+                v,
+                format!(
+                    "expected a tuple of {} elements, but the wood here is a leaf",
+                    arity
+                ),
+            )
+            .with_kind(ErrorKind::UnexpectedLeaf),
+        )),
+    }
 }
 
+/// Writes a tuple as a branch of its elements, each with the wooder in the corresponding position, eg, `TupleBi((Iden, YesNo))` for an `(i32, bool)`
+#[derive(Copy, Clone)]
+pub struct TupleBi<SubTrans>(pub SubTrans);
+
+macro_rules! tuple_impls {
+    (@iden $T:ident) => {
+        Iden
+    };
+    ($arity:expr; $($T:ident $Tran:ident $i:tt),+) => {
+        impl<$($T),+> Woodable for ($($T,)+)
+        where
+            $($T: Woodable),+
+        {
+            fn woodify(&self) -> Wood {
+                Wood::branch(vec![$(self.$i.woodify()),+])
+            }
+        }
+        impl<$($T),+> Dewoodable for ($($T,)+)
+        where
+            $($T: Dewoodable),+
+        {
+            fn dewoodify(v: &Wood) -> Result<($($T,)+), Box<WoodError>> {
+                let li = tuple_elements(v, $arity)?;
+                Ok(($($T::dewoodify(&li[$i]).map_err(|e| e.within(PathSegment::Index($i)))?,)+))
+            }
+            fn dewoodify_all_into(v: &Wood, errors: &mut WoodErrors) -> Option<($($T,)+)> {
+                TupleBi(($(tuple_impls!(@iden $T),)+)).dewoodify_all_into(v, errors)
+            }
+        }
+        impl<$($T, $Tran),+> Wooder<($($T,)+)> for TupleBi<($($Tran,)+)>
+        where
+            $($Tran: Wooder<$T>),+
+        {
+            fn woodify(&self, v: &($($T,)+)) -> Wood {
+                Wood::branch(vec![$(self.0.$i.woodify(&v.$i)),+])
+            }
+        }
+        impl<$($T, $Tran),+> Dewooder<($($T,)+)> for TupleBi<($($Tran,)+)>
+        where
+            $($Tran: Dewooder<$T>),+
+        {
+            fn dewoodify(&self, v: &Wood) -> Result<($($T,)+), Box<WoodError>> {
+                let li = tuple_elements(v, $arity)?;
+                Ok(($(self.0.$i.dewoodify(&li[$i]).map_err(|e| e.within(PathSegment::Index($i)))?,)+))
+            }
+            fn dewoodify_all_into(&self, v: &Wood, errors: &mut WoodErrors) -> Option<($($T,)+)> {
+                let li = errors.record(tuple_elements(v, $arity))?;
+                let read = ($(errors.within(PathSegment::Index($i), |errors| {
+                    self.0.$i.dewoodify_all_into(&li[$i], errors)
+                }),)+);
+                Some(($(read.$i?,)+))
+            }
+        }
+    };
+}
+tuple_impls!(1; A TA 0);
+tuple_impls!(2; A TA 0, B TB 1);
+tuple_impls!(3; A TA 0, B TB 1, C TC 2);
+tuple_impls!(4; A TA 0, B TB 1, C TC 2, D TD 3);
+tuple_impls!(5; A TA 0, B TB 1, C TC 2, D TD 3, E TE 4);
+tuple_impls!(6; A TA 0, B TB 1, C TC 2, D TD 3, E TE 4, F TF 5);
+tuple_impls!(7; A TA 0, B TB 1, C TC 2, D TD 3, E TE 4, F TF 5, G TG 6);
+tuple_impls!(8; A TA 0, B TB 1, C TC 2, D TD 3, E TE 4, F TF 5, G TG 6, H TH 7);
+tuple_impls!(9; A TA 0, B TB 1, C TC 2, D TD 3, E TE 4, F TF 5, G TG 6, H TH 7, I TI 8);
+tuple_impls!(10; A TA 0, B TB 1, C TC 2, D TD 3, E TE 4, F TF 5, G TG 6, H TH 7, I TI 8, J TJ 9);
+tuple_impls!(11; A TA 0, B TB 1, C TC 2, D TD 3, E TE 4, F TF 5, G TG 6, H TH 7, I TI 8, J TJ 9, K TK 10);
+tuple_impls!(12; A TA 0, B TB 1, C TC 2, D TD 3, E TE 4, F TF 5, G TG 6, H TH 7, I TI 8, J TJ 9, K TK 10, L TL 11);
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[cfg(feature = "indexmap")]
+    #[test]
+    fn index_map_keeps_insertion_order() {
+        use indexmap::{IndexMap, IndexSet};
+        let mut m = IndexMap::new();
+        m.insert("z".to_string(), 1u32);
+        m.insert("a".to_string(), 2);
+        assert_eq!("((z 1) (a 2))", serialize(&m));
+        let back: IndexMap<String, u32> =
+            dewoodify(&parse_woodslist("(z 1) (a 2)").unwrap()).unwrap();
+        assert_eq!(vec!["z", "a"], back.keys().collect::<Vec<_>>());
+        let set: IndexSet<u32> = [3, 1, 2].into_iter().collect();
+        assert_eq!("(3 1 2)", serialize(&set));
+    }
+
     #[test]
     fn idempotent_int() {
         assert!(90isize == Iden.dewoodify(&Iden.woodify(&90isize)).unwrap());
@@ -492,4 +1217,180 @@ mod tests {
 	fn yesno() {
 		assert_eq!("yes", YesNo.woodify(&true).initial_str());
 	}
+
+    /// checks that v survives being printed and parsed back, and that it's printed as `text`
+    fn round_trip<T>(v: T, text: &str)
+    where
+        T: Woodable + Dewoodable + PartialEq + std::fmt::Debug,
+    {
+        let printed = serialize(&v);
+        assert_eq!(text, printed);
+        assert_eq!(v, deserialize::<T>(&printed).unwrap());
+    }
+
+    #[test]
+    fn std_types() {
+        use std::net::{IpAddr, SocketAddr};
+        use std::num::NonZeroU16;
+        use std::path::PathBuf;
+        use std::time::Duration;
+
+        round_trip((-3i8, 200u8, -300i16, 60000u16), "(-3 200 -300 60000)");
+        round_trip(NonZeroU16::new(5).unwrap(), "5");
+        assert!(deserialize::<NonZeroU16>("0").is_err());
+        round_trip((), "()");
+        round_trip(Box::new(1u32), "1");
+        round_trip(Rc::new("a".to_string()), "a");
+        round_trip(Arc::new(vec![true]), "(true)");
+        round_trip(Cow::<str>::Owned("b".into()), "b");
+        round_trip(("a".to_string(), (1u32,), [1u32, 2, 3]), "(a (1) (1 2 3))");
+        assert!(deserialize::<[u32; 2]>("1 2 3").is_err());
+        assert!(deserialize::<(u32, u32)>("(1 2 3)").is_err());
+        round_trip(VecDeque::from(vec![1u32, 2]), "(1 2)");
+        round_trip(BTreeSet::from([3u32, 1, 2]), "(1 2 3)");
+        round_trip(HashSet::from([1u32]), "(1)");
+        round_trip(
+            BTreeMap::from([("b".to_string(), 2u32), ("a".to_string(), 1)]),
+            "((a 1) (b 2))",
+        );
+        round_trip(PathBuf::from("dir/file.term"), "dir/file.term");
+        round_trip(Duration::new(1, 500_000_000), "1.5");
+        round_trip(Duration::new(0, 1), "0.000000001");
+        round_trip(Duration::from_secs(60), "60");
+        assert!(deserialize::<Duration>("1.").is_ok());
+        assert!(deserialize::<Duration>("-1").is_err());
+        assert!(deserialize::<Duration>("0.0000000001").is_err());
+        let ip: IpAddr = "::1".parse().unwrap();
+        assert_eq!("::1", ip.woodify().initial_str());
+        assert_eq!(ip, IpAddr::dewoodify(&ip.woodify()).unwrap());
+        let socket: SocketAddr = "10.0.0.1:80".parse().unwrap();
+        assert_eq!(socket, SocketAddr::dewoodify(&socket.woodify()).unwrap());
+    }
+
+    #[test]
+    fn std_combinators() {
+        let tran = TupleBi((YesNo, OptionBi(YesNo), ArrayBi(YesNo)));
+        let v = (true, None, [false, true]);
+        let w = tran.woodify(&v);
+        assert_eq!("(yes () (no yes))", w.to_string());
+        assert_eq!(v, tran.dewoodify(&w).unwrap());
+
+        let tran = BTreeMapBi(Iden, BTreeSetBi(YesNo));
+        let m = BTreeMap::from([(1u32, BTreeSet::from([true]))]);
+        assert_eq!(m, tran.dewoodify(&tran.woodify(&m)).unwrap());
+
+        let tran = PointerBi(SequenceBi(YesNo));
+        let b: Rc<Vec<bool>> = Rc::new(vec![true, false]);
+        assert_eq!("(yes no)", tran.woodify(&b).to_string());
+        let back: Rc<Vec<bool>> = tran.dewoodify(&tran.woodify(&b)).unwrap();
+        assert_eq!(b, back);
+
+        let m: HashMap<String, u32> = (0..20).map(|i| (format!("k{:02}", i), i)).collect();
+        let text = SortedHashMapBi(Iden, Iden).woodify(&m).to_string();
+        assert!(text.starts_with("((k00 0) (k01 1) (k02 2)") && text.ends_with("(k19 19))"));
+        let back: HashMap<String, u32> = SortedHashMapBi(Iden, Iden)
+            .dewoodify(&parse_woodslist(&text).unwrap())
+            .unwrap();
+        assert_eq!(m, back);
+        let set: HashSet<u32> = (0..20).rev().collect();
+        let w = SortedHashSetBi(Iden).woodify(&set);
+        assert_eq!(
+            (0..20).map(|i| i.to_string()).collect::<Vec<_>>().join(" "),
+            w.to_string().trim_matches(&['(', ')'][..])
+        );
+
+        //values no longer need to be hashable
+        let m: HashMap<String, Vec<f64>> = deserialize("a:(1.5 2) b:()").unwrap();
+        assert_eq!((vec![1.5, 2.0], vec![]), (m["a"].clone(), m["b"].clone()));
+    }
+
+    /// the errors an accumulating read found
+    fn all_errors<T: Dewoodable>(text: &str) -> Vec<String> {
+        match dewoodify_all::<T>(&parse_woodslist(text).unwrap()) {
+            Ok(_) => Vec::new(),
+            Err(es) => es.iter().map(|e| e.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn accumulating_reads_find_every_error() {
+        assert_eq!(
+            vec![
+                "1:4: [1]: couldn't parse u32",
+                "1:8: [3]: couldn't parse u32"
+            ],
+            all_errors::<Vec<u32>>("(1 a 2 b)")
+        );
+        assert_eq!(
+            vec![
+                "1:17: 2[0]: expected a bool here",
+                "1:24: couldn't parse u32"
+            ],
+            all_errors::<BTreeMap<u32, Vec<bool>>>("((1 (true)) (2 (nah)) (x (false)))")
+        );
+        assert_eq!(
+            vec![
+                "1:2: [0]: couldn't parse u32",
+                "1:6: [2]: expected an empty branch, ()"
+            ],
+            all_errors::<(u32, String, ())>("(x y z)")
+        );
+        assert_eq!(
+            vec!["1:1: expected an array of 2 elements, but there are 3"],
+            all_errors::<[u32; 2]>("(1 2 3)")
+        );
+        assert!(all_errors::<HashMap<String, Option<Box<u32>>>>("((a 1) (b ()))").is_empty());
+
+        let tran = TaggedSequenceBi("flags", SequenceBi(YesNo));
+        let w = parse_woodslist("(flags (yes) (yes maybe) (perhaps))").unwrap();
+        let es = tran.dewoodify_all(&w).unwrap_err();
+        let paths: Vec<Vec<PathSegment>> = es.iter().map(|e| e.path.clone()).collect();
+        use PathSegment::Index;
+        assert_eq!(
+            vec![vec![Index(1), Index(1)], vec![Index(2), Index(0)]],
+            paths
+        );
+    }
+
+    #[test]
+    fn errors_know_their_kind_and_path() {
+        let e = deserialize::<Vec<(u32, bool)>>("(1 true) (2 maybe)").unwrap_err();
+        assert_eq!(ErrorKind::ParseScalar, e.kind);
+        assert_eq!("[1][1]", e.path_string());
+
+        let w = woods!(woods!("a", woods!("1", "x")));
+        let e = dewoodify::<HashMap<String, Vec<u32>>>(&w).unwrap_err();
+        assert_eq!("a[1]: couldn't parse u32", e.to_string());
+
+        let kind = |text: &str| {
+            deserialize::<BTreeMap<String, [u32; 2]>>(text)
+                .unwrap_err()
+                .kind
+        };
+        assert_eq!(ErrorKind::ArityMismatch, kind("a:(1 2 3) b:(1 2)"));
+        assert_eq!(ErrorKind::UnexpectedLeaf, kind("a b"));
+        assert_eq!(
+            ErrorKind::UnexpectedBranch,
+            String::dewoodify(&woods!()).unwrap_err().kind
+        );
+        assert_eq!(ErrorKind::Syntax, parse_termpose("a)").unwrap_err().kind);
+
+        let tran = TaggedSequenceBi("flags", YesNo);
+        let e = tran.dewoodify(&woods!("nags", "yes")).unwrap_err();
+        assert_eq!(ErrorKind::UnknownVariant, e.kind);
+
+        let w = parse_termpose("server host:a").unwrap();
+        let e = FieldScanning::new(&w).find("port").unwrap_err();
+        assert_eq!(
+            (ErrorKind::MissingKey, "port".into()),
+            (e.kind, e.path_string())
+        );
+        let e = FieldScanning::new(&w)
+            .deny_unknown_keys(&["port"], "server")
+            .unwrap_err();
+        assert_eq!(
+            (ErrorKind::UnknownKey, "host".into()),
+            (e.kind, e.path_string())
+        );
+    }
 }
@@ -0,0 +1,77 @@
+extern crate wood;
+extern crate wood_derive;
+use wood::{parse_termpose, pretty_termpose, Woodable, Dewoodable};
+use wood_derive::{Woodable, Dewoodable};
+
+#[derive(Woodable, Dewoodable, PartialEq, Debug)]
+#[wood(rename_all = "kebab-case")]
+enum Request {
+	GetHTTPHeaders,
+	#[wood(rename = "put", alias = "upload", alias = "store")]
+	PutFile{ file_name: String },
+	DeleteAll(u32),
+	#[wood(other)]
+	Unknown,
+}
+
+#[derive(Woodable, Dewoodable, PartialEq, Debug)]
+#[wood(rename_all = "camelCase")]
+struct Settings {
+	max_size: u32,
+	use_tls: bool,
+}
+
+#[derive(Woodable, Dewoodable, PartialEq, Debug)]
+#[wood(untagged)]
+enum Value {
+	Nothing,
+	Point(i32, i32),
+	Named{ name: String, value: i32 },
+	Number(i64),
+	Text(String),
+}
+
+#[derive(Woodable, Dewoodable, PartialEq, Debug)]
+#[wood(untagged, rename_all = "snake_case")]
+enum Flag {
+	AllOn,
+	AllOff,
+	#[wood(other)]
+	Mixed,
+}
+
+fn main(){
+	let read = |s:&str| parse_termpose(s).unwrap();
+	
+	for (r, s) in [
+		(Request::GetHTTPHeaders, "get-http-headers"),
+		(Request::PutFile{ file_name:"a".into() }, "put file_name:a"),
+		(Request::DeleteAll(3), "delete-all 3"),
+	] {
+		assert_eq!(s, &pretty_termpose(&r.woodify()));
+		assert_eq!(r, Request::dewoodify(&read(s)).unwrap());
+	}
+	assert_eq!(Request::PutFile{ file_name:"b".into() }, Request::dewoodify(&read("store file_name:b")).unwrap());
+	assert_eq!(Request::Unknown, Request::dewoodify(&read("patch 1 2")).unwrap());
+	
+	let s = Settings{ max_size:5, use_tls:true };
+	assert_eq!("Settings maxSize:5 useTls:true", &pretty_termpose(&s.woodify()));
+	assert_eq!(s, Settings::dewoodify(&read("Settings useTls:true maxSize:5")).unwrap());
+	
+	for (v, s) in [
+		(Value::Nothing, "Nothing"),
+		(Value::Point(1, -2), "(1 -2)"),
+		(Value::Named{ name:"n".into(), value:7 }, "(name:n value:7)"),
+		(Value::Number(9), "9"),
+		(Value::Text("hi".into()), "hi"),
+	] {
+		let w = v.woodify();
+		assert_eq!(w, read(s));
+		assert_eq!(v, Value::dewoodify(&w).unwrap());
+	}
+	assert!(Value::dewoodify(&read("(a b c)")).is_err());
+	
+	assert_eq!("all_on", &pretty_termpose(&Flag::AllOn.woodify()));
+	assert_eq!(Flag::AllOff, Flag::dewoodify(&read("all_off")).unwrap());
+	assert_eq!(Flag::Mixed, Flag::dewoodify(&read("some")).unwrap());
+}
@@ -0,0 +1,43 @@
+extern crate wood;
+extern crate wood_derive;
+use wood::{parse_termpose, pretty_termpose, dewoodify_all, woods, ErrorKind, Woodable, Dewoodable};
+use wood_derive::{Woodable, Dewoodable};
+
+#[derive(Woodable, Dewoodable, PartialEq, Debug)]
+struct Server {
+	host: String,
+	port: u16,
+}
+
+#[derive(Woodable, Dewoodable, PartialEq, Debug)]
+struct Config {
+	name: String,
+	servers: Vec<Server>,
+}
+
+fn main(){
+	let c = Config{ name:"main".into(), servers:vec!(Server{ host:"a".into(), port:80 }, Server{ host:"b".into(), port:81 }) };
+	let text = pretty_termpose(&c.woodify());
+	assert_eq!(c, dewoodify_all(&parse_termpose(&text).unwrap()).unwrap());
+	
+	//dewoodify would stop at the first of these, dewoodify_all reports all of them, with the path to each
+	let w = parse_termpose("Config servers:((Server host:a port:eighty) (Server host:b port:81) (Server port:-1))").unwrap();
+	let errors = dewoodify_all::<Config>(&w).unwrap_err();
+	let paths:Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+	assert_eq!(vec!(
+		"1:1: name: could not find key \"name\"",
+		"1:37: servers[0].port: couldn't parse u16",
+		"1:69: servers[2].host: could not find key \"host\"",
+		"1:82: servers[2].port: couldn't parse u16",
+	), paths);
+	
+	//while dewoodify gives up at the first, but it still says where it was, and what kind of error it is
+	let e = Config::dewoodify(&w).unwrap_err();
+	assert_eq!((ErrorKind::MissingKey, "name".to_string()), (e.kind, e.path_string()));
+	
+	//woods made in code have no line numbers, the path is all there is to go on
+	let w = woods!("Config", woods!("name", "b"), woods!("servers", woods!(woods!("Server", woods!("host", "a"), woods!("port", "x")))));
+	let e = Config::dewoodify(&w).unwrap_err();
+	assert_eq!(ErrorKind::ParseScalar, e.kind);
+	assert_eq!("servers[0].port: couldn't parse u16", &e.to_string());
+}
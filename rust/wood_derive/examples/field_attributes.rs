@@ -0,0 +1,56 @@
+extern crate wood;
+extern crate wood_derive;
+use wood::{parse_termpose, pretty_termpose, wooder, Woodable, Dewoodable};
+use wood_derive::{Woodable, Dewoodable};
+
+fn default_port()-> u32 { 80 }
+
+#[derive(Woodable, Dewoodable, PartialEq, Debug)]
+struct Position {
+	x: i32,
+	y: i32,
+}
+
+#[derive(Woodable, Dewoodable, PartialEq, Debug)]
+struct Server {
+	#[wood(rename = "host-name")]
+	name: String,
+	#[wood(default = "default_port")]
+	port: u32,
+	#[wood(default)]
+	aliases: Vec<String>,
+	#[wood(skip)]
+	connections: usize,
+	#[wood(flatten)]
+	position: Position,
+	#[wood(with = "wooder::YesNo")]
+	public: bool,
+	r#type: String,
+}
+
+#[derive(Woodable, Dewoodable, PartialEq, Debug)]
+enum Shape {
+	Circle{ #[wood(rename = "r")] radius: u32, #[wood(default)] filled: bool },
+	Polygon(u32, #[wood(skip)] Vec<u32>, #[wood(default)] bool),
+}
+
+fn main(){
+	let s = Server{ name:"a".into(), port:8080, aliases:vec!(), connections:3, position:Position{ x:1, y:2 }, public:true, r#type:"mirror".into() };
+	let text = pretty_termpose(&s.woodify());
+	assert_eq!("Server host-name:a port:8080 aliases:() x:1 y:2 public:yes type:mirror", &text);
+	let back = Server::dewoodify(&parse_termpose(&text).unwrap()).unwrap();
+	assert_eq!(Server{ connections:0, ..s }, back);
+
+	//files written before the port and aliases existed still read
+	let old = Server::dewoodify(&parse_termpose("Server host-name:b y:0 x:0 public:no type:origin").unwrap()).unwrap();
+	assert_eq!((80, vec!()), (old.port, old.aliases));
+
+	assert!(Server::dewoodify(&parse_termpose("Server name:b y:0 x:0 public:no type:origin").unwrap()).is_err());
+
+	let c = Shape::Circle{ radius:2, filled:false };
+	assert_eq!("Circle r:2 filled:false", &pretty_termpose(&c.woodify()));
+	assert_eq!(c, Shape::dewoodify(&parse_termpose("Circle r:2").unwrap()).unwrap());
+	let p = Shape::Polygon(5, vec!(1), true);
+	assert_eq!("Polygon 5 true", &pretty_termpose(&p.woodify()));
+	assert_eq!(Shape::Polygon(5, vec!(), false), Shape::dewoodify(&parse_termpose("Polygon 5").unwrap()).unwrap());
+}
@@ -0,0 +1,55 @@
+extern crate wood;
+extern crate wood_derive;
+use wood::{parse_termpose, pretty_termpose, Wood, Woodable, Dewoodable};
+use wood_derive::{Woodable, Dewoodable};
+
+#[derive(Woodable, Dewoodable, PartialEq, Debug)]
+struct Wrapper<T> {
+	inner: T,
+	others: Vec<T>,
+}
+
+#[derive(Woodable, Dewoodable, PartialEq, Debug)]
+enum Either<L, R> where L: Clone {
+	Left(L),
+	Right{ right: R },
+}
+
+struct Name<'a>(&'a str);
+impl<'a> Woodable for Name<'a> {
+	fn woodify(&self)-> Wood { self.0.into() }
+}
+
+#[derive(Woodable)]
+struct Greeting<'a, T> {
+	name: Name<'a>,
+	extra: T,
+}
+
+trait Units { type Amount; }
+struct Metres;
+impl Units for Metres { type Amount = f64; }
+
+//Metres itself isn't Woodable, the field's type is
+#[derive(Woodable, Dewoodable)]
+#[wood(bound = "U::Amount: Woodable + Dewoodable")]
+struct Measure<U: Units> {
+	amount: U::Amount,
+}
+
+fn main(){
+	let w = Wrapper{ inner:1u32, others:vec!(2, 3) };
+	let s = pretty_termpose(&w.woodify());
+	assert_eq!("Wrapper inner:1 others:2:3", &s);
+	assert_eq!(w, Wrapper::dewoodify(&parse_termpose(&s).unwrap()).unwrap());
+
+	for e in [Either::Left("l".to_string()), Either::Right{ right:Wrapper{ inner:true, others:vec!() } }] {
+		assert_eq!(e, Either::dewoodify(&e.woodify()).unwrap());
+	}
+
+	let g = Greeting{ name:Name("you"), extra:vec!('a') };
+	assert_eq!("Greeting name:you extra:(a)", &pretty_termpose(&g.woodify()));
+
+	let m = Measure::<Metres>{ amount:2.5 };
+	assert_eq!(2.5, Measure::<Metres>::dewoodify(&m.woodify()).unwrap().amount);
+}
@@ -0,0 +1,42 @@
+extern crate wood;
+extern crate wood_derive;
+use wood::{parse_termpose, pretty_termpose, Woodable, Dewoodable};
+use wood_derive::{Woodable, Dewoodable};
+
+#[derive(Woodable, Dewoodable, PartialEq, Debug)]
+struct Config {
+	name: String,
+	proxy: Option<String>,
+	retries: Option<u32>,
+}
+
+#[derive(Woodable, Dewoodable, PartialEq, Debug)]
+#[wood(deny_unknown_fields)]
+struct Strict {
+	a: u32,
+	b: Option<u32>,
+}
+
+#[derive(Woodable, Dewoodable, PartialEq, Debug)]
+struct Empty {}
+
+fn main(){
+	let c = Config{ name:"c".into(), proxy:None, retries:Some(3) };
+	let text = pretty_termpose(&c.woodify());
+	assert_eq!("Config name:c retries:3", &text);
+	assert_eq!(c, Config::dewoodify(&parse_termpose(&text).unwrap()).unwrap());
+
+	//unknown keys are fine unless they're denied
+	let read = Config::dewoodify(&parse_termpose("Config name:d colour:blue proxy:p").unwrap()).unwrap();
+	assert_eq!(Config{ name:"d".into(), proxy:Some("p".into()), retries:None }, read);
+
+	assert_eq!(Strict{ a:1, b:None }, Strict::dewoodify(&parse_termpose("Strict a:1").unwrap()).unwrap());
+	let e = Strict::dewoodify(&parse_termpose("Strict a:1 c:2 d:3").unwrap()).unwrap_err();
+	assert_eq!((1, 12), (e.line, e.column));
+	assert_eq!("Strict has no field called \"c\" (nor \"d\"). Its fields are: a, b", &e.msg);
+
+	assert_eq!(Empty{}, Empty::dewoodify(&Empty{}.woodify()).unwrap());
+
+	let v:Vec<Option<u32>> = vec!(Some(1), None);
+	assert_eq!(v, Vec::<Option<u32>>::dewoodify(&parse_termpose(&pretty_termpose(&v.woodify())).unwrap()).unwrap());
+}
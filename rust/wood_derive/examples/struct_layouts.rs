@@ -0,0 +1,66 @@
+extern crate wood;
+extern crate wood_derive;
+use wood::{parse_termpose, pretty_termpose, wooder, Woodable, Dewoodable};
+use wood_derive::{Woodable, Dewoodable};
+
+#[derive(Woodable, Dewoodable, PartialEq, Debug)]
+#[wood(positional)]
+struct Colour {
+	r: u32,
+	g: u32,
+	b: u32,
+	a: Option<u32>,
+}
+
+#[derive(Woodable, Dewoodable, PartialEq, Debug)]
+#[wood(positional, untagged)]
+struct Point {
+	x: i32,
+	y: i32,
+}
+
+#[derive(Woodable, Dewoodable, PartialEq, Debug)]
+#[wood(untagged)]
+struct Line {
+	from: Point,
+	to: Point,
+}
+
+#[derive(Woodable, Dewoodable, PartialEq, Debug)]
+#[wood(transparent)]
+struct Metres(f64);
+
+#[derive(Woodable, Dewoodable, PartialEq, Debug)]
+#[wood(transparent)]
+struct Visible {
+	#[wood(with = "wooder::YesNo")]
+	on: bool,
+	#[wood(skip)]
+	changed: bool,
+}
+
+#[derive(Woodable, Dewoodable, PartialEq, Debug)]
+struct Scene {
+	background: Colour,
+	length: Metres,
+	visible: Visible,
+	line: Line,
+}
+
+fn main(){
+	let s = Scene{
+		background: Colour{ r:1, g:2, b:3, a:None },
+		length: Metres(2.5),
+		visible: Visible{ on:true, changed:false },
+		line: Line{ from:Point{ x:0, y:0 }, to:Point{ x:4, y:-1 } },
+	};
+	let w = s.woodify();
+	assert_eq!(parse_termpose("Scene background:(Colour 1 2 3 ()) length:2.5 visible:yes line:(from:(0 0) to:(4 -1))").unwrap(), w);
+	assert_eq!(s, Scene::dewoodify(&parse_termpose(&pretty_termpose(&w)).unwrap()).unwrap());
+	
+	//the trailing fields of a positional struct can be left off if they have defaults
+	assert_eq!(Colour{ r:9, g:9, b:9, a:None }, Colour::dewoodify(&parse_termpose("Colour 9 9 9").unwrap()).unwrap());
+	assert_eq!(Some(128), Colour::dewoodify(&parse_termpose("Colour 9 9 9 128").unwrap()).unwrap().a);
+	assert!(Colour::dewoodify(&parse_termpose("Colour 9 9").unwrap()).is_err());
+	assert!(Point::dewoodify(&parse_termpose("1").unwrap()).is_err());
+}
@@ -3,12 +3,496 @@
 
 extern crate proc_macro;
 extern crate proc_macro2;
-extern crate wood;
 
 use proc_macro::TokenStream;
 use proc_macro2::{Span, TokenStream as PM2TS};
 use quote::quote;
-use syn::{Ident, Data::{Struct, Enum, Union}, Fields::{Named, Unnamed, Unit}};
+use syn::{Ident, Lit, Meta, NestedMeta, Data::{Struct, Enum, Union}, Fields::{Named, Unnamed, Unit}};
+
+
+///the contents of all of the `#[wood(...)]` attributes in `attrs`
+fn wood_attrs(attrs:&[syn::Attribute])-> Vec<NestedMeta> {
+	attrs.iter().filter(|a| a.path.segments.len() == 1 && a.path.segments[0].ident == "wood").flat_map(|a|{
+		match a.parse_meta() {
+			Ok(Meta::List(l))=> l.nested.into_iter(),
+			_=> panic!("wood attributes should look like #[wood(...)]"),
+		}
+	}).collect()
+}
+
+fn lit_str(lit:&Lit)-> String {
+	match *lit {
+		Lit::Str(ref s)=> s.value(),
+		_=> panic!("expected a string literal in the wood attribute, found {}", quote!{#lit}),
+	}
+}
+
+///what the `#[wood(...)]` attributes on the type itself ask for
+#[derive(Default)]
+struct ContainerAttrs {
+	///where predicates to use instead of the ones that'd be inferred
+	bound: Option<String>,
+	///whether to reject woods with keys that aren't any field's
+	deny_unknown_fields: bool,
+	///the case to convert the names of an enum's variants, or a struct's fields, to
+	rename_all: Option<String>,
+	///whether a struct is written without its name, or an enum's variants are written without theirs, to be told apart by trying each in order
+	untagged: bool,
+	///whether a struct's named fields are written by position, without their keys
+	positional: bool,
+	///whether a struct with one field is written as just that field
+	transparent: bool,
+	///the path to the wood crate, for when it's only available as a re-export
+	krate: Option<syn::Path>,
+}
+impl ContainerAttrs {
+	fn of(ast:&syn::DeriveInput)-> Self {
+		let mut ret = Self::default();
+		for m in wood_attrs(&ast.attrs) {
+			match m {
+				NestedMeta::Meta(Meta::NameValue(ref nv)) if nv.ident == "bound" => { ret.bound = Some(lit_str(&nv.lit)); }
+				NestedMeta::Meta(Meta::Word(ref w)) if w == "deny_unknown_fields" => { ret.deny_unknown_fields = true; }
+				NestedMeta::Meta(Meta::NameValue(ref nv)) if nv.ident == "rename_all" => {
+					let case = lit_str(&nv.lit);
+					rename_case("", &case); //checks that it's a case we know
+					ret.rename_all = Some(case);
+				}
+				NestedMeta::Meta(Meta::Word(ref w)) if w == "untagged" => { ret.untagged = true; }
+				NestedMeta::Meta(Meta::Word(ref w)) if w == "positional" => { ret.positional = true; }
+				NestedMeta::Meta(Meta::Word(ref w)) if w == "transparent" => { ret.transparent = true; }
+				NestedMeta::Meta(Meta::NameValue(ref nv)) if nv.ident == "crate" => {
+					let path = lit_str(&nv.lit);
+					ret.krate = Some(syn::parse_str(&path).unwrap_or_else(|e| panic!("couldn't parse the wood crate path \"{}\": {}", path, e)));
+				}
+				_=> panic!("unrecognised wood attribute on {}: {}", ast.ident, quote!{#m}),
+			}
+		}
+		if let Struct(_) = ast.data {}else if ret.positional || ret.transparent {
+			panic!("positional and transparent are for structs, {} isn't one", ast.ident);
+		}
+		if ret.deny_unknown_fields && (ret.positional || ret.transparent) {
+			panic!("{} has no keys to check, deny_unknown_fields can't be used along with positional or transparent", ast.ident);
+		}
+		//a transparent struct is written as if it were positional and untagged, which for one field is just that field
+		if ret.transparent {
+			ret.positional = true;
+			ret.untagged = true;
+		}
+		ret
+	}
+	///wraps the generated impl in a scope where `_wood` is the wood crate, so that the generated code never depends on what the user has imported, nor on what they've called their own things
+	fn in_wood_scope(&self, generated:PM2TS)-> PM2TS {
+		let krate = match self.krate {
+			Some(ref k)=> quote!{ #k },
+			None=> quote!{ ::wood },
+		};
+		quote!{
+			const _: () = {
+				use #krate as _wood;
+				#generated
+			};
+		}
+	}
+	///the fields of a struct, with their attributes. Positional fields have no keys.
+	fn struct_fields<'a>(&self, ast:&'a syn::DeriveInput)-> Vec<(&'a syn::Field, FieldAttrs)> {
+		let s = match ast.data { Struct(ref s)=> s, _=> unreachable!() };
+		let mut ret = fields_of(&s.fields, self.rename_all.as_deref());
+		if self.positional {
+			for (_, a) in ret.iter_mut() {
+				if a.flatten { panic!("{} is positional, so it can't have flattened fields, they'd have no keys to find their own fields by", ast.ident); }
+				a.key = None;
+			}
+		}
+		if self.transparent && ret.iter().filter(|(_, a)| !a.skip).count() != 1 {
+			panic!("transparent structs are written as their one field, but {} doesn't have exactly one field that isn't skipped", ast.ident);
+		}
+		ret
+	}
+}
+
+///splits an identifier, in PascalCase or in snake_case, into its words
+fn words(id:&str)-> Vec<String> {
+	let cs:Vec<char> = id.chars().collect();
+	let mut ret = Vec::new();
+	let mut cur = String::new();
+	for (i, &c) in cs.iter().enumerate() {
+		if c == '_' {
+			if !cur.is_empty() { ret.push(std::mem::take(&mut cur)); }
+			continue;
+		}
+		if c.is_uppercase() && !cur.is_empty() {
+			let prev = cs[i - 1];
+			//the end of an acronym is where an uppercase letter is followed by a lowercase one
+			let next_is_lower = cs.get(i + 1).is_some_and(|n| n.is_lowercase());
+			if !prev.is_uppercase() || next_is_lower { ret.push(std::mem::take(&mut cur)); }
+		}
+		cur.push(c);
+	}
+	if !cur.is_empty() { ret.push(cur); }
+	ret
+}
+
+///converts an identifier to one of the cases `rename_all` accepts
+fn rename_case(id:&str, case:&str)-> String {
+	let ws = words(id);
+	let lower = || ws.iter().map(|w| w.to_lowercase());
+	let upper = || ws.iter().map(|w| w.to_uppercase());
+	let capitalized = |w:&String|-> String {
+		let mut cs = w.chars();
+		match cs.next() {
+			Some(f)=> f.to_uppercase().chain(cs.flat_map(|c| c.to_lowercase())).collect(),
+			None=> String::new(),
+		}
+	};
+	match case {
+		"lowercase"=> lower().collect(),
+		"UPPERCASE"=> upper().collect(),
+		"PascalCase"=> ws.iter().map(capitalized).collect(),
+		"camelCase"=> lower().take(1).chain(ws.iter().skip(1).map(capitalized)).collect(),
+		"snake_case"=> lower().collect::<Vec<_>>().join("_"),
+		"SCREAMING_SNAKE_CASE"=> upper().collect::<Vec<_>>().join("_"),
+		"kebab-case"=> lower().collect::<Vec<_>>().join("-"),
+		"SCREAMING-KEBAB-CASE"=> upper().collect::<Vec<_>>().join("-"),
+		_=> panic!("unknown rename_all case \"{}\", the cases are lowercase, UPPERCASE, PascalCase, camelCase, snake_case, SCREAMING_SNAKE_CASE, kebab-case and SCREAMING-KEBAB-CASE", case),
+	}
+}
+
+///the name something is written under, given its identifier, any rename it has, and the container's rename_all
+fn written_name(id:&Ident, rename:Option<String>, case:Option<&str>)-> String {
+	rename.unwrap_or_else(||{
+		let id = id.to_string();
+		let id = id.trim_start_matches("r#");
+		match case {
+			Some(c)=> rename_case(id, c),
+			None=> id.to_string(),
+		}
+	})
+}
+
+///what the `#[wood(...)]` attributes on an enum variant ask for
+struct VariantAttrs {
+	///what the variant is called in the wood
+	name: String,
+	///other names to accept when reading
+	aliases: Vec<String>,
+	///whether this is the variant to use for unknown tags
+	other: bool,
+}
+impl VariantAttrs {
+	fn of(v:&syn::Variant, container:&ContainerAttrs)-> Self {
+		let mut rename = None;
+		let mut aliases = Vec::new();
+		let mut other = false;
+		for m in wood_attrs(&v.attrs) {
+			match m {
+				NestedMeta::Meta(Meta::NameValue(ref nv)) if nv.ident == "rename" => { rename = Some(lit_str(&nv.lit)); }
+				NestedMeta::Meta(Meta::NameValue(ref nv)) if nv.ident == "alias" => { aliases.push(lit_str(&nv.lit)); }
+				NestedMeta::Meta(Meta::Word(ref w)) if w == "other" => {
+					if let Unit = v.fields {}else{ panic!("the other variant, {}, must be a unit variant, it'll be used for any tag that's not recognised", v.ident); }
+					other = true;
+				}
+				_=> panic!("unrecognised wood attribute on variant {}: {}", v.ident, quote!{#m}),
+			}
+		}
+		VariantAttrs{ name:written_name(&v.ident, rename, container.rename_all.as_deref()), aliases, other }
+	}
+	///a pattern matching the variant's name and aliases
+	fn pattern(&self)-> PM2TS {
+		let names = std::iter::once(&self.name).chain(self.aliases.iter());
+		quote!{ #(#names)|* }
+	}
+}
+
+///the type's generics, with a `P: #trait_path` bound added for each type parameter P, or, if there's a `#[wood(bound = "...")]`, with those predicates added instead
+fn generics_with_bounds(ast:&syn::DeriveInput, attrs:&ContainerAttrs, trait_path:PM2TS)-> syn::Generics {
+	let mut generics = ast.generics.clone();
+	let predicates:Vec<syn::WherePredicate> = match attrs.bound {
+		Some(ref b)=> {
+			if b.trim().is_empty() {
+				vec!()
+			}else{
+				let wc:syn::WhereClause = syn::parse_str(&format!("where {}", b)).unwrap_or_else(|e| panic!("couldn't parse the wood bound \"{}\": {}", b, e));
+				wc.predicates.into_iter().collect()
+			}
+		}
+		None=> {
+			ast.generics.type_params().map(|p|{
+				let id = &p.ident;
+				syn::parse_quote!{ #id: #trait_path }
+			}).collect()
+		}
+	};
+	generics.make_where_clause().predicates.extend(predicates);
+	generics
+}
+
+
+///how a field with `#[wood(default)]` or `#[wood(default = "path")]` is filled in when its key is absent
+enum FieldDefault {
+	Trait,
+	Path(syn::ExprPath),
+}
+
+///what the `#[wood(...)]` attributes on a field ask for
+#[derive(Default)]
+struct FieldAttrs {
+	///the key a named field is written under
+	key: Option<String>,
+	default: Option<FieldDefault>,
+	skip: bool,
+	flatten: bool,
+	///an expression for the Biwooder to use instead of the field type's own impls
+	with: Option<syn::Expr>,
+	///whether the field is an Option, which is None when its key is absent
+	option: bool,
+}
+
+fn is_option(ty:&syn::Type)-> bool {
+	match *ty {
+		syn::Type::Path(ref p)=> p.qself.is_none() && p.path.segments.last().is_some_and(|s| s.value().ident == "Option"),
+		_=> false,
+	}
+}
+
+impl FieldAttrs {
+	fn of(f:&syn::Field, case:Option<&str>)-> Self {
+		let mut ret = Self{ option:is_option(&f.ty), ..Self::default() };
+		let named = f.ident.is_some();
+		let mut rename = None;
+		for m in wood_attrs(&f.attrs) {
+			match m {
+				NestedMeta::Meta(Meta::NameValue(ref nv)) if nv.ident == "rename" && named => { rename = Some(lit_str(&nv.lit)); }
+				NestedMeta::Meta(Meta::Word(ref w)) if w == "default" => { ret.default = Some(FieldDefault::Trait); }
+				NestedMeta::Meta(Meta::NameValue(ref nv)) if nv.ident == "default" => {
+					let path = lit_str(&nv.lit);
+					ret.default = Some(FieldDefault::Path(syn::parse_str(&path).unwrap_or_else(|e| panic!("couldn't parse the wood default \"{}\": {}", path, e))));
+				}
+				NestedMeta::Meta(Meta::Word(ref w)) if w == "skip" => { ret.skip = true; }
+				NestedMeta::Meta(Meta::Word(ref w)) if w == "flatten" && named => { ret.flatten = true; }
+				NestedMeta::Meta(Meta::NameValue(ref nv)) if nv.ident == "with" => {
+					let with = lit_str(&nv.lit);
+					ret.with = Some(syn::parse_str(&with).unwrap_or_else(|e| panic!("couldn't parse the wood biwooder \"{}\": {}", with, e)));
+				}
+				_=> panic!("unrecognised wood attribute on a{} field: {}", if named {" named"}else{"n unnamed"}, quote!{#m}),
+			}
+		}
+		ret.key = f.ident.as_ref().map(|id| written_name(id, rename, case));
+		ret
+	}
+	///whether the field's key might not be in the wood
+	fn optional(&self)-> bool { self.default.is_some() || self.skip || self.option }
+}
+
+///the fields, with their attributes
+fn fields_of<'a>(fields:&'a syn::Fields, case:Option<&str>)-> Vec<(&'a syn::Field, FieldAttrs)> {
+	fields.iter().map(|f| (f, FieldAttrs::of(f, case))).collect()
+}
+
+fn default_value(attrs:&FieldAttrs)-> PM2TS {
+	match attrs.default {
+		Some(FieldDefault::Path(ref p))=> quote!{ #p() },
+		_=> quote!{ ::std::default::Default::default() },
+	}
+}
+
+///a statement pushing what a field is written as onto `contents`. `value` is an expression for a reference to the field
+fn woodify_field(attrs:&FieldAttrs, value:PM2TS)-> PM2TS {
+	let key = attrs.key.as_ref();
+	if attrs.skip { return quote!{}; }
+	let wooded = match attrs.with {
+		Some(ref w)=> quote!{ _wood::Wooder::woodify(&#w, #value) },
+		None=> quote!{ _wood::Woodable::woodify(#value) },
+	};
+	if attrs.flatten {
+		//the flattened field's own fields go in alongside ours, without its name
+		quote!{ if let _wood::Branchv(mut b) = #wooded { contents.extend(::std::mem::take(&mut b.v).into_iter().skip(1)); } }
+	}else if let (Some(k), true, None) = (key, attrs.option, attrs.with.as_ref()) {
+		//absent means None
+		quote!{ if let ::std::option::Option::Some(x) = #value { contents.push(_wood::woods!(#k, _wood::Woodable::woodify(x))); } }
+	}else if let Some(k) = key {
+		quote!{ contents.push(_wood::woods!(#k, #wooded)); }
+	}else{
+		quote!{ contents.push(#wooded); }
+	}
+}
+
+///an expression for the wood of some fields. `tag` is what to put before them, if anything. A lone untagged field is written as just its own wood.
+fn woodify_contents(tag:Option<&str>, fields:&[(&syn::Field, FieldAttrs)], pushes:Vec<PM2TS>)-> PM2TS {
+	let start = match tag {
+		Some(t)=> quote!{ ::std::vec!(_wood::Wood::from(#t)) },
+		None=> quote!{ ::std::vec::Vec::new() },
+	};
+	let end = if tag.is_none() && fields.iter().all(|(_, a)| a.key.is_none()) && fields.iter().filter(|(_, a)| !a.skip).count() == 1 {
+		quote!{ contents.pop().unwrap() }
+	}else{
+		quote!{ _wood::Wood::from(contents) }
+	};
+	quote!{
+		let mut contents: ::std::vec::Vec<_wood::Wood> = #start;
+		#(#pushes)*
+		#end
+	}
+}
+
+///an expression, reading a field out of the `&Wood` expression `w`, with the field's Biwooder if it has one. Errors are a `step` further down the path, if it has one. If `accumulate`, it's an Option, and the errors go into `errors`.
+fn dewoodify_value(attrs:&FieldAttrs, ty:&syn::Type, w:PM2TS, step:Option<PM2TS>, accumulate:bool)-> PM2TS {
+	let read = match (attrs.with.as_ref(), accumulate) {
+		(Some(with), false)=> quote!{ _wood::Dewooder::dewoodify(&#with, #w) },
+		(None, false)=> quote!{ <#ty as _wood::Dewoodable>::dewoodify(#w) },
+		(Some(with), true)=> quote!{ _wood::Dewooder::dewoodify_all_into(&#with, #w, errors) },
+		(None, true)=> quote!{ <#ty as _wood::Dewoodable>::dewoodify_all_into(#w, errors) },
+	};
+	match (step, accumulate) {
+		(Some(step), false)=> quote!{ #read.map_err(|e| e.within(#step))? },
+		(None, false)=> quote!{ #read? },
+		(Some(step), true)=> quote!{ errors.within(#step, |errors| #read) },
+		(None, true)=> read,
+	}
+}
+
+///a block that gives up on the read with the WoodError expression `e`, noting it in `errors` if `accumulate`
+fn fail(e:PM2TS, accumulate:bool)-> PM2TS {
+	if accumulate {
+		quote!{ { errors.push(#e); return ::std::option::Option::None; } }
+	}else{
+		quote!{ { return ::std::result::Result::Err(::std::boxed::Box::new(#e)); } }
+	}
+}
+
+///the value of the `Result<_, Box<WoodError>>` expression `r`, giving up on the read if it's an error
+fn attempt(r:PM2TS, accumulate:bool)-> PM2TS {
+	if accumulate { quote!{ errors.record(#r)? } }else{ quote!{ #r? } }
+}
+
+///`v`, as a read would produce it. Accumulating reads produce Options.
+fn produced(v:PM2TS, accumulate:bool)-> PM2TS {
+	if accumulate { quote!{ ::std::option::Option::Some(#v) } }else{ v }
+}
+
+///the statement that makes sure there are a possible number of elements. `what` and `subject` make up the error message, with `what` taking the subject, the expected number and the found number
+fn count_check(required:usize, present:usize, len:PM2TS, what:&str, subject:&PM2TS, accumulate:bool)-> PM2TS {
+	let expected = if required == present { format!("{}", present) }else{ format!("between {} and {}", required, present) };
+	let fail = fail(quote!{ _wood::WoodError::new(v, ::std::format!(#what, #subject, #expected, #len)).with_kind(_wood::ErrorKind::ArityMismatch) }, accumulate);
+	quote!{
+		if #len < #required || #len > #present #fail
+	}
+}
+
+///builds `constructor` out of the fields' values, which have been read into locals, `field0`, `field1`... If `named`, it's built with braces.
+fn construct(fields:&[(&syn::Field, FieldAttrs)], constructor:PM2TS, named:bool, accumulate:bool)-> PM2TS {
+	let values = (0..fields.len()).map(|n|{
+		let local = Ident::new(&format!("field{}", n), Span::call_site());
+		if accumulate { quote!{ #local? } }else{ quote!{ #local } }
+	});
+	let construction = if named {
+		let ids = fields.iter().map(|(f, _)| &f.ident);
+		quote!{ #constructor { #(#ids: #values),* } }
+	}else{
+		quote!{ #constructor( #(#values),* ) }
+	};
+	if accumulate { quote!{ ::std::option::Option::Some(#construction) } }else{ quote!{ ::std::result::Result::Ok(#construction) } }
+}
+
+///the statements reading each field into its local, `field0`, `field1`...
+fn read_fields(reads:Vec<PM2TS>)-> PM2TS {
+	let locals = (0..reads.len()).map(|n| Ident::new(&format!("field{}", n), Span::call_site()));
+	quote!{ #(let #locals = #reads;)* }
+}
+
+///statements that read named fields out of `v` and then build `constructor` with them. Entries with other keys are ignored, unless `deny_unknown`. If not `tagged`, there's no tag before the fields. If `accumulate`, they're the body of a `dewoodify_all_into`, otherwise of a `dewoodify`.
+fn dewoodify_named(fields:&[(&syn::Field, FieldAttrs)], constructor:PM2TS, deny_unknown:bool, subject:PM2TS, tagged:bool, accumulate:bool)-> PM2TS {
+	let own_keys:Vec<&String> = fields.iter().filter(|(_, a)| !a.skip && !a.flatten).map(|(_, a)| a.key.as_ref().unwrap()).collect();
+	let any_flattened = fields.iter().any(|(_, a)| a.flatten);
+	let reads = fields.iter().map(|(f, attrs)|{
+		let ty = &f.ty;
+		if attrs.skip {
+			produced(default_value(attrs), accumulate)
+		}else if attrs.flatten {
+			dewoodify_value(attrs, ty, quote!{ &rest }, None, accumulate)
+		}else{
+			//the scanning's own errors already have the key in their path
+			let key = attrs.key.as_ref().unwrap();
+			let found = dewoodify_value(attrs, ty, quote!{ w }, Some(quote!{ _wood::PathSegment::Key(::std::string::String::from(#key)) }), accumulate);
+			//a field that can't be found doesn't stop an accumulating read from looking for the others
+			if attrs.optional() {
+				let default = produced(default_value(attrs), accumulate);
+				let choice = quote!{ ::std::option::Option::Some(w)=> #found, ::std::option::Option::None=> #default };
+				if accumulate {
+					quote!{ errors.record(scanning.find_optional(#key)).and_then(|found| match found { #choice }) }
+				}else{
+					quote!{ match scanning.find_optional(#key)? { #choice } }
+				}
+			}else if accumulate {
+				quote!{ errors.record(scanning.find(#key)).and_then(|w| #found) }
+			}else{
+				quote!{ { let w = scanning.find(#key)?; #found } }
+			}
+		}
+	}).collect();
+	let check = if deny_unknown {
+		if any_flattened { panic!("deny_unknown_fields can't be used along with flatten, there'd be no way to know which keys the flattened fields will take"); }
+		let keys = own_keys.iter();
+		let check = quote!{ scanning.deny_unknown_keys(&[#(#keys),*], #subject) };
+		//unknown keys don't stop an accumulating read from reading the known ones
+		if accumulate { quote!{ errors.record(#check); } }else{ quote!{ #check?; } }
+	}else{
+		quote!{}
+	};
+	let keys = own_keys.iter();
+	let rest = if any_flattened { quote!{ let rest = scanning.without_keys(&[#(#keys),*]); } }else{ quote!{} };
+	let scanning = if tagged { quote!{ _wood::wooder::FieldScanning::new(v) } }else{ attempt(quote!{ _wood::wooder::FieldScanning::untagged(v) }, accumulate) };
+	let reads = read_fields(reads);
+	let construction = construct(fields, constructor, true, accumulate);
+	quote!{
+		#[allow(unused_mut, unused_variables)]
+		let mut scanning = #scanning;
+		#check
+		#rest
+		#reads
+		#construction
+	}
+}
+
+///statements that read fields out of `v` by position and then build `constructor` with them. The fields may be named, if they're a positional struct's. If not `tagged`, there's no tag before the fields, and a lone field is the whole of `v`. If `accumulate`, they're the body of a `dewoodify_all_into`, otherwise of a `dewoodify`.
+fn dewoodify_unnamed(fields:&[(&syn::Field, FieldAttrs)], constructor:PM2TS, what:&str, subject:PM2TS, tagged:bool, accumulate:bool)-> PM2TS {
+	let mut present = 0;
+	let mut required = 0;
+	let reads:Vec<PM2TS> = fields.iter().map(|(f, attrs)|{
+		if attrs.skip { return produced(default_value(attrs), accumulate); }
+		let i = present;
+		present += 1;
+		let step = Some(quote!{ _wood::PathSegment::Index(#i) });
+		if attrs.optional() {
+			let found = dewoodify_value(attrs, &f.ty, quote!{ w }, step, accumulate);
+			let default = produced(default_value(attrs), accumulate);
+			quote!{ match li.get(#i) { ::std::option::Option::Some(w)=> #found, ::std::option::Option::None=> #default } }
+		}else{
+			required = present;
+			dewoodify_value(attrs, &f.ty, quote!{ &li[#i] }, step, accumulate)
+		}
+	}).collect();
+	let check = count_check(required, present, quote!{ li.len() }, what, &subject, accumulate);
+	let li = if tagged {
+		quote!{ v.tail().as_slice() }
+	}else if present == 1 {
+		quote!{ ::std::slice::from_ref(v) }
+	}else{
+		let fail = fail(quote!{ _wood::WoodError::new(v, ::std::format!("{} expected a list of {} elements, but this is a leaf", #subject, #present)).with_kind(_wood::ErrorKind::UnexpectedLeaf) }, accumulate);
+		quote!{ match v.get_branch() {
+			::std::option::Option::Some(b)=> b,
+			::std::option::Option::None=> #fail
+		} }
+	};
+	let reads = read_fields(reads);
+	let named = fields.first().is_some_and(|(f, _)| f.ident.is_some());
+	let construction = construct(fields, constructor, named, accumulate);
+	quote!{
+		let li = #li;
+		#check
+		#reads
+		#construction
+	}
+}
 
 
 // was going to make an impl that only does part of the translation, to let the user prepend tags. But I decided I'd prefer to just write translations by hand. Opening this up to the point where it's helping to automate, but also the user has full control, seems daunting. I might hate magic.
@@ -17,7 +501,7 @@ use syn::{Ident, Data::{Struct, Enum, Union}, Fields::{Named, Unnamed, Unit}};
 // 	generated_Woodable(input, quote!{GeneratedWoodable});
 // }
 
-#[proc_macro_derive(Woodable)]
+#[proc_macro_derive(Woodable, attributes(wood))]
 pub fn woodable_derive(input: TokenStream) -> TokenStream {
 	generated_woodable(input, quote!{Woodable})
 }
@@ -25,41 +509,29 @@ fn generated_woodable(input: TokenStream, being_impld: PM2TS) -> TokenStream {
 	let ast: syn::DeriveInput = syn::parse(input).unwrap();
 
 	let name = &ast.ident;
+	let attrs = ContainerAttrs::of(&ast);
+	let generics = generics_with_bounds(&ast, &attrs, quote!{ _wood::#being_impld });
+	let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
 	
 	let ret: TokenStream = match ast.data {
-		Struct(ref s)=> {
-			let woodify_branch_contents:Vec<PM2TS> = match s.fields {
-				Named(ref n)=> {
-					n.named.iter().map(|m:&syn::Field|{
-						let mid = &m.ident.as_ref().unwrap();
-						quote!{
-							wood::woods!(
-								stringify!(#mid),
-								self.#mid.woodify(),
-							)
-						}
-					}).collect()
-				},
-				Unnamed(ref n)=> {
-					n.unnamed.iter().enumerate().map(|(i, _m)|{
+		Struct(_)=> {
+			let fields = attrs.struct_fields(&ast);
+			let pushes:Vec<PM2TS> = fields.iter().enumerate().map(|(i, (f, attrs))|{
+				match f.ident {
+					Some(ref id)=> woodify_field(attrs, quote!{ &self.#id }),
+					None=> {
 						let id = syn::Index::from(i);
-						quote!{ self.#id.woodify() }
-					}).collect()
-				},
-				Unit=> {
-					vec!(quote!{})
+						woodify_field(attrs, quote!{ &self.#id })
+					}
 				}
-			};
-			
-			let tit = woodify_branch_contents.into_iter();
+			}).collect();
+			let tag = name.to_string();
+			let body = woodify_contents(if attrs.untagged { None }else{ Some(&tag) }, &fields, pushes);
 			
-			(quote! {
-				impl wood::#being_impld for #name {
-					fn woodify(&self)-> wood::Wood {
-						wood::woods!(
-							stringify!(#name),
-							#(#tit),*
-						)
+			attrs.in_wood_scope(quote! {
+				impl #impl_generics _wood::#being_impld for #name #ty_generics #where_clause {
+					fn woodify(&self)-> _wood::Wood {
+						#body
 					}
 				}
 			}).into()
@@ -69,63 +541,50 @@ fn generated_woodable(input: TokenStream, being_impld: PM2TS) -> TokenStream {
 		Enum(ref e)=> {
 			let variant_cases:Vec<PM2TS> = e.variants.iter().map(|m:&syn::Variant|{
 				let variant_name = &m.ident;
-				
-				//left is just the name, right is the 
-				let has_fields = |bindings: PM2TS, woodifications:PM2TS|{
-					quote!{
-						#name::#variant_name #bindings => {
-							wood::woods!(
-								stringify!(#variant_name),
-								#woodifications
-							)
-						}
-					}
-				};
-				
+				let vattrs = VariantAttrs::of(m, &attrs);
+				let key = &vattrs.name;
+				//untagged variants are written without their names
+				let tag = if attrs.untagged { None }else{ Some(key.as_str()) };
+				let fields = fields_of(&m.fields, None);
 				
 				match m.fields {
-					Named(ref fs)=>{
-						let bindvec:Vec<PM2TS> = fs.named.iter().map(|m:&syn::Field|{
-							let id = &m.ident;
-							quote!{ ref #id }
-						}).collect();
-						let woodvec:Vec<PM2TS> = fs.named.iter().map(|m:&syn::Field|{
-							let fid = m.ident.as_ref().unwrap();
-							quote!{
-								wood::woods!(
-									stringify!(#fid),
-									#fid.woodify(),
-								)
-							}
+					Named(_)=>{
+						//the fields are bound to names of our own, as in the unnamed case, so that they can't be shadowed by the generated code's locals
+						let names:Vec<Ident> = (0..fields.len()).map(|i|{
+							Ident::new(format!("v{}", i).as_str(), Span::call_site())
 						}).collect();
-						
-						has_fields(
-							quote!{ { #(#bindvec),* } },
-							quote!{ #(#woodvec),* }
-						)
+						let bindvec = names.iter().zip(fields.iter()).map(|(s, (f, a))|{
+							let id = &f.ident;
+							if a.skip { quote!{ #id: _ } }else{ quote!{ #id: ref #s } }
+						});
+						let woodvec = names.iter().zip(fields.iter()).map(|(s, (_, a))| woodify_field(a, quote!{ #s })).collect();
+						let body = woodify_contents(tag, &fields, woodvec);
+						quote!{
+							#name::#variant_name { #(#bindvec),* } => { #body }
+						}
 					},
-					Unnamed(ref fs)=>{
-						let names:Vec<Ident> = (0..fs.unnamed.len()).map(|i|{
+					Unnamed(_)=>{
+						let names:Vec<Ident> = (0..fields.len()).map(|i|{
 							Ident::new(format!("v{}", i).as_str(), Span::call_site())
 						}).collect();
-						let nam = names.iter().map(|id| quote!{ ref #id });
-						let woodvec = names.iter().map(|s| quote!{ #s.woodify() });
-						has_fields(
-							quote!{ ( #(#nam),* ) },
-							quote!{ #(#woodvec),* },
-						)
+						let nam = names.iter().zip(fields.iter()).map(|(id, (_, a))| if a.skip { quote!{ _ } }else{ quote!{ ref #id } });
+						let woodvec = names.iter().zip(fields.iter()).map(|(s, (_, a))| woodify_field(a, quote!{ #s })).collect();
+						let body = woodify_contents(tag, &fields, woodvec);
+						quote!{
+							#name::#variant_name ( #(#nam),* ) => { #body }
+						}
 					},
 					Unit =>{
 						quote! {
-							#name::#variant_name => stringify!(#variant_name).into()
+							#name::#variant_name => _wood::Wood::from(#key)
 						}
 					}
 				}
 			}).collect();
 			
-			(quote! {
-				impl wood::#being_impld for #name {
-					fn woodify(&self)-> wood::Wood {
+			attrs.in_wood_scope(quote! {
+				impl #impl_generics _wood::#being_impld for #name #ty_generics #where_clause {
+					fn woodify(&self)-> _wood::Wood {
 					 	match *self {
 							#(#variant_cases),*
 						}
@@ -154,148 +613,148 @@ pub fn dewoodable_derive(input: TokenStream) -> TokenStream {
 	let ast: syn::DeriveInput = syn::parse(input).unwrap();
 
 	let name = &ast.ident;
+	let attrs = ContainerAttrs::of(&ast);
+	let generics = generics_with_bounds(&ast, &attrs, quote!{ _wood::Dewoodable });
+	let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
 	
 	let ret = TokenStream::from( match ast.data {
 		Struct(ref s)=> {
 			
-			let with_body = |method_body|-> PM2TS {
+			//`all_body` is for the accumulating read, if it needs one of its own
+			let with_bodies = |method_body, all_body:Option<PM2TS>|-> PM2TS {
+				let all_method = all_body.map(|all_body| quote!{
+					fn dewoodify_all_into(v:&_wood::Wood, errors:&mut _wood::WoodErrors)-> ::std::option::Option<Self> {
+						#all_body
+					}
+				});
 				//it seeks over the contents of the wood branch in such way where if the items are in order it will find each one immediately
-				quote! {
-					impl wood::Dewoodable for #name {
-						fn dewoodify(v:&wood::Wood)-> Result<Self, Box<wood::WoodError>> {
+				attrs.in_wood_scope(quote! {
+					impl #impl_generics _wood::Dewoodable for #name #ty_generics #where_clause {
+						fn dewoodify(v:&_wood::Wood)-> ::std::result::Result<Self, ::std::boxed::Box<_wood::WoodError>> {
 							#method_body
 						}
+						#all_method
 					}
-				}
+				})
 			};
 			
+			let fields = attrs.struct_fields(&ast);
+			let tagged = !attrs.untagged;
 			match s.fields {
-				Named(ref n)=> {
-					
-					if n.named.len() == 0 {
-						with_body(quote! { Self{} })
-					}else{
-						
-						let mut var_parsing:Vec<PM2TS> = Vec::new();
-						for m in n.named.iter() {
-							// if m.attrs.iter().any(|a| a.path.segments.zip(["wood", "dont"].iter()).all(|i, s| i.ident == s)) {continue;}
-							let var_ident = &m.ident.as_ref().unwrap();
-							let var_type = &m.ty;
-							var_parsing.push(quote!{
-								#var_ident: {type T = #var_type; T::dewoodify(scanning.find(stringify!(#var_ident))?)?}
-							});
-						}
-						
-						let number_of_fields = var_parsing.len();
-						
-						with_body(quote!{
-							let mut scanning = wood::wooder::FieldScanning::new(v);
-							if scanning.li.len() != #number_of_fields {
-								return Err(Box::new(wood::WoodError::new(v, format!("{} expected the wood to have {} elements, but it has {}", stringify!(#name), #number_of_fields, scanning.li.len()))));
-							}
-							
-							Ok(Self{
-								#(#var_parsing),*
-							})
-						})
-					}
+				Named(_) if !attrs.positional => {
+					let body = |accumulate| dewoodify_named(&fields, quote!{ Self }, attrs.deny_unknown_fields, quote!{ ::std::stringify!(#name) }, tagged, accumulate);
+					with_bodies(body(false), Some(body(true)))
 				},
-				
-				Unnamed(ref n)=> {
-					
-					let number_of_fields = n.unnamed.len();
-					
-					let each_field = n.unnamed.iter().enumerate().map(|(i, m)|{
-						let ty = &m.ty;
-						quote!{#ty::dewoodify(&li[#i])?}
-					});
-					
-					with_body(quote!{
-						let li = v.tail().as_slice();
-						if li.len() == #number_of_fields {
-							Ok(Self(#(#each_field),*))
-						}else{
-							Err(Box::new(wood::WoodError::new(v, format!("{} expected the wood to have {} fields, but it has {}", stringify!(#name), #number_of_fields, li.len()))))
-						}
-					})
+				Named(_) | Unnamed(_)=> {
+					let body = |accumulate| dewoodify_unnamed(&fields, quote!{ Self }, "{} expected the wood to have {} fields, but it has {}", quote!{ ::std::stringify!(#name) }, tagged, accumulate);
+					with_bodies(body(false), Some(body(true)))
 				},
 				Unit=> {
-					with_body(quote!{
-						Ok(#name)
-					})
+					with_bodies(quote!{
+						::std::result::Result::Ok(#name)
+					}, None)
 				}
 			}
 		},
 		
 		
 		Enum(ref e)=> {
-			
-			let variant_cases:Vec<PM2TS> = e.variants.iter().map(|m:&syn::Variant|{
+			let tagged = !attrs.untagged;
+			let mut other = None;
+			//the arms of the accumulating read's match, for tagged enums
+			let mut all_cases:Vec<PM2TS> = vec!();
+			let variant_cases:Vec<PM2TS> = e.variants.iter().filter_map(|m:&syn::Variant|{
 				let variant_name = &m.ident;
+				let vattrs = VariantAttrs::of(m, &attrs);
+				if vattrs.other {
+					if other.is_some() { panic!("{} has more than one other variant", name); }
+					other = Some(variant_name);
+					//when untagged, the other variant is only ever the fallback
+					if !tagged { return None; }
+				}
+				let fields = fields_of(&m.fields, None);
 				
-				let for_fields:PM2TS = match m.fields {
-					Named(ref n)=> {
-						let each_feild:Vec<PM2TS> = n.named.iter().map(|f:&syn::Field|{
-							let id = &f.ident;
-							let ty = &f.ty;
-							quote!{ #id: #ty::dewoodify(scanning.find(stringify!(#id))?)? }
-						}).collect();
-						
-						let number_of_fields = each_feild.len();
-						
-						quote!{
-							let mut scanning = wooder::FieldScanning::new(v);
-							if scanning.li.len() != #number_of_fields {
-								return Err(Box::new(wood::WoodError::new(v, format!("variant {} expected {} elements, found {}", stringify!(#variant_name), #number_of_fields, scanning.li.len()))));
-							}
-							Ok(#name::#variant_name {
-								#(#each_feild),*
-							})
-						}
+				let variant_body = |accumulate| match m.fields {
+					Named(_)=> {
+						dewoodify_named(&fields, quote!{ #name::#variant_name }, attrs.deny_unknown_fields, quote!{ ::std::stringify!(#variant_name) }, tagged, accumulate)
 					},
-					Unnamed(ref n)=> {
-						
-						let each_feild = n.unnamed.iter().enumerate().map(|(i,m)|{
-							let ty = &m.ty;
-							quote!{ #ty::dewoodify(&li[#i])? }
-						});
-						
-						let number_of_fields = each_feild.len();
-						
-						quote!{
-							let li = v.tail().as_slice();
-							
-							if li.len() != #number_of_fields {
-								return Err(Box::new(wood::WoodError::new(v, format!("variant {} expected {} elements, found {}", stringify!(#variant_name), #number_of_fields, li.len()))));
-							}
-							
-							Ok(#name::#variant_name(
-								#(#each_feild),*
-							))
-						}
+					Unnamed(_)=> {
+						dewoodify_unnamed(&fields, quote!{ #name::#variant_name }, "variant {} expected {} elements, found {}", quote!{ ::std::stringify!(#variant_name) }, tagged, accumulate)
 					},
 					Unit=> {
-						quote!{ Ok(#name::#variant_name) }
+						if accumulate { quote!{ ::std::option::Option::Some(#name::#variant_name) } }else{ quote!{ ::std::result::Result::Ok(#name::#variant_name) } }
 					}
 				};
+				let for_fields = variant_body(false);
 				
-				quote!{
-					stringify!(#variant_name)=> {
-						#for_fields
+				let names = vattrs.pattern();
+				Some(if tagged {
+					let for_fields_all = variant_body(true);
+					all_cases.push(quote!{
+						#names => {
+							#for_fields_all
+						}
+					});
+					quote!{
+						#names => {
+							#for_fields
+						}
 					}
-				}
+				}else if let Unit = m.fields {
+					quote!{
+						if v.is_leaf() && ::std::matches!(v.initial_str(), #names) { return ::std::result::Result::Ok(#name::#variant_name); }
+					}
+				}else{
+					quote!{
+						if let ::std::result::Result::Ok(r) = (|| -> ::std::result::Result<Self, ::std::boxed::Box<_wood::WoodError>> { #for_fields })() { return ::std::result::Result::Ok(r); }
+					}
+				})
 			}).collect();
 			
-			quote!{
-				impl Dewoodable for #name {
-					fn dewoodify(v:&wood::Wood)-> Result<Self, Box<wood::WoodError>> {
+			//an untagged enum's read has to try each variant in turn, so an accumulating read couldn't say which variant's errors to report, it just reports the first error, as the default does
+			let mut all_method = None;
+			let body = if tagged {
+				let no_variant = quote!{ _wood::WoodError::new(v, ::std::format!("expected a {}, but no variant of {} is called {}", ::std::stringify!(#name), ::std::stringify!(#name), erc)).with_kind(_wood::ErrorKind::UnknownVariant) };
+				let (fallback, all_fallback) = match other {
+					Some(o)=> (quote!{ _ => ::std::result::Result::Ok(#name::#o), }, quote!{ _ => ::std::option::Option::Some(#name::#o), }),
+					None=> (
+						quote!{ erc => ::std::result::Result::Err(::std::boxed::Box::new(#no_variant)), },
+						quote!{ erc => { errors.push(#no_variant); ::std::option::Option::None } },
+					),
+				};
+				all_method = Some(quote!{
+					fn dewoodify_all_into(v:&_wood::Wood, errors:&mut _wood::WoodErrors)-> ::std::option::Option<Self> {
 						match v.initial_str() {
-							#(#variant_cases,)*
-							erc => Err(Box::new(wood::WoodError::new(v, format!("expected a {}, but no variant of {} is called {}", stringify!(#name), stringify!(#name), erc)))),
+							#(#all_cases,)*
+							#all_fallback
 						}
 					}
+				});
+				quote!{
+					match v.initial_str() {
+						#(#variant_cases,)*
+						#fallback
+					}
 				}
-			}
+			}else{
+				let fallback = match other {
+					Some(o)=> quote!{ ::std::result::Result::Ok(#name::#o) },
+					None=> quote!{ ::std::result::Result::Err(::std::boxed::Box::new(_wood::WoodError::new(v, ::std::format!("the wood didn't match any of {}'s variants", ::std::stringify!(#name))).with_kind(_wood::ErrorKind::UnknownVariant))) },
+				};
+				quote!{
+					#(#variant_cases)*
+					#fallback
+				}
+			};
+			
+			attrs.in_wood_scope(quote!{
+				impl #impl_generics _wood::Dewoodable for #name #ty_generics #where_clause {
+					fn dewoodify(v:&_wood::Wood)-> ::std::result::Result<Self, ::std::boxed::Box<_wood::WoodError>> {
+						#body
+					}
+					#all_method
+				}
+			})
 		},
 		
 		
@@ -0,0 +1,8 @@
+//the derives are checked by compiling small crates that use them. To update the expected errors after changing a message, run with TRYBUILD=overwrite
+
+#[test]
+fn derives_compile(){
+	let t = trybuild::TestCases::new();
+	t.pass("tests/ui/pass/*.rs");
+	t.compile_fail("tests/ui/fail/*.rs");
+}
@@ -0,0 +1,10 @@
+use wood_derive::Dewoodable;
+
+#[derive(Dewoodable)]
+enum E {
+	A,
+	#[wood(other)]
+	Rest(String),
+}
+
+fn main(){}
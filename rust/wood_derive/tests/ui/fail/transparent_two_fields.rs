@@ -0,0 +1,7 @@
+use wood_derive::Woodable;
+
+#[derive(Woodable)]
+#[wood(transparent)]
+struct Two(u32, u32);
+
+fn main(){}
@@ -0,0 +1,9 @@
+use wood_derive::Dewoodable;
+
+#[derive(Dewoodable)]
+union U {
+	a: u32,
+	b: f32,
+}
+
+fn main(){}
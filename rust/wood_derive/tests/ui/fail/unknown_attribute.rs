@@ -0,0 +1,9 @@
+use wood_derive::Woodable;
+
+#[derive(Woodable)]
+#[wood(tagged)]
+struct A {
+	a: u32,
+}
+
+fn main(){}
@@ -0,0 +1,26 @@
+//for crates that use wood through a re-export, rather than depending on it directly
+mod reexported {
+	pub use wood as wood_again;
+}
+
+use reexported::wood_again::{Woodable, Dewoodable};
+use wood_derive::{Woodable, Dewoodable};
+
+#[derive(Woodable, Dewoodable, PartialEq, Debug)]
+#[wood(crate = "crate::reexported::wood_again")]
+struct Point {
+	x: i32,
+	y: i32,
+}
+
+#[derive(Woodable, Dewoodable, PartialEq, Debug)]
+#[wood(crate = "crate::reexported::wood_again")]
+enum Shape {
+	Dot(Point),
+	Nothing,
+}
+
+fn main(){
+	let s = Shape::Dot(Point{ x:1, y:2 });
+	assert_eq!(s, Shape::dewoodify(&s.woodify()).unwrap());
+}
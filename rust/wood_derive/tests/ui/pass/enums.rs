@@ -0,0 +1,31 @@
+use wood::{Woodable, Dewoodable};
+use wood_derive::{Woodable, Dewoodable};
+
+#[derive(Woodable, Dewoodable, PartialEq, Debug)]
+enum Every {
+	Unit,
+	Newtype(u32),
+	Tuple(u32, String),
+	Named{ a: u32, b: Vec<String> },
+	EmptyTuple(),
+	EmptyNamed{},
+}
+
+#[derive(Woodable, Dewoodable, PartialEq, Debug)]
+#[wood(untagged)]
+enum Untagged {
+	Unit,
+	//tried in order, and a u32 would be read from the first element of a tuple, so the tuple goes first
+	Tuple(u32, String),
+	Newtype(u32),
+	Named{ a: u32, b: Vec<String> },
+}
+
+fn main(){
+	for e in vec!(Every::Unit, Every::Newtype(1), Every::Tuple(1, "a".into()), Every::Named{ a:1, b:vec!("b".into()) }, Every::EmptyTuple(), Every::EmptyNamed{}) {
+		assert_eq!(e, Every::dewoodify(&e.woodify()).unwrap());
+	}
+	for e in vec!(Untagged::Unit, Untagged::Newtype(1), Untagged::Tuple(1, "a".into()), Untagged::Named{ a:1, b:vec!("b".into()) }) {
+		assert_eq!(e, Untagged::dewoodify(&e.woodify()).unwrap());
+	}
+}
@@ -0,0 +1,68 @@
+//the generated code must not depend on what's imported, nor be confused by things that share names with std's or wood's
+#![allow(dead_code)]
+
+struct Result;
+struct Box;
+struct Vec;
+struct Option;
+struct Ok;
+struct Err;
+struct Some;
+struct None;
+struct String;
+struct Wood;
+struct WoodError;
+mod wooder {}
+macro_rules! format { ($($t:tt)*)=> { compile_error!("used the local format") } }
+macro_rules! vec { ($($t:tt)*)=> { compile_error!("used the local vec") } }
+macro_rules! stringify { ($($t:tt)*)=> { compile_error!("used the local stringify") } }
+macro_rules! matches { ($($t:tt)*)=> { compile_error!("used the local matches") } }
+
+#[derive(wood_derive::Woodable, wood_derive::Dewoodable)]
+struct Named {
+	a: u32,
+	#[wood(default)]
+	b: std::vec::Vec<u32>,
+	c: std::option::Option<u32>,
+	#[wood(flatten)]
+	d: Tuple,
+}
+
+#[derive(wood_derive::Woodable, wood_derive::Dewoodable)]
+struct Tuple(u32, #[wood(default)] u32);
+
+#[derive(wood_derive::Woodable, wood_derive::Dewoodable)]
+enum Kinds {
+	Unit,
+	Tuple(u32),
+	Named{ a: u32, #[wood(skip)] b: u32 },
+	#[wood(other)]
+	Other,
+}
+
+//fields named like the generated code's own locals
+#[derive(wood_derive::Woodable, wood_derive::Dewoodable)]
+enum Locals {
+	Named{ contents: u32, x: std::option::Option<u32>, b: u32, v: u32, w: u32, li: u32, errors: u32, scanning: u32, rest: u32, field0: u32, v0: u32 },
+	Tuple(u32, u32),
+}
+
+#[derive(wood_derive::Woodable, wood_derive::Dewoodable)]
+struct LocalNames { contents: u32, x: std::option::Option<u32>, b: u32, v: u32, w: u32, li: u32, errors: u32, scanning: u32, rest: u32, field0: u32 }
+
+#[derive(wood_derive::Woodable, wood_derive::Dewoodable)]
+#[wood(untagged)]
+enum Untagged {
+	Unit,
+	Tuple(u32, u32),
+	Named{ a: u32 },
+}
+
+#[derive(wood_derive::Woodable, wood_derive::Dewoodable)]
+#[wood(positional)]
+struct Positional {
+	a: u32,
+	b: u32,
+}
+
+fn main(){}
@@ -0,0 +1,32 @@
+use wood::{Woodable, Dewoodable};
+use wood_derive::{Woodable, Dewoodable};
+
+#[derive(Woodable, Dewoodable, PartialEq, Debug)]
+struct Pair<A, B> {
+	a: A,
+	b: B,
+}
+
+#[derive(Woodable, Dewoodable, PartialEq, Debug)]
+enum Tree<T> {
+	Leaf(T),
+	Node(Vec<Tree<T>>),
+}
+
+#[derive(Woodable, Dewoodable, PartialEq, Debug)]
+struct Nested<T> where T: Clone {
+	pairs: Vec<Pair<T, Vec<T>>>,
+	named: Vec<Pair<String, Option<T>>>,
+	tree: Tree<Pair<T, T>>,
+	maybe: Option<Vec<T>>,
+}
+
+fn main(){
+	let n = Nested{
+		pairs: vec!(Pair{ a:1u32, b:vec!(2, 3) }),
+		named: vec!(Pair{ a:"x".into(), b:Some(3) }, Pair{ a:"y".into(), b:None }),
+		tree: Tree::Node(vec!(Tree::Leaf(Pair{ a:4, b:5 }), Tree::Node(vec!()))),
+		maybe: Some(vec!(6)),
+	};
+	assert_eq!(n, Nested::dewoodify(&n.woodify()).unwrap());
+}
@@ -0,0 +1,23 @@
+use wood::{Woodable, Dewoodable};
+use wood_derive::{Woodable, Dewoodable};
+
+#[derive(Woodable, Dewoodable, PartialEq, Debug)]
+struct Named {
+	a: u32,
+	b: String,
+	c: Vec<u32>,
+}
+
+#[derive(Woodable, Dewoodable, PartialEq, Debug)]
+struct Tuple(u32, String);
+
+#[derive(Woodable, Dewoodable, PartialEq, Debug)]
+struct Unit;
+
+fn main(){
+	let n = Named{ a:1, b:"b".into(), c:vec!(2, 3) };
+	assert_eq!(n, Named::dewoodify(&n.woodify()).unwrap());
+	let t = Tuple(1, "b".into());
+	assert_eq!(t, Tuple::dewoodify(&t.woodify()).unwrap());
+	assert_eq!(Unit, Unit::dewoodify(&Unit.woodify()).unwrap());
+}